@@ -0,0 +1,26 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE ds_item_annos
+    DROP CONSTRAINT ds_item_annos_item_id_fkey,
+    ADD CONSTRAINT ds_item_annos_item_id_fkey
+        FOREIGN KEY (item_id) REFERENCES ds_items(id);
+
+ALTER TABLE datasets_shards_rel
+    DROP CONSTRAINT datasets_shards_rel_ds_id_fkey,
+    DROP CONSTRAINT datasets_shards_rel_shard_id_fkey,
+    ADD CONSTRAINT datasets_shards_rel_ds_id_fkey
+        FOREIGN KEY (ds_id) REFERENCES datasets(id),
+    ADD CONSTRAINT datasets_shards_rel_shard_id_fkey
+        FOREIGN KEY (shard_id) REFERENCES ds_shards(id);
+
+ALTER TABLE datasets_items_rel
+    DROP CONSTRAINT datasets_items_rel_ds_id_fkey,
+    DROP CONSTRAINT datasets_items_rel_item_id_fkey,
+    ADD CONSTRAINT datasets_items_rel_ds_id_fkey
+        FOREIGN KEY (ds_id) REFERENCES datasets(id),
+    ADD CONSTRAINT datasets_items_rel_item_id_fkey
+        FOREIGN KEY (item_id) REFERENCES ds_items(id);
+
+DROP TABLE ds_version_item_annos;
+DROP TABLE ds_version_shards;
+DROP TABLE ds_version_items;
+DROP TABLE ds_versions;
@@ -0,0 +1,76 @@
+-- Versions keep a copy of their items, shards and annotations rather than
+-- referencing them, so that later updates don't rewrite a version and deletes
+-- aren't held back by one. They go with their dataset.
+CREATE TABLE ds_versions (
+    id SERIAL PRIMARY KEY,
+    ds_id INTEGER NOT NULL REFERENCES datasets(id) ON DELETE CASCADE,
+    tag VARCHAR(255) NOT NULL,
+    num_items INTEGER NOT NULL DEFAULT 0,
+    num_shards INTEGER NOT NULL DEFAULT 0,
+    num_annos INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE(ds_id, tag)
+);
+
+CREATE TABLE ds_version_items (
+    version_id INTEGER NOT NULL REFERENCES ds_versions(id) ON DELETE CASCADE,
+    item_id INTEGER NOT NULL,
+    typ VARCHAR(255) NOT NULL,
+    uri VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_by INTEGER,
+    updated_by INTEGER,
+    PRIMARY KEY(version_id, item_id)
+);
+
+CREATE TABLE ds_version_shards (
+    version_id INTEGER NOT NULL REFERENCES ds_versions(id) ON DELETE CASCADE,
+    shard_id INTEGER NOT NULL,
+    uri VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_by INTEGER,
+    updated_by INTEGER,
+    PRIMARY KEY(version_id, shard_id)
+);
+
+CREATE TABLE ds_version_item_annos (
+    version_id INTEGER NOT NULL REFERENCES ds_versions(id) ON DELETE CASCADE,
+    anno_id INTEGER NOT NULL,
+    item_id INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    typ VARCHAR(255) NOT NULL,
+    uri VARCHAR(255),
+    number FLOAT,
+    text TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY(version_id, anno_id)
+);
+
+CREATE INDEX ds_version_item_annos_item_id_idx ON ds_version_item_annos(version_id, item_id);
+
+-- With versions no longer pointing at them, memberships and annotations go
+-- with the dataset, item or shard they belong to, which is what the delete
+-- handlers rely on.
+ALTER TABLE datasets_items_rel
+    DROP CONSTRAINT datasets_items_rel_ds_id_fkey,
+    DROP CONSTRAINT datasets_items_rel_item_id_fkey,
+    ADD CONSTRAINT datasets_items_rel_ds_id_fkey
+        FOREIGN KEY (ds_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    ADD CONSTRAINT datasets_items_rel_item_id_fkey
+        FOREIGN KEY (item_id) REFERENCES ds_items(id) ON DELETE CASCADE;
+
+ALTER TABLE datasets_shards_rel
+    DROP CONSTRAINT datasets_shards_rel_ds_id_fkey,
+    DROP CONSTRAINT datasets_shards_rel_shard_id_fkey,
+    ADD CONSTRAINT datasets_shards_rel_ds_id_fkey
+        FOREIGN KEY (ds_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    ADD CONSTRAINT datasets_shards_rel_shard_id_fkey
+        FOREIGN KEY (shard_id) REFERENCES ds_shards(id) ON DELETE CASCADE;
+
+ALTER TABLE ds_item_annos
+    DROP CONSTRAINT ds_item_annos_item_id_fkey,
+    ADD CONSTRAINT ds_item_annos_item_id_fkey
+        FOREIGN KEY (item_id) REFERENCES ds_items(id) ON DELETE CASCADE;
@@ -0,0 +1,21 @@
+-- A grant gives one user or one group a role on a dataset. Grants go away
+-- with the dataset, user or group they refer to. Datasets created before
+-- this table have no grant, only holders of `datasets.admin` reach them
+-- until an owner is granted.
+CREATE TABLE ds_acls (
+    id SERIAL PRIMARY KEY,
+    ds_id INTEGER NOT NULL REFERENCES datasets(id) ON DELETE CASCADE,
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    group_id INTEGER REFERENCES groups(id) ON DELETE CASCADE,
+    role VARCHAR(16) NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK ((user_id IS NULL) <> (group_id IS NULL)),
+    UNIQUE(ds_id, user_id),
+    UNIQUE(ds_id, group_id)
+);
+
+SELECT diesel_manage_updated_at('ds_acls');
+
+CREATE INDEX ds_acls_user_id_idx ON ds_acls(user_id);
+CREATE INDEX ds_acls_group_id_idx ON ds_acls(group_id);
@@ -0,0 +1,20 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE ds_version_item_annos
+    DROP COLUMN created_by,
+    DROP COLUMN updated_by;
+
+ALTER TABLE ds_item_annos
+    DROP COLUMN created_by,
+    DROP COLUMN updated_by;
+
+ALTER TABLE ds_shards
+    DROP COLUMN created_by,
+    DROP COLUMN updated_by;
+
+ALTER TABLE ds_items
+    DROP COLUMN created_by,
+    DROP COLUMN updated_by;
+
+ALTER TABLE datasets
+    DROP COLUMN created_by,
+    DROP COLUMN updated_by;
@@ -0,0 +1,28 @@
+-- The user who created a row and the one who last changed it. Both are left
+-- empty for rows written before they were tracked, and once their user is
+-- deleted.
+ALTER TABLE datasets
+    ADD COLUMN created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE ds_items
+    ADD COLUMN created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE ds_shards
+    ADD COLUMN created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+ALTER TABLE ds_item_annos
+    ADD COLUMN created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+-- Frozen annotations keep who wrote them, without holding on to the user.
+ALTER TABLE ds_version_item_annos
+    ADD COLUMN created_by INTEGER,
+    ADD COLUMN updated_by INTEGER;
+
+CREATE INDEX datasets_created_by_idx ON datasets(created_by);
+CREATE INDEX ds_items_created_by_idx ON ds_items(created_by);
+CREATE INDEX ds_shards_created_by_idx ON ds_shards(created_by);
+CREATE INDEX ds_item_annos_created_by_idx ON ds_item_annos(created_by);
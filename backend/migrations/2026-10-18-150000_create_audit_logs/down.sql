@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE audit_logs;
+DROP FUNCTION audit_logs_append_only();
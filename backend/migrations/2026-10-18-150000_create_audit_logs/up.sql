@@ -0,0 +1,27 @@
+-- One row per mutating request. `user_id` is deliberately not a foreign key:
+-- the trail has to outlive the users it mentions, and rows are never updated
+-- or deleted once written.
+CREATE TABLE audit_logs (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER,
+    method VARCHAR(16) NOT NULL,
+    route VARCHAR(255) NOT NULL,
+    target_id INTEGER,
+    body_digest VARCHAR(64),
+    status INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE FUNCTION audit_logs_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_logs is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_logs_append_only
+    BEFORE UPDATE OR DELETE OR TRUNCATE ON audit_logs
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_logs_append_only();
+
+CREATE INDEX audit_logs_user_id_idx ON audit_logs(user_id);
+CREATE INDEX audit_logs_route_target_id_idx ON audit_logs(route, target_id);
+CREATE INDEX audit_logs_created_at_idx ON audit_logs(created_at);
@@ -0,0 +1,17 @@
+-- A session starts at login and lives as long as its refresh token keeps
+-- being rotated. Only SHA-256 digests of refresh tokens are stored. The
+-- digest of the token rotated out last is kept to detect its reuse.
+CREATE TABLE sessions (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    previous_token_hash VARCHAR(64) UNIQUE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+SELECT diesel_manage_updated_at('sessions');
+
+CREATE INDEX sessions_user_id_idx ON sessions(user_id);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE users_groups_rel DROP COLUMN source;
+DROP TABLE user_identities;
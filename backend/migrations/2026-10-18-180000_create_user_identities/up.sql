@@ -0,0 +1,19 @@
+-- Links a user to their account at an external identity provider, e.g. the
+-- `sub` of an OpenID Connect issuer.
+CREATE TABLE user_identities (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider VARCHAR(255) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE(provider, subject)
+);
+
+SELECT diesel_manage_updated_at('user_identities');
+
+CREATE INDEX user_identities_user_id_idx ON user_identities(user_id);
+
+-- Which identity provider a membership was synced from, NULL for the ones
+-- granted through the API. Syncing only ever touches its own memberships.
+ALTER TABLE users_groups_rel ADD COLUMN source VARCHAR(16);
@@ -0,0 +1,24 @@
+-- Address notifications are sent to, given when registering.
+ALTER TABLE users ADD COLUMN email VARCHAR(255) UNIQUE;
+
+-- Self-service registration of a user, from the verification of their email
+-- to the decision of an admin. Users created otherwise have none.
+CREATE TABLE registrations (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL DEFAULT 'unverified'
+        CHECK (status IN ('unverified', 'pending', 'approved', 'rejected')),
+    -- Digest of the verification token, cleared once it is used
+    token_hash VARCHAR(64) UNIQUE,
+    token_expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    verified_at TIMESTAMP WITH TIME ZONE,
+    reviewed_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+SELECT diesel_manage_updated_at('registrations');
+
+CREATE INDEX registrations_status_idx ON registrations(status);
@@ -0,0 +1,19 @@
+-- Failed logins in a row of a username or from an IP address. Past a few,
+-- each failure blocks further attempts for longer, up to a lockout. Rows are
+-- dropped on success and once the failures are old enough to be forgotten.
+CREATE TABLE lockouts (
+    id SERIAL PRIMARY KEY,
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('username', 'ip')),
+    key VARCHAR(255) NOT NULL,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- Attempts are refused until then
+    locked_until TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, key)
+);
+
+SELECT diesel_manage_updated_at('lockouts');
+
+CREATE INDEX lockouts_last_failure_at_idx ON lockouts(last_failure_at);
@@ -0,0 +1,150 @@
+use std::{collections::BTreeSet, sync::Mutex};
+
+use argon2::{password_hash::SaltString, Argon2, PasswordHasher};
+use rand_core::OsRng;
+use thiserror::Error;
+
+use crate::{
+    domain::models::user::UserModel,
+    infra::repositories::{
+        self,
+        error::{RepoError, RepoResult},
+        group::NewGroupDB,
+        group_permission_rel::NewGroupPermDB,
+        user::NewUserDB,
+    },
+    routes::datasets::access::ACL_BYPASS_PERMISSION,
+};
+
+/// Group of the superusers, holding every permission.
+pub const ROOT_GROUP: &str = "root";
+
+/// Permission holding every other one.
+pub const ALL_PERMISSIONS: &str = "*";
+
+/// Permissions checked by handlers rather than by an `AuthLayer`.
+const BUILTIN_PERMISSIONS: &[&str] = &[ALL_PERMISSIONS, ACL_BYPASS_PERMISSION];
+
+#[derive(Error, Debug)]
+pub enum BootstrapError {
+    #[error("{0}")]
+    Repo(#[from] RepoError),
+    #[error("username {0} is already taken")]
+    DuplicateUsername(String),
+    #[error("failed to hash the password")]
+    Hash,
+}
+
+/// Names of the permissions required by the routes, filled in as their
+/// `AuthLayer`s are built.
+#[derive(Default)]
+pub struct PermissionRegistry {
+    names: Mutex<BTreeSet<String>>,
+}
+
+impl PermissionRegistry {
+    pub fn register<I, S>(&self, names: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut registered = self.names.lock().unwrap_or_else(|err| err.into_inner());
+        registered.extend(names.into_iter().map(Into::into));
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let registered = self.names.lock().unwrap_or_else(|err| err.into_inner());
+        registered.iter().cloned().collect()
+    }
+}
+
+pub fn hash_password(password: &str) -> Result<String, BootstrapError> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hashed_password = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|_| BootstrapError::Hash)?
+        .to_string();
+
+    Ok(hashed_password)
+}
+
+/// Adds the permissions the registered routes require and that are missing
+/// from the catalogue. Permissions created through the API are left alone,
+/// so are patterns and deny entries. Returns how many were added.
+pub async fn reconcile_permissions(
+    db: &deadpool_diesel::postgres::Pool,
+    registry: &PermissionRegistry,
+) -> RepoResult<usize> {
+    let mut names = registry.names();
+    names.extend(BUILTIN_PERMISSIONS.iter().map(ToString::to_string));
+
+    repositories::permission::create_missing(db, names).await
+}
+
+/// Creates an active user member of the root group, creating the group and
+/// granting it every permission when missing.
+pub async fn create_superuser(
+    db: &deadpool_diesel::postgres::Pool,
+    username: String,
+    password: String,
+) -> Result<UserModel, BootstrapError> {
+    let user_in_db = repositories::user::try_get_by_username(
+        db, username.clone()
+    )
+        .await?;
+
+    if user_in_db.is_some() {
+        return Err(BootstrapError::DuplicateUsername(username));
+    }
+
+    repositories::permission::create_missing(
+        db, vec![ALL_PERMISSIONS.to_string()]
+    )
+        .await?;
+    let all_perms = repositories::permission::try_get_by_name(
+        db, ALL_PERMISSIONS.to_string()
+    )
+        .await?
+        .ok_or(RepoError::Diesel(diesel::NotFound))?;
+
+    let group = match repositories::group::try_get_by_name(
+        db, ROOT_GROUP.to_string()
+    )
+        .await?
+    {
+        Some(group) => group,
+        None => repositories::group::create(
+            db, NewGroupDB { name: ROOT_GROUP.to_string() }
+        )
+            .await?,
+    };
+
+    let rel_in_db = repositories::group_permission_rel::try_get_by_id(
+        db, group.id, all_perms.id
+    )
+        .await?;
+
+    if rel_in_db.is_none() {
+        repositories::group_permission_rel::create(
+            db, NewGroupPermDB { group_id: group.id, permission_id: all_perms.id }
+        )
+            .await?;
+    }
+
+    let hashed_password = hash_password(&password)?;
+
+    let user = repositories::user::create_active_member(
+        db,
+        NewUserDB {
+            nickname: username.clone(),
+            username,
+            hashed_password,
+            avatar_uri: String::new(),
+            email: None,
+        },
+        group.id,
+    )
+        .await?;
+
+    Ok(user)
+}
@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use futures_util::{pin_mut, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    infra::repositories,
+    routes::datasets::{
+        error::DatasetError,
+        export::{export_items, ExportFormat},
+        import::{import_items, ImportFormat},
+    },
+};
+use super::CliError;
+
+#[derive(Subcommand, Debug)]
+pub enum DatasetCommand {
+    /// Import items from an NDJSON or CSV file, see
+    /// `POST /v1/datasets/{id}/items:import`
+    Import {
+        /// Dataset id
+        #[clap(long)]
+        dataset: i32,
+        /// File to read, `-` for stdin
+        #[clap(long)]
+        file: PathBuf,
+        /// Defaults to csv for `.csv` files, ndjson otherwise
+        #[clap(long, value_enum)]
+        format: Option<ImportFormat>,
+        /// User recorded as the creator of the items
+        #[clap(long)]
+        username: String,
+    },
+    /// Export the items of a dataset, see `GET /v1/datasets/{id}/export`
+    Export {
+        /// Dataset id
+        #[clap(long)]
+        dataset: i32,
+        /// Dataset version id, defaults to the current items
+        #[clap(long)]
+        version: Option<i32>,
+        #[clap(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+        format: ExportFormat,
+        /// File to write, defaults to stdout
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+impl From<DatasetError> for CliError {
+    fn from(err: DatasetError) -> Self {
+        match err {
+            DatasetError::NotFound => Self::NotFound("dataset".to_string()),
+            DatasetError::VersionNotFound => Self::NotFound("dataset version".to_string()),
+            DatasetError::InvalidImport(msg) => Self::Invalid(msg),
+            DatasetError::ExportFailed(msg) => Self::Io(std::io::Error::other(msg)),
+            DatasetError::RepoError(err) => Self::Repo(err),
+            err => Self::Invalid(format!("{:?}", err)),
+        }
+    }
+}
+
+pub async fn run(
+    pool: deadpool_diesel::postgres::Pool,
+    command: DatasetCommand,
+) -> Result<(), CliError> {
+    match command {
+        DatasetCommand::Import { dataset, file, format, username } => {
+            let user = repositories::user::try_get_by_username(&pool, username.clone())
+                .await?
+                .ok_or_else(|| CliError::NotFound(format!("user {}", username)))?;
+
+            let format = format.unwrap_or_else(|| {
+                match file.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+                    true => ImportFormat::Csv,
+                    false => ImportFormat::Ndjson,
+                }
+            });
+
+            let input: Box<dyn AsyncRead + Unpin + Send> = match file.to_str() {
+                Some("-") => Box::new(tokio::io::stdin()),
+                _ => Box::new(tokio::fs::File::open(&file).await?),
+            };
+
+            // The CLI has direct database access, so dataset grants don't
+            // apply to it.
+            let report = import_items(
+                pool, dataset, user.id, None, format, ReaderStream::new(input)
+            )
+                .await?;
+
+            for error in &report.errors {
+                eprintln!("line {}: {}", error.line, error.msg);
+            }
+            println!(
+                "Imported {} of {} records, {} failed",
+                report.imported, report.total, report.failed
+            );
+        }
+        DatasetCommand::Export { dataset, version, format, output } => {
+            let stream = export_items(pool, dataset, version, format).await?;
+            pin_mut!(stream);
+
+            let mut output: Box<dyn AsyncWrite + Unpin + Send> = match output {
+                Some(path) => Box::new(tokio::fs::File::create(path).await?),
+                None => Box::new(tokio::io::stdout()),
+            };
+
+            while let Some(chunk) = stream.next().await {
+                output.write_all(&chunk?).await?;
+            }
+            output.flush().await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use clap::Subcommand;
+
+use crate::{
+    domain::policy,
+    infra::repositories::{self, group_permission_rel::NewGroupPermDB},
+};
+use super::CliError;
+
+#[derive(Subcommand, Debug)]
+pub enum GroupCommand {
+    /// Grant permissions to a group, adding the ones missing from the
+    /// catalogue, such as patterns and deny entries
+    Grant {
+        #[clap(long)]
+        group: String,
+        #[clap(long = "permission", required = true)]
+        permissions: Vec<String>,
+    },
+}
+
+pub async fn run(
+    pool: &deadpool_diesel::postgres::Pool,
+    command: GroupCommand,
+) -> Result<(), CliError> {
+    match command {
+        GroupCommand::Grant { group, permissions } => {
+            let group = repositories::group::try_get_by_name(pool, group.clone())
+                .await?
+                .ok_or_else(|| CliError::NotFound(format!("group {}", group)))?;
+
+            if let Some(name) = permissions.iter().find(|name| !policy::is_valid_name(name)) {
+                return Err(CliError::Invalid(format!("invalid permission name {}", name)));
+            }
+
+            repositories::permission::create_missing(pool, permissions.clone()).await?;
+
+            for name in permissions {
+                let perm = repositories::permission::try_get_by_name(pool, name.clone())
+                    .await?
+                    .ok_or_else(|| CliError::NotFound(format!("permission {}", name)))?;
+
+                let rel_in_db = repositories::group_permission_rel::try_get_by_id(
+                    pool, group.id, perm.id
+                )
+                    .await?;
+
+                if rel_in_db.is_some() {
+                    println!("Group {} already holds {}", group.name, perm.name);
+                    continue;
+                }
+
+                repositories::group_permission_rel::create(
+                    pool, NewGroupPermDB { group_id: group.id, permission_id: perm.id }
+                )
+                    .await?;
+
+                println!("Granted {} to group {}", perm.name, group.name);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use clap::Subcommand;
+use diesel::{migration::MigrationSource, pg::Pg, PgConnection};
+use diesel_migrations::MigrationHarness;
+
+use crate::{infra::repositories::error::{map_interact_error, RepoError}, server::MIGRATIONS};
+use super::CliError;
+
+#[derive(Subcommand, Debug)]
+pub enum MigrateCommand {
+    /// Apply the pending migrations
+    Up,
+    /// Revert the last applied migrations
+    Down {
+        /// Number of migrations to revert
+        #[clap(long, default_value = "1")]
+        steps: usize,
+    },
+    /// List the migrations and whether they are applied
+    Status,
+}
+
+/// Applies the pending migrations, returning the versions applied.
+pub async fn apply_pending(
+    pool: &deadpool_diesel::postgres::Pool,
+) -> Result<Vec<String>, CliError> {
+    let conn = pool.get().await.map_err(RepoError::Pool)?;
+
+    let applied = conn
+        .interact(run_pending)
+        .await
+        .map_err(map_interact_error)?
+        .map_err(CliError::Migration)?;
+
+    Ok(applied)
+}
+
+fn run_pending(conn: &mut PgConnection) -> Result<Vec<String>, String> {
+    let applied = conn
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|err| err.to_string())?;
+
+    Ok(applied.iter().map(ToString::to_string).collect())
+}
+
+pub async fn run(
+    pool: &deadpool_diesel::postgres::Pool,
+    command: MigrateCommand,
+) -> Result<(), CliError> {
+    let conn = pool.get().await.map_err(RepoError::Pool)?;
+
+    let lines = conn
+        .interact(move |conn| -> Result<Vec<String>, String> {
+            match command {
+                MigrateCommand::Up => Ok(run_pending(conn)?
+                    .iter()
+                    .map(|version| format!("Applied {}", version))
+                    .collect()),
+                MigrateCommand::Down { steps } => {
+                    let applied = conn.applied_migrations().map_err(|err| err.to_string())?;
+
+                    (0..steps.min(applied.len()))
+                        .map(|_| {
+                            conn.revert_last_migration(MIGRATIONS)
+                                .map(|version| format!("Reverted {}", version))
+                                .map_err(|err| err.to_string())
+                        })
+                        .collect()
+                }
+                MigrateCommand::Status => {
+                    let applied = conn.applied_migrations().map_err(|err| err.to_string())?;
+                    let migrations = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+                        .map_err(|err| err.to_string())?;
+
+                    Ok(migrations
+                        .iter()
+                        .map(|migration| {
+                            let name = migration.name();
+                            let status = match applied.contains(&name.version()) {
+                                true => "applied",
+                                false => "pending",
+                            };
+                            format!("{:<8} {}", status, name)
+                        })
+                        .collect())
+                }
+            }
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(CliError::Migration)?;
+
+    if lines.is_empty() {
+        println!("Nothing to do");
+    }
+    for line in lines {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//! Subcommands of the backend binary, managing the instance straight through
+//! the repositories instead of the API.
+
+mod dataset;
+mod group;
+mod migrate;
+mod user;
+
+use std::io::IsTerminal;
+
+use clap::Subcommand;
+use deadpool_diesel::postgres::{Manager, Pool};
+use thiserror::Error;
+
+use crate::{
+    bootstrap::BootstrapError,
+    domain::password::PasswordPolicy,
+    infra::repositories::error::RepoError,
+};
+pub use dataset::DatasetCommand;
+pub use group::GroupCommand;
+pub use migrate::MigrateCommand;
+pub use user::{AdminCommand, UserCommand};
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Serve the API, the default when no command is given
+    Serve,
+    /// Apply or revert the database migrations
+    Migrate {
+        #[clap(subcommand)]
+        command: MigrateCommand,
+    },
+    /// Manage users
+    User {
+        #[clap(subcommand)]
+        command: UserCommand,
+    },
+    /// Manage groups
+    Group {
+        #[clap(subcommand)]
+        command: GroupCommand,
+    },
+    /// Import and export the items of datasets
+    Dataset {
+        #[clap(subcommand)]
+        command: DatasetCommand,
+    },
+    /// Administration of the instance
+    Admin {
+        #[clap(subcommand)]
+        command: AdminCommand,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("failed to connect to the database: {0}")]
+    Connect(String),
+    #[error("{0}")]
+    Repo(#[from] RepoError),
+    #[error("{0}")]
+    Bootstrap(#[from] BootstrapError),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("migration failed: {0}")]
+    Migration(String),
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// Runs every command but `serve`, which is up to the caller. Passwords
+/// set by the commands must meet `password_policy`.
+pub async fn run(
+    command: Command,
+    database_url: String,
+    password_policy: PasswordPolicy,
+) -> Result<(), CliError> {
+    let pool = connect(database_url)?;
+
+    match command {
+        Command::Serve => unreachable!("the API is served by the caller"),
+        Command::Migrate { command } => migrate::run(&pool, command).await,
+        Command::User { command } => user::run(&pool, command, &password_policy).await,
+        Command::Group { command } => group::run(&pool, command).await,
+        Command::Dataset { command } => dataset::run(pool, command).await,
+        Command::Admin { command } => user::run_admin(&pool, command, &password_policy).await,
+    }
+}
+
+fn connect(database_url: String) -> Result<Pool, CliError> {
+    let manager = Manager::new(
+        database_url, deadpool_diesel::Runtime::Tokio1
+    );
+
+    Pool::builder(manager)
+        .build()
+        .map_err(|err| CliError::Connect(err.to_string()))
+}
+
+/// Prompts twice for a password without echoing it, or reads one line when
+/// stdin is no terminal so that scripts can pipe it in. The password must
+/// meet `policy`.
+fn prompt_password(policy: &PasswordPolicy) -> Result<String, CliError> {
+    if !std::io::stdin().is_terminal() {
+        let mut password = String::new();
+        std::io::stdin().read_line(&mut password)?;
+        let password = password.trim_end_matches(['\r', '\n']).to_string();
+        if password.is_empty() {
+            return Err(CliError::Invalid("the password can't be empty".to_string()));
+        }
+        if let Err(violation) = policy.check(&password) {
+            return Err(CliError::Invalid(format!("the password {}", violation)));
+        }
+        return Ok(password);
+    }
+
+    loop {
+        let password = rpassword::prompt_password("Password: ")?;
+        if password.is_empty() {
+            eprintln!("The password can't be empty");
+            continue;
+        }
+        if let Err(violation) = policy.check(&password) {
+            eprintln!("The password {}", violation);
+            continue;
+        }
+
+        if rpassword::prompt_password("Confirm password: ")? == password {
+            return Ok(password);
+        }
+        eprintln!("The passwords don't match");
+    }
+}
@@ -0,0 +1,148 @@
+use clap::Subcommand;
+
+use crate::{
+    bootstrap::{self, BootstrapError},
+    domain::{models::user::UserModel, password::PasswordPolicy},
+    infra::{mailer, repositories::{self, user::{NewUserDB, UpdatedUserDB}}},
+};
+use super::{prompt_password, CliError};
+
+#[derive(Subcommand, Debug)]
+pub enum UserCommand {
+    /// Create a user, prompting for their password
+    Create {
+        #[clap(long)]
+        username: String,
+        /// Defaults to the username
+        #[clap(long)]
+        nickname: Option<String>,
+        #[clap(long)]
+        email: Option<String>,
+        /// Activate the user straight away
+        #[clap(long)]
+        active: bool,
+    },
+    /// Activate a user so that they can log in
+    Activate {
+        #[clap(long)]
+        username: String,
+    },
+    /// Set the password of a user, prompting for it, and log them out
+    ResetPassword {
+        #[clap(long)]
+        username: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AdminCommand {
+    /// Create an active superuser, prompting for their password
+    Create {
+        #[clap(long)]
+        username: String,
+    },
+}
+
+async fn get_user(
+    pool: &deadpool_diesel::postgres::Pool,
+    username: String,
+) -> Result<UserModel, CliError> {
+    repositories::user::try_get_by_username(pool, username.clone())
+        .await?
+        .ok_or_else(|| CliError::NotFound(format!("user {}", username)))
+}
+
+pub async fn run(
+    pool: &deadpool_diesel::postgres::Pool,
+    command: UserCommand,
+    password_policy: &PasswordPolicy,
+) -> Result<(), CliError> {
+    match command {
+        UserCommand::Create { username, nickname, email, active } => {
+            let user_in_db = repositories::user::try_get_by_username(
+                pool, username.clone()
+            )
+                .await?;
+
+            if user_in_db.is_some() {
+                return Err(BootstrapError::DuplicateUsername(username).into());
+            }
+
+            if let Some(email) = &email {
+                if !mailer::is_valid_address(email) {
+                    return Err(CliError::Invalid(format!("invalid email address {}", email)));
+                }
+
+                let email_in_db = repositories::user::try_get_by_email(pool, email.clone()).await?;
+                if email_in_db.is_some() {
+                    return Err(CliError::Invalid(format!("email {} is already taken", email)));
+                }
+            }
+
+            let hashed_password = bootstrap::hash_password(&prompt_password(password_policy)?)?;
+            let mut user = repositories::user::create(
+                pool,
+                NewUserDB {
+                    nickname: nickname.unwrap_or_else(|| username.clone()),
+                    username,
+                    hashed_password,
+                    avatar_uri: String::new(),
+                    email,
+                },
+            )
+                .await?;
+
+            if active {
+                user = repositories::user::activate_by_id(pool, user.id).await?;
+            }
+
+            println!("Created user {} with id {}", user.username, user.id);
+        }
+        UserCommand::Activate { username } => {
+            let user = get_user(pool, username).await?;
+            repositories::user::activate_by_id(pool, user.id).await?;
+
+            println!("Activated user {}", user.username);
+        }
+        UserCommand::ResetPassword { username } => {
+            let user = get_user(pool, username).await?;
+            let hashed_password = bootstrap::hash_password(&prompt_password(password_policy)?)?;
+
+            repositories::user::update_by_id(
+                pool,
+                user.id,
+                UpdatedUserDB {
+                    hashed_password: Some(hashed_password),
+                    nickname: None,
+                    avatar_uri: None,
+                },
+            )
+                .await?;
+            let revoked = repositories::session::revoke_all_by_user_id(pool, user.id).await?;
+
+            println!("Reset the password of user {}, revoked {} sessions", user.username, revoked);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn run_admin(
+    pool: &deadpool_diesel::postgres::Pool,
+    command: AdminCommand,
+    password_policy: &PasswordPolicy,
+) -> Result<(), CliError> {
+    match command {
+        AdminCommand::Create { username } => {
+            // Meant for fresh databases, which the server hasn't migrated yet.
+            super::migrate::apply_pending(pool).await?;
+
+            let password = prompt_password(password_policy)?;
+            let user = bootstrap::create_superuser(pool, username, password).await?;
+
+            println!("Created superuser {} with id {}", user.username, user.id);
+        }
+    }
+
+    Ok(())
+}
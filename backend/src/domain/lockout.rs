@@ -0,0 +1,103 @@
+//! Throttling of failed logins.
+//!
+//! Failures are counted in a row per username and per client address. The
+//! first few are free, each further one blocks logins for twice as long as
+//! the previous one, and past a maximum the username or address is locked
+//! out. Counts are forgotten once no failure happened for a lockout.
+
+use std::time::Duration;
+
+use super::models::lockout::LockoutKind;
+
+#[derive(Clone, Copy, Debug)]
+pub struct LockoutPolicy {
+    /// Failures in a row blocking nothing
+    pub free_failures: i32,
+    /// Block after the first failure past the free ones
+    pub backoff: Duration,
+    /// Failures in a row of a username locking it out
+    pub max_failures: i32,
+    /// Failures in a row from an address locking it out, higher since many
+    /// users may share one
+    pub max_failures_per_ip: i32,
+    /// How long lockouts last, and how long failures are remembered
+    pub lockout: Duration,
+}
+
+impl LockoutPolicy {
+    fn max_failures(&self, kind: LockoutKind) -> i32 {
+        match kind {
+            LockoutKind::Username => self.max_failures,
+            LockoutKind::Ip => self.max_failures_per_ip,
+        }
+    }
+
+    /// Whether `failures` in a row lock the username or address out.
+    pub fn is_locked_out(&self, kind: LockoutKind, failures: i32) -> bool {
+        failures >= self.max_failures(kind)
+    }
+
+    /// How long logins are refused after the `failures`-th failure in a row.
+    pub fn block(&self, kind: LockoutKind, failures: i32) -> Option<Duration> {
+        if self.is_locked_out(kind, failures) {
+            return Some(self.lockout);
+        }
+        if failures <= self.free_failures {
+            return None;
+        }
+
+        let doublings = (failures - self.free_failures - 1).min(31) as u32;
+        let backoff = self.backoff
+            .saturating_mul(1 << doublings)
+            .min(self.lockout);
+
+        Some(backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: LockoutPolicy = LockoutPolicy {
+        free_failures: 3,
+        backoff: Duration::from_secs(1),
+        max_failures: 10,
+        max_failures_per_ip: 50,
+        lockout: Duration::from_secs(60),
+    };
+
+    #[test]
+    fn free_failures_block_nothing() {
+        for failures in 0..=POLICY.free_failures {
+            assert_eq!(POLICY.block(LockoutKind::Username, failures), None);
+        }
+    }
+
+    #[test]
+    fn block_doubles_with_each_further_failure() {
+        let blocks = (4..=9)
+            .map(|failures| POLICY.block(LockoutKind::Username, failures).unwrap().as_secs())
+            .collect::<Vec<u64>>();
+
+        assert_eq!(blocks, vec![1, 2, 4, 8, 16, 32]);
+    }
+
+    #[test]
+    fn block_is_capped_at_the_lockout() {
+        assert_eq!(POLICY.block(LockoutKind::Ip, 9), Some(Duration::from_secs(32)));
+        assert_eq!(POLICY.block(LockoutKind::Ip, 10), Some(POLICY.lockout));
+        assert_eq!(POLICY.block(LockoutKind::Ip, 49), Some(POLICY.lockout));
+    }
+
+    #[test]
+    fn max_failures_lock_out() {
+        assert!(!POLICY.is_locked_out(LockoutKind::Username, 9));
+        assert!(POLICY.is_locked_out(LockoutKind::Username, 10));
+        assert_eq!(POLICY.block(LockoutKind::Username, 10), Some(POLICY.lockout));
+
+        assert!(!POLICY.is_locked_out(LockoutKind::Ip, 10));
+        assert!(POLICY.is_locked_out(LockoutKind::Ip, 50));
+        assert_eq!(POLICY.block(LockoutKind::Ip, i32::MAX), Some(POLICY.lockout));
+    }
+}
@@ -1 +1,5 @@
+pub mod lockout;
 pub mod models;
+pub mod password;
+pub mod policy;
+pub mod totp;
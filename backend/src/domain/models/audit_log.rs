@@ -0,0 +1,13 @@
+use chrono::NaiveDateTime;
+
+#[derive(Clone, Debug)]
+pub struct AuditLogModel {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub method: String,
+    pub route: String,
+    pub target_id: Option<i32>,
+    pub body_digest: Option<String>,
+    pub status: i32,
+    pub created_at: NaiveDateTime,
+}
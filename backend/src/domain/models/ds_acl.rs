@@ -0,0 +1,50 @@
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+/// Role of a user or group on a single dataset. Roles are ordered, each one
+/// allowing everything the previous one does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DatasetRole {
+    /// Reads the dataset, its items, shards and annotations
+    Viewer,
+    /// Also changes them
+    Editor,
+    /// Also deletes the dataset and manages its grants
+    Owner,
+}
+
+impl DatasetRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Editor => "editor",
+            Self::Owner => "owner",
+        }
+    }
+}
+
+impl FromStr for DatasetRole {
+    type Err = String;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        match role {
+            "viewer" => Ok(Self::Viewer),
+            "editor" => Ok(Self::Editor),
+            "owner" => Ok(Self::Owner),
+            _ => Err(format!("unknown dataset role: {}", role)),
+        }
+    }
+}
+
+/// A dataset-scoped grant, given to exactly one of a user or a group.
+#[derive(Clone, Debug)]
+pub struct DatasetAclModel {
+    pub id: i32,
+    pub ds_id: i32,
+    pub user_id: Option<i32>,
+    pub group_id: Option<i32>,
+    pub role: DatasetRole,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -7,4 +7,6 @@ pub struct DatasetItemModel {
     pub uri: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
 }
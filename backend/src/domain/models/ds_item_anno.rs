@@ -11,4 +11,6 @@ pub struct DatasetItemAnnoModel {
     pub text: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
 }
@@ -6,4 +6,6 @@ pub struct DatasetShardModel {
     pub uri: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
 }
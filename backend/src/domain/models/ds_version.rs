@@ -0,0 +1,46 @@
+use chrono::NaiveDateTime;
+
+use super::ds_item_anno::DatasetItemAnnoModel;
+
+#[derive(Clone, Debug)]
+pub struct DatasetVersionModel {
+    pub id: i32,
+    pub ds_id: i32,
+    pub tag: String,
+    pub num_items: i32,
+    pub num_shards: i32,
+    pub num_annos: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DatasetDiffKind {
+    Item,
+    Shard,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DatasetDiffChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Clone, Debug)]
+pub struct DatasetItemAnnoDiffModel {
+    pub anno_id: i32,
+    pub change: DatasetDiffChange,
+    pub from: Option<DatasetItemAnnoModel>,
+    pub to: Option<DatasetItemAnnoModel>,
+}
+
+/// One item or shard whose membership, `typ` and `uri`, or annotations differ
+/// between two states of a dataset. Only modified items carry annotation
+/// changes.
+#[derive(Clone, Debug)]
+pub struct DatasetDiffModel {
+    pub kind: DatasetDiffKind,
+    pub id: i32,
+    pub change: DatasetDiffChange,
+    pub annos: Vec<DatasetItemAnnoDiffModel>,
+}
@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+/// What failed logins are counted against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockoutKind {
+    /// The username given, whether or not such a user exists
+    Username,
+    /// The address of the client
+    Ip,
+}
+
+impl LockoutKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Username => "username",
+            Self::Ip => "ip",
+        }
+    }
+}
+
+impl FromStr for LockoutKind {
+    type Err = String;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "username" => Ok(Self::Username),
+            "ip" => Ok(Self::Ip),
+            _ => Err(format!("unknown lockout kind: {}", kind)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LockoutModel {
+    pub id: i32,
+    pub kind: LockoutKind,
+    /// Username or IP address
+    pub key: String,
+    /// Failed logins in a row
+    pub failures: i32,
+    pub last_failure_at: NaiveDateTime,
+    /// Logins are refused until then
+    pub locked_until: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -1,11 +1,21 @@
+pub mod api_key;
+pub mod audit_log;
 pub mod dataset_item;
 pub mod dataset_shard;
 pub mod dataset;
+pub mod ds_acl;
 pub mod ds_item_anno;
 pub mod ds_item;
 pub mod ds_shard;
+pub mod ds_version;
 pub mod group_perm;
 pub mod group;
+pub mod lockout;
+pub mod password_reset;
 pub mod permission;
+pub mod registration;
+pub mod session;
+pub mod totp_secret;
 pub mod user_group;
 pub mod user;
+pub mod user_identity;
@@ -0,0 +1,11 @@
+use chrono::NaiveDateTime;
+
+#[derive(Clone, Debug)]
+pub struct PasswordResetModel {
+    pub id: i32,
+    pub user_id: i32,
+    pub expires_at: NaiveDateTime,
+    /// Set once the token was used, it can't be used again
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,56 @@
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+/// Where a self-service registration stands. The user stays inactive until
+/// an admin approves it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistrationStatus {
+    /// Waiting for the user to follow the link sent to their email
+    Unverified,
+    /// Email verified, waiting for an admin
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl RegistrationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unverified => "unverified",
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+impl FromStr for RegistrationStatus {
+    type Err = String;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status {
+            "unverified" => Ok(Self::Unverified),
+            "pending" => Ok(Self::Pending),
+            "approved" => Ok(Self::Approved),
+            "rejected" => Ok(Self::Rejected),
+            _ => Err(format!("unknown registration status: {}", status)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RegistrationModel {
+    pub id: i32,
+    pub user_id: i32,
+    pub status: RegistrationStatus,
+    pub token_expires_at: NaiveDateTime,
+    pub verified_at: Option<NaiveDateTime>,
+    /// Admin who approved or rejected the registration
+    pub reviewed_by: Option<i32>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    /// Given by the admin along with their decision
+    pub reason: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
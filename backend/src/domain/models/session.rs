@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+
+#[derive(Clone, Debug)]
+pub struct SessionModel {
+    pub id: i32,
+    pub user_id: i32,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl SessionModel {
+    /// Whether access tokens of the session are still accepted and its
+    /// refresh token can still be rotated.
+    pub fn is_live(&self, now: NaiveDateTime) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
@@ -0,0 +1,36 @@
+use chrono::NaiveDateTime;
+
+#[derive(Clone)]
+pub struct TotpSecretModel {
+    pub id: i32,
+    pub user_id: i32,
+    /// Base32 encoded
+    pub secret: String,
+    /// Null while the enrollment awaits a first code
+    pub enabled_at: Option<NaiveDateTime>,
+    /// Time step of the last code accepted
+    pub last_used_step: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// `Debug` is implemented manually to keep the secret out of the logs.
+impl std::fmt::Debug for TotpSecretModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TotpSecretModel")
+            .field("id", &self.id)
+            .field("user_id", &self.user_id)
+            .field("secret", &"[redacted]")
+            .field("enabled_at", &self.enabled_at)
+            .field("last_used_step", &self.last_used_step)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .finish()
+    }
+}
+
+impl TotpSecretModel {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
@@ -10,6 +10,7 @@ pub struct UserModel {
     pub nickname: String,
     pub avatar_uri: String,
     pub is_active: bool,
+    pub email: Option<String>,
     pub groups: Option<Vec<GroupModel>>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -26,6 +27,7 @@ impl std::fmt::Debug for UserModel {
             .field("nickname", &self.nickname)
             .field("avatar_uri", &self.avatar_uri)
             .field("is_active", &self.is_active)
+            .field("email", &self.email)
             .field("groups", &self.groups)
             .field("created_at", &self.created_at)
             .field("updated_at", &self.updated_at)
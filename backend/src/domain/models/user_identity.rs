@@ -0,0 +1,13 @@
+use chrono::NaiveDateTime;
+
+#[derive(Clone, Debug)]
+pub struct UserIdentityModel {
+    pub id: i32,
+    pub user_id: i32,
+    /// Identity provider, e.g. the issuer URL of an OpenID Connect provider
+    pub provider: String,
+    /// Id of the user at the provider
+    pub subject: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,161 @@
+//! Strength requirements of the passwords users choose.
+//!
+//! They are checked whenever a password is set, be it by the user, an admin
+//! or the CLI. Passwords set before the policy changed are left alone.
+
+use std::{collections::HashSet, fmt::Display, io, path::Path};
+
+/// Kinds of characters a password can mix.
+const CLASSES: [fn(char) -> bool; 3] = [
+    char::is_lowercase,
+    char::is_uppercase,
+    char::is_numeric,
+];
+
+/// Number of character classes a password can mix: lowercase letters,
+/// uppercase letters, digits and symbols.
+pub const MAX_CLASSES: usize = CLASSES.len() + 1;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PasswordViolation {
+    /// Shorter than the minimum length, in characters
+    TooShort(usize),
+    /// Mixes fewer than the minimum number of character classes
+    TooFewClasses(usize),
+    /// Listed among the breached passwords
+    Breached,
+}
+
+impl Display for PasswordViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooShort(min) => write!(f, "must be at least {} characters long", min),
+            Self::TooFewClasses(min) => write!(
+                f,
+                "must mix at least {} of lowercase letters, uppercase letters, digits and symbols",
+                min
+            ),
+            Self::Breached => write!(f, "appears in a list of breached passwords"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PasswordPolicy {
+    /// Minimum number of characters
+    pub min_length: usize,
+    /// Minimum number of character classes mixed, up to `MAX_CLASSES`
+    pub min_classes: usize,
+    /// Passwords known to have leaked, refused whatever their strength
+    breached: HashSet<String>,
+}
+
+impl std::fmt::Debug for PasswordPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PasswordPolicy")
+            .field("min_length", &self.min_length)
+            .field("min_classes", &self.min_classes)
+            .field("breached", &self.breached.len())
+            .finish()
+    }
+}
+
+impl PasswordPolicy {
+    pub fn new(min_length: usize, min_classes: usize) -> Self {
+        Self {
+            min_length,
+            min_classes: min_classes.min(MAX_CLASSES),
+            breached: HashSet::new(),
+        }
+    }
+
+    /// Refuses the passwords listed in `path`, one per line, such as the
+    /// lists published after breaches. The whole list is kept in memory.
+    pub fn with_breached_list(mut self, path: &Path) -> io::Result<Self> {
+        let list = std::fs::read(path)?;
+
+        // Lists aren't always valid UTF-8, the lines that aren't can't match
+        // a password anyway.
+        self.breached = String::from_utf8_lossy(&list)
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(self)
+    }
+
+    /// Number of passwords of the breached list.
+    pub fn breached_count(&self) -> usize {
+        self.breached.len()
+    }
+
+    pub fn check(&self, password: &str) -> Result<(), PasswordViolation> {
+        if password.chars().count() < self.min_length {
+            return Err(PasswordViolation::TooShort(self.min_length));
+        }
+
+        let mut classes = CLASSES
+            .iter()
+            .filter(|is_class| password.chars().any(is_class))
+            .count();
+        if password.chars().any(|c| !CLASSES.iter().any(|is_class| is_class(c))) {
+            classes += 1;
+        }
+        if classes < self.min_classes {
+            return Err(PasswordViolation::TooFewClasses(self.min_classes));
+        }
+
+        if self.breached.contains(password) {
+            return Err(PasswordViolation::Breached);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_is_counted_in_characters() {
+        let policy = PasswordPolicy::new(8, 0);
+
+        // 8 characters, 16 bytes
+        assert_eq!(policy.check("ééééééé1"), Ok(()));
+        assert_eq!(policy.check("éééééé1"), Err(PasswordViolation::TooShort(8)));
+    }
+
+    #[test]
+    fn symbols_count_as_a_class() {
+        let policy = PasswordPolicy::new(0, 2);
+
+        assert_eq!(policy.check("abcdefgh"), Err(PasswordViolation::TooFewClasses(2)));
+        assert_eq!(policy.check("abcdefg!"), Ok(()));
+        assert_eq!(policy.check("abcdefg "), Ok(()));
+        assert_eq!(PasswordPolicy::new(0, MAX_CLASSES).check("aB3!"), Ok(()));
+        assert_eq!(
+            PasswordPolicy::new(0, MAX_CLASSES).check("aB3c"),
+            Err(PasswordViolation::TooFewClasses(MAX_CLASSES))
+        );
+    }
+
+    #[test]
+    fn breached_lists_may_end_lines_with_crlf() {
+        let path = std::env::temp_dir()
+            .join(format!("breached-{}.txt", std::process::id()));
+        std::fs::write(&path, "password1\r\nletmein22\n\r\nqwerty123").unwrap();
+
+        let policy = PasswordPolicy::new(0, 0).with_breached_list(&path);
+        std::fs::remove_file(&path).unwrap();
+        let policy = policy.unwrap();
+
+        assert_eq!(policy.breached_count(), 3);
+        assert_eq!(policy.check("password1"), Err(PasswordViolation::Breached));
+        assert_eq!(policy.check("letmein22"), Err(PasswordViolation::Breached));
+        assert_eq!(policy.check("qwerty123"), Err(PasswordViolation::Breached));
+        assert_eq!(policy.check("password1\r"), Ok(()));
+    }
+}
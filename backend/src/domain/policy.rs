@@ -0,0 +1,286 @@
+//! Evaluation of the permissions a user holds against what a route requires.
+//!
+//! Permission names are dot separated paths such as `datasets.items.read`.
+//! A held permission may be a pattern: `datasets.*` holds every permission
+//! below `datasets`, and `*` holds all of them. A held name starting with
+//! `!` is a deny entry, refusing what it matches whatever else is held.
+
+/// Prefix of deny entries.
+pub const DENY_PREFIX: char = '!';
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Pattern {
+    /// `*`
+    Any,
+    /// `datasets.*`, stored without the trailing `.*`
+    Below(String),
+    /// `datasets.read`
+    Exact(String),
+}
+
+impl Pattern {
+    /// `None` for names that aren't valid permission names or patterns.
+    fn parse(name: &str) -> Option<Self> {
+        if name == "*" {
+            return Some(Self::Any);
+        }
+
+        let (path, below) = match name.strip_suffix(".*") {
+            Some(path) => (path, true),
+            None => (name, false),
+        };
+
+        let is_valid = path.split('.').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        });
+        if !is_valid {
+            return None;
+        }
+
+        Some(match below {
+            true => Self::Below(path.to_string()),
+            false => Self::Exact(path.to_string()),
+        })
+    }
+
+    fn matches(&self, permission: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Below(path) => permission
+                .strip_prefix(path.as_str())
+                .is_some_and(|rest| rest.starts_with('.')),
+            Self::Exact(name) => name == permission,
+        }
+    }
+
+    /// Whether everything `other` matches is matched by this pattern too.
+    fn covers(&self, other: &Pattern) -> bool {
+        match other {
+            Self::Any => *self == Self::Any,
+            Self::Below(path) => match self {
+                Self::Any => true,
+                Self::Below(own) => own == path || self.matches(path),
+                Self::Exact(_) => false,
+            },
+            Self::Exact(name) => self.matches(name),
+        }
+    }
+}
+
+/// Whether `name` can be stored as a permission: a dot separated path, a
+/// pattern, or either of them prefixed with `!` to deny it.
+pub fn is_valid_name(name: &str) -> bool {
+    Pattern::parse(name.strip_prefix(DENY_PREFIX).unwrap_or(name)).is_some()
+}
+
+/// What a route requires of the caller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    /// At least one of the permissions
+    AnyOf(Vec<String>),
+    /// Every one of the permissions
+    AllOf(Vec<String>),
+}
+
+impl Requirement {
+    pub fn permissions(&self) -> &[String] {
+        match self {
+            Self::AnyOf(perms) | Self::AllOf(perms) => perms,
+        }
+    }
+
+    /// Whether the requirement holds given which permissions are allowed.
+    pub fn is_met(&self, allows: impl Fn(&str) -> bool) -> bool {
+        match self {
+            Self::AnyOf(perms) => perms.iter().any(|perm| allows(perm)),
+            Self::AllOf(perms) => perms.iter().all(|perm| allows(perm)),
+        }
+    }
+}
+
+impl From<String> for Requirement {
+    fn from(permission: String) -> Self {
+        Self::AnyOf(vec![permission])
+    }
+}
+
+/// The permissions held by a user, or the scopes of an API key.
+#[derive(Clone, Debug, Default)]
+pub struct Policy {
+    grants: Vec<Pattern>,
+    denies: Vec<Pattern>,
+}
+
+impl Policy {
+    /// Names that are not valid permissions are ignored.
+    pub fn new<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut policy = Self::default();
+        for name in names {
+            let name = name.as_ref();
+            match name.strip_prefix(DENY_PREFIX) {
+                Some(denied) => policy.denies.extend(Pattern::parse(denied)),
+                None => policy.grants.extend(Pattern::parse(name)),
+            }
+        }
+
+        policy
+    }
+
+    pub fn allows(&self, permission: &str) -> bool {
+        !self.denies.iter().any(|deny| deny.matches(permission))
+            && self.grants.iter().any(|grant| grant.matches(permission))
+    }
+
+    pub fn satisfies(&self, requirement: &Requirement) -> bool {
+        requirement.is_met(|perm| self.allows(perm))
+    }
+
+    /// Whether everything the permission or pattern `name` reaches is held,
+    /// which is what it takes to hand it on, e.g. as an API key scope.
+    pub fn covers(&self, name: &str) -> bool {
+        let Some(pattern) = Pattern::parse(name) else {
+            return false;
+        };
+
+        !self.denies.iter().any(|deny| deny.covers(&pattern) || pattern.covers(deny))
+            && self.grants.iter().any(|grant| grant.covers(&pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_names_match_themselves_only() {
+        let policy = Policy::new(["datasets.read"]);
+
+        assert!(policy.allows("datasets.read"));
+        assert!(!policy.allows("datasets.update"));
+        assert!(!policy.allows("datasets.read.extra"));
+        assert!(!policy.allows("datasets"));
+    }
+
+    #[test]
+    fn wildcards_match_every_permission_below() {
+        let policy = Policy::new(["datasets.*"]);
+
+        assert!(policy.allows("datasets.read"));
+        assert!(policy.allows("datasets.items.annos.delete"));
+        assert!(!policy.allows("datasets"));
+        assert!(!policy.allows("datasets_extra.read"));
+        assert!(!policy.allows("groups.read"));
+    }
+
+    #[test]
+    fn star_matches_everything() {
+        let policy = Policy::new(["*"]);
+
+        assert!(policy.allows("users.read_all"));
+        assert!(policy.allows("datasets.items.read"));
+    }
+
+    #[test]
+    fn denies_win_over_grants() {
+        let policy = Policy::new(["*", "!datasets.delete", "!users.*"]);
+
+        assert!(policy.allows("datasets.read"));
+        assert!(!policy.allows("datasets.delete"));
+        assert!(!policy.allows("users.activate"));
+        assert!(policy.allows("groups.read"));
+    }
+
+    #[test]
+    fn deny_entries_alone_grant_nothing() {
+        let policy = Policy::new(["!datasets.delete"]);
+
+        assert!(!policy.allows("datasets.read"));
+        assert!(!policy.allows("datasets.delete"));
+    }
+
+    #[test]
+    fn invalid_names_are_ignored() {
+        let policy = Policy::new(["datasets.*.read", "", "datasets..read", "da*"]);
+
+        assert!(!policy.allows("datasets.items.read"));
+        assert!(!policy.allows("datasets.read"));
+        assert!(!policy.allows("dashboards"));
+    }
+
+    #[test]
+    fn name_validation() {
+        assert!(is_valid_name("datasets.read"));
+        assert!(is_valid_name("datasets.items.*"));
+        assert!(is_valid_name("*"));
+        assert!(is_valid_name("!datasets.delete"));
+        assert!(is_valid_name("!*"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("!"));
+        assert!(!is_valid_name("datasets.*.read"));
+        assert!(!is_valid_name("datasets."));
+        assert!(!is_valid_name("datasets read"));
+        assert!(!is_valid_name("!!datasets.read"));
+    }
+
+    #[test]
+    fn any_of_needs_one_permission() {
+        let policy = Policy::new(["datasets.read"]);
+
+        assert!(policy.satisfies(&Requirement::AnyOf(vec![
+            "datasets.admin".to_string(),
+            "datasets.read".to_string(),
+        ])));
+        assert!(!policy.satisfies(&Requirement::AnyOf(vec![
+            "datasets.admin".to_string(),
+        ])));
+        assert!(!policy.satisfies(&Requirement::AnyOf(vec![])));
+    }
+
+    #[test]
+    fn all_of_needs_every_permission() {
+        let policy = Policy::new(["datasets.*", "!datasets.delete"]);
+
+        assert!(policy.satisfies(&Requirement::AllOf(vec![
+            "datasets.read".to_string(),
+            "datasets.update".to_string(),
+        ])));
+        assert!(!policy.satisfies(&Requirement::AllOf(vec![
+            "datasets.read".to_string(),
+            "datasets.delete".to_string(),
+        ])));
+    }
+
+    #[test]
+    fn covers_patterns_held_in_full() {
+        let policy = Policy::new(["datasets.*"]);
+
+        assert!(policy.covers("datasets.read"));
+        assert!(policy.covers("datasets.items.*"));
+        assert!(policy.covers("datasets.*"));
+        assert!(!policy.covers("*"));
+        assert!(!policy.covers("groups.read"));
+
+        let policy = Policy::new(["datasets.read"]);
+
+        assert!(policy.covers("datasets.read"));
+        assert!(!policy.covers("datasets.*"));
+    }
+
+    #[test]
+    fn covers_nothing_a_deny_reaches() {
+        let policy = Policy::new(["*", "!datasets.delete"]);
+
+        assert!(policy.covers("groups.*"));
+        assert!(policy.covers("datasets.read"));
+        assert!(!policy.covers("datasets.delete"));
+        assert!(!policy.covers("datasets.*"));
+        assert!(!policy.covers("*"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Time-based one-time passwords (RFC 6238) as generated by authenticator
+//! apps, and the recovery codes standing in for them.
+//!
+//! Codes have 6 digits, change every 30 seconds and are accepted one step
+//! early or late to make up for clock drift. Each code is accepted once: the
+//! step of the last one is remembered and older steps are refused.
+
+use rand_core::{OsRng, RngCore};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const DIGITS: usize = 6;
+const STEP_SECS: u64 = 30;
+const SKEW_STEPS: u64 = 1;
+
+/// Number of recovery codes issued at once.
+pub const RECOVERY_CODES: usize = 10;
+
+/// Alphabet of recovery codes, without look-alike characters.
+const RECOVERY_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// New random secret, base32 encoded.
+pub fn new_secret() -> String {
+    let mut bytes = [0u8; 20];
+    OsRng.fill_bytes(&mut bytes);
+
+    match Secret::Raw(bytes.to_vec()).to_encoded() {
+        Secret::Encoded(secret) => secret,
+        Secret::Raw(_) => unreachable!("to_encoded always returns an encoded secret"),
+    }
+}
+
+fn totp(secret: &str, issuer: &str, account_name: &str) -> Option<TOTP> {
+    let secret = Secret::Encoded(secret.to_string()).to_bytes().ok()?;
+
+    Some(TOTP::new_unchecked(
+        Algorithm::SHA1,
+        DIGITS,
+        0,
+        STEP_SECS,
+        secret,
+        Some(issuer.to_string()),
+        account_name.to_string(),
+    ))
+}
+
+/// `otpauth://` URL of the secret, which authenticator apps import, usually
+/// from a QR code.
+pub fn otpauth_url(secret: &str, issuer: &str, account_name: &str) -> Option<String> {
+    totp(secret, issuer, account_name).map(|totp| totp.get_url())
+}
+
+/// Step of `code` when it is valid at `now`, in seconds since the epoch,
+/// and newer than `last_used_step`.
+pub fn verify(secret: &str, code: &str, now: u64, last_used_step: Option<i64>) -> Option<i64> {
+    let totp = totp(secret, "", "")?;
+    let code = code.trim();
+    let current = now / STEP_SECS;
+
+    (current.saturating_sub(SKEW_STEPS)..=current + SKEW_STEPS)
+        .filter(|step| totp.check(code, step * STEP_SECS))
+        .map(|step| step as i64)
+        .find(|step| last_used_step.is_none_or(|last| *step > last))
+}
+
+/// New recovery codes, such as `k7dq-2mxa-p9se-hw4b`.
+pub fn new_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            let chars: Vec<char> = (0..16)
+                .map(|_| {
+                    let index = OsRng.next_u32() as usize % RECOVERY_ALPHABET.len();
+                    RECOVERY_ALPHABET[index] as char
+                })
+                .collect();
+
+            chars
+                .chunks(4)
+                .map(|chunk| chunk.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect()
+}
+
+/// Recovery codes as they are stored, so that typing them without dashes or
+/// in capitals doesn't matter.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Secret of the RFC 6238 test vectors, "12345678901234567890".
+    const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    fn code_at(step: u64) -> String {
+        totp(SECRET, "", "").unwrap().generate(step * STEP_SECS)
+    }
+
+    #[test]
+    fn codes_match_the_rfc_test_vectors() {
+        // 94287082 at 59 seconds, of which authenticator apps show 6 digits
+        assert_eq!(verify(SECRET, "287082", 59, None), Some(1));
+        assert_eq!(verify(SECRET, " 287082\n", 59, None), Some(1));
+        assert_eq!(verify(SECRET, "287083", 59, None), None);
+    }
+
+    #[test]
+    fn codes_are_accepted_one_step_early_or_late() {
+        let code = code_at(1000);
+
+        assert_eq!(verify(SECRET, &code, 999 * STEP_SECS, None), Some(1000));
+        assert_eq!(verify(SECRET, &code, 1000 * STEP_SECS + 29, None), Some(1000));
+        assert_eq!(verify(SECRET, &code, 1001 * STEP_SECS + 29, None), Some(1000));
+        assert_eq!(verify(SECRET, &code, 998 * STEP_SECS + 29, None), None);
+        assert_eq!(verify(SECRET, &code, 1002 * STEP_SECS, None), None);
+    }
+
+    #[test]
+    fn codes_are_accepted_once() {
+        let code = code_at(1000);
+        let now = 1000 * STEP_SECS;
+
+        assert_eq!(verify(SECRET, &code, now, Some(1000)), None);
+        // Nor is an older code once a newer one was used.
+        assert_eq!(verify(SECRET, &code, now, Some(1001)), None);
+        assert_eq!(verify(SECRET, &code, now, Some(999)), Some(1000));
+    }
+
+    #[test]
+    fn recovery_codes_are_normalized() {
+        assert_eq!(normalize_recovery_code("K7DQ-2MXA-P9SE-HW4B"), "k7dq2mxap9sehw4b");
+        assert_eq!(normalize_recovery_code(" k7dq 2mxa\tp9sehw4b "), "k7dq2mxap9sehw4b");
+
+        let codes = new_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODES);
+        for code in codes {
+            assert_eq!(code.len(), 19);
+            assert_eq!(normalize_recovery_code(&code), code.replace('-', ""));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::models::{
+    api_key::ApiKeyModel,
+    permission::PermissionModel,
+    session::SessionModel,
+    user::UserModel,
+};
+use crate::infra::repositories::{self, error::RepoResult};
+
+/// Entries of one kind, each remembered for the TTL of the cache.
+struct Entries<K, V> {
+    entries: HashMap<K, (Instant, V)>,
+}
+
+impl<K: Eq + Hash, V: Clone> Entries<K, V> {
+    fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    fn get(&self, key: &K, ttl: Duration) -> Option<V> {
+        self.entries
+            .get(key)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V, ttl: Duration) {
+        // Expired entries are dropped as they are replaced, and all at once
+        // when they pile up.
+        if self.entries.len() >= 1024 {
+            self.entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < ttl);
+        }
+        self.entries.insert(key, (Instant::now(), value));
+    }
+}
+
+struct Inner {
+    /// Bumped by every invalidation, so that a lookup racing with one
+    /// doesn't store what it read before it.
+    generation: u64,
+    users: Entries<i32, UserModel>,
+    permissions: Entries<i32, Arc<[PermissionModel]>>,
+    permission_names: Entries<i32, Option<String>>,
+    sessions: Entries<i32, SessionModel>,
+    /// Keys by the digest of a token they were verified against, so that
+    /// their hash is only checked once per TTL.
+    api_keys: Entries<Vec<u8>, ApiKeyModel>,
+}
+
+/// In-process cache of what authenticating a request reads: the session or
+/// API key, the user, their permissions, and the names of permissions by id.
+/// Handlers changing any of them invalidate it, other instances of the
+/// server and the CLI see changes once the TTL elapsed.
+pub struct AuthCache {
+    ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl AuthCache {
+    /// A zero TTL disables the cache.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            inner: Mutex::new(Inner {
+                generation: 0,
+                users: Entries::new(),
+                permissions: Entries::new(),
+                permission_names: Entries::new(),
+                sessions: Entries::new(),
+                api_keys: Entries::new(),
+            }),
+        }
+    }
+
+    fn inner(&self) -> std::sync::MutexGuard<'_, Inner> {
+        // The entries stay consistent even if a holder panicked.
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub async fn user(
+        &self,
+        db: &deadpool_diesel::postgres::Pool,
+        user_id: i32,
+    ) -> RepoResult<UserModel> {
+        let generation = {
+            let inner = self.inner();
+            if let Some(user) = inner.users.get(&user_id, self.ttl) {
+                return Ok(user);
+            }
+            inner.generation
+        };
+
+        let user = repositories::user::get_by_id(db, user_id).await?;
+
+        let mut inner = self.inner();
+        if inner.generation == generation && !self.ttl.is_zero() {
+            inner.users.insert(user_id, user.clone(), self.ttl);
+        }
+
+        Ok(user)
+    }
+
+    pub async fn permissions(
+        &self,
+        db: &deadpool_diesel::postgres::Pool,
+        user_id: i32,
+    ) -> RepoResult<Arc<[PermissionModel]>> {
+        let generation = {
+            let inner = self.inner();
+            if let Some(perms) = inner.permissions.get(&user_id, self.ttl) {
+                return Ok(perms);
+            }
+            inner.generation
+        };
+
+        let perms: Arc<[PermissionModel]> = repositories::user::get_permissions(db, user_id)
+            .await?
+            .into();
+
+        let mut inner = self.inner();
+        if inner.generation == generation && !self.ttl.is_zero() {
+            inner.permissions.insert(user_id, perms.clone(), self.ttl);
+        }
+
+        Ok(perms)
+    }
+
+    /// Name of the permission `perm_id`, `None` when there is none.
+    pub async fn permission_name(
+        &self,
+        db: &deadpool_diesel::postgres::Pool,
+        perm_id: i32,
+    ) -> RepoResult<Option<String>> {
+        let generation = {
+            let inner = self.inner();
+            if let Some(name) = inner.permission_names.get(&perm_id, self.ttl) {
+                return Ok(name);
+            }
+            inner.generation
+        };
+
+        let name = repositories::permission::try_get_by_id(db, perm_id)
+            .await?
+            .map(|perm| perm.name);
+
+        let mut inner = self.inner();
+        if inner.generation == generation && !self.ttl.is_zero() {
+            inner.permission_names.insert(perm_id, name.clone(), self.ttl);
+        }
+
+        Ok(name)
+    }
+
+    /// The session `session_id`, `None` when there is none. Whether it is
+    /// still live is up to the caller, as it depends on the time.
+    pub async fn session(
+        &self,
+        db: &deadpool_diesel::postgres::Pool,
+        session_id: i32,
+    ) -> RepoResult<Option<SessionModel>> {
+        let generation = {
+            let inner = self.inner();
+            if let Some(session) = inner.sessions.get(&session_id, self.ttl) {
+                return Ok(Some(session));
+            }
+            inner.generation
+        };
+
+        let session = repositories::session::try_get_by_id(db, session_id).await?;
+
+        let mut inner = self.inner();
+        if let Some(session) = &session {
+            if inner.generation == generation && !self.ttl.is_zero() {
+                inner.sessions.insert(session_id, session.clone(), self.ttl);
+            }
+        }
+
+        Ok(session)
+    }
+
+    /// The API key `token` belongs to, looked up by its `prefix` and checked
+    /// with `verify` unless the same token was verified within the TTL.
+    /// `None` when there is no such key or the token doesn't match it.
+    pub async fn api_key(
+        &self,
+        db: &deadpool_diesel::postgres::Pool,
+        token: &str,
+        prefix: String,
+        verify: impl FnOnce(&ApiKeyModel) -> bool,
+    ) -> RepoResult<Option<ApiKeyModel>> {
+        let digest = Sha256::digest(token.as_bytes()).to_vec();
+        let generation = {
+            let inner = self.inner();
+            if let Some(key) = inner.api_keys.get(&digest, self.ttl) {
+                return Ok(Some(key));
+            }
+            inner.generation
+        };
+
+        let key = repositories::api_key::try_get_by_prefix(db, prefix)
+            .await?
+            .filter(verify);
+
+        let mut inner = self.inner();
+        if let Some(key) = &key {
+            if inner.generation == generation && !self.ttl.is_zero() {
+                inner.api_keys.insert(digest, key.clone(), self.ttl);
+            }
+        }
+
+        Ok(key)
+    }
+
+    /// Records in the cached copies of a key that it was used `at`, so that
+    /// its use isn't written again before it is due.
+    pub fn api_key_used(&self, key_id: i32, at: chrono::NaiveDateTime) {
+        let mut inner = self.inner();
+        for (_, key) in inner.api_keys.entries.values_mut() {
+            if key.id == key_id {
+                key.last_used_at = Some(at);
+            }
+        }
+    }
+
+    /// Forgets a session, after it was revoked or rotated.
+    pub fn invalidate_session(&self, session_id: i32) {
+        let mut inner = self.inner();
+        inner.generation += 1;
+        inner.sessions.entries.remove(&session_id);
+    }
+
+    /// Forgets an API key, after it was deleted.
+    pub fn invalidate_api_key(&self, key_id: i32) {
+        let mut inner = self.inner();
+        inner.generation += 1;
+        inner.api_keys.entries.retain(|_, (_, key)| key.id != key_id);
+    }
+
+    /// Forgets a user along with their permissions, sessions and API keys,
+    /// after the user, their group memberships or their credentials changed.
+    pub fn invalidate_user(&self, user_id: i32) {
+        let mut inner = self.inner();
+        inner.generation += 1;
+        inner.users.entries.remove(&user_id);
+        inner.permissions.entries.remove(&user_id);
+        inner.sessions.entries.retain(|_, (_, session)| session.user_id != user_id);
+        inner.api_keys.entries.retain(|_, (_, key)| key.user_id != user_id);
+    }
+
+    /// Forgets everything, after groups or permissions changed, which
+    /// reaches any number of users.
+    pub fn invalidate_all(&self) {
+        let mut inner = self.inner();
+        inner.generation += 1;
+        inner.users.entries.clear();
+        inner.permissions.entries.clear();
+        inner.permission_names.entries.clear();
+        inner.sessions.entries.clear();
+        inner.api_keys.entries.clear();
+    }
+}
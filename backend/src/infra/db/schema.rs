@@ -1,5 +1,46 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 255]
+        name -> Varchar,
+        #[max_length = 16]
+        prefix -> Varchar,
+        #[max_length = 255]
+        hashed_secret -> Varchar,
+        expires_at -> Nullable<Timestamptz>,
+        last_used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    api_keys_permissions_rel (key_id, permission_id) {
+        key_id -> Int4,
+        permission_id -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    audit_logs (id) {
+        id -> Int4,
+        user_id -> Nullable<Int4>,
+        #[max_length = 16]
+        method -> Varchar,
+        #[max_length = 255]
+        route -> Varchar,
+        target_id -> Nullable<Int4>,
+        #[max_length = 64]
+        body_digest -> Nullable<Varchar>,
+        status -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     datasets (id) {
         id -> Int4,
@@ -8,6 +49,8 @@ diesel::table! {
         description -> Text,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        created_by -> Nullable<Int4>,
+        updated_by -> Nullable<Int4>,
     }
 }
 
@@ -27,6 +70,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    ds_acls (id) {
+        id -> Int4,
+        ds_id -> Int4,
+        user_id -> Nullable<Int4>,
+        group_id -> Nullable<Int4>,
+        #[max_length = 16]
+        role -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     ds_item_annos (id) {
         id -> Int4,
@@ -41,6 +97,8 @@ diesel::table! {
         text -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        created_by -> Nullable<Int4>,
+        updated_by -> Nullable<Int4>,
     }
 }
 
@@ -53,6 +111,8 @@ diesel::table! {
         uri -> Varchar,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        created_by -> Nullable<Int4>,
+        updated_by -> Nullable<Int4>,
     }
 }
 
@@ -63,6 +123,69 @@ diesel::table! {
         uri -> Varchar,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        created_by -> Nullable<Int4>,
+        updated_by -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    ds_version_item_annos (version_id, anno_id) {
+        version_id -> Int4,
+        anno_id -> Int4,
+        item_id -> Int4,
+        #[max_length = 255]
+        name -> Varchar,
+        #[max_length = 255]
+        typ -> Varchar,
+        #[max_length = 255]
+        uri -> Nullable<Varchar>,
+        number -> Nullable<Float8>,
+        text -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        created_by -> Nullable<Int4>,
+        updated_by -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    ds_version_items (version_id, item_id) {
+        version_id -> Int4,
+        item_id -> Int4,
+        #[max_length = 255]
+        typ -> Varchar,
+        #[max_length = 255]
+        uri -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        created_by -> Nullable<Int4>,
+        updated_by -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    ds_version_shards (version_id, shard_id) {
+        version_id -> Int4,
+        shard_id -> Int4,
+        #[max_length = 255]
+        uri -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        created_by -> Nullable<Int4>,
+        updated_by -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    ds_versions (id) {
+        id -> Int4,
+        ds_id -> Int4,
+        #[max_length = 255]
+        tag -> Varchar,
+        num_items -> Int4,
+        num_shards -> Int4,
+        num_annos -> Int4,
+        created_at -> Timestamptz,
     }
 }
 
@@ -84,6 +207,33 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    lockouts (id) {
+        id -> Int4,
+        #[max_length = 16]
+        kind -> Varchar,
+        #[max_length = 255]
+        key -> Varchar,
+        failures -> Int4,
+        last_failure_at -> Timestamptz,
+        locked_until -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    password_resets (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        expires_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     permissions (id) {
         id -> Int4,
@@ -94,6 +244,76 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    recovery_codes (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 64]
+        code_hash -> Varchar,
+        used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    registrations (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 16]
+        status -> Varchar,
+        #[max_length = 64]
+        token_hash -> Nullable<Varchar>,
+        token_expires_at -> Timestamptz,
+        verified_at -> Nullable<Timestamptz>,
+        reviewed_by -> Nullable<Int4>,
+        reviewed_at -> Nullable<Timestamptz>,
+        reason -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    sessions (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        #[max_length = 64]
+        previous_token_hash -> Nullable<Varchar>,
+        expires_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    totp_secrets (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 64]
+        secret -> Varchar,
+        enabled_at -> Nullable<Timestamptz>,
+        last_used_step -> Nullable<Int8>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    user_identities (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 255]
+        provider -> Varchar,
+        #[max_length = 255]
+        subject -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Int4,
@@ -108,6 +328,8 @@ diesel::table! {
         is_active -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        #[max_length = 255]
+        email -> Nullable<Varchar>,
     }
 }
 
@@ -116,29 +338,61 @@ diesel::table! {
         user_id -> Int4,
         group_id -> Int4,
         created_at -> Timestamptz,
+        #[max_length = 16]
+        source -> Nullable<Varchar>,
     }
 }
 
+diesel::joinable!(api_keys -> users (user_id));
+diesel::joinable!(api_keys_permissions_rel -> api_keys (key_id));
+diesel::joinable!(api_keys_permissions_rel -> permissions (permission_id));
 diesel::joinable!(datasets_items_rel -> datasets (ds_id));
 diesel::joinable!(datasets_items_rel -> ds_items (item_id));
 diesel::joinable!(datasets_shards_rel -> datasets (ds_id));
 diesel::joinable!(datasets_shards_rel -> ds_shards (shard_id));
+diesel::joinable!(ds_acls -> datasets (ds_id));
+diesel::joinable!(ds_acls -> groups (group_id));
+diesel::joinable!(ds_acls -> users (user_id));
 diesel::joinable!(ds_item_annos -> ds_items (item_id));
+diesel::joinable!(ds_version_item_annos -> ds_versions (version_id));
+diesel::joinable!(ds_version_items -> ds_versions (version_id));
+diesel::joinable!(ds_version_shards -> ds_versions (version_id));
+diesel::joinable!(ds_versions -> datasets (ds_id));
 diesel::joinable!(groups_permissions_rel -> groups (group_id));
 diesel::joinable!(groups_permissions_rel -> permissions (permission_id));
+diesel::joinable!(password_resets -> users (user_id));
+diesel::joinable!(recovery_codes -> users (user_id));
+diesel::joinable!(sessions -> users (user_id));
+diesel::joinable!(totp_secrets -> users (user_id));
+diesel::joinable!(user_identities -> users (user_id));
 diesel::joinable!(users_groups_rel -> groups (group_id));
 diesel::joinable!(users_groups_rel -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    api_keys_permissions_rel,
+    audit_logs,
     datasets,
     datasets_items_rel,
     datasets_shards_rel,
+    ds_acls,
     ds_item_annos,
     ds_items,
     ds_shards,
+    ds_version_item_annos,
+    ds_version_items,
+    ds_version_shards,
+    ds_versions,
     groups,
     groups_permissions_rel,
+    lockouts,
+    password_resets,
     permissions,
+    recovery_codes,
+    registrations,
+    sessions,
+    totp_secrets,
+    user_identities,
     users,
     users_groups_rel,
 );
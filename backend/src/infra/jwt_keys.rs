@@ -0,0 +1,298 @@
+//! Keys access tokens are signed and verified with.
+//!
+//! Without a key directory tokens are signed with HS256 and the server
+//! secret, as they always were. With one, every `<kid>.pem` private key in
+//! it, RSA or Ed25519, verifies tokens and is published at
+//! `/.well-known/jwks.json`, and one of them signs the new tokens: the one
+//! named in the `signing_kid` file of the directory, else the one given on
+//! startup, else the last one in order of name.
+//!
+//! Keys are rotated without logging anybody out: add the new key and reload
+//! so that it is published, write its kid to `signing_kid` and reload once
+//! clients picked it up, then remove the old one once the tokens it signed
+//! expired. `SIGHUP` reloads the directory.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    errors::ErrorKind,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation,
+};
+use ring::{
+    rsa::PublicKeyComponents,
+    signature::{Ed25519KeyPair, KeyPair, RsaKeyPair},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// File of the key directory naming the key that signs, read on every load.
+const SIGNING_KID_FILE: &str = "signing_kid";
+
+#[derive(Debug)]
+pub enum JwtKeyError {
+    Io(PathBuf, std::io::Error),
+    /// The file holds no RSA or Ed25519 private key.
+    InvalidKey(PathBuf, String),
+    NoKeys(PathBuf),
+    /// The key asked to sign with isn't in the directory.
+    SigningKeyNotFound(String),
+}
+
+impl Display for JwtKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "failed to read {}: {}", path.display(), err),
+            Self::InvalidKey(path, msg) => write!(f, "invalid key {}: {}", path.display(), msg),
+            Self::NoKeys(dir) => write!(f, "no .pem key in {}", dir.display()),
+            Self::SigningKeyNotFound(kid) => write!(f, "no key {} to sign with", kid),
+        }
+    }
+}
+
+struct SigningKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    key: EncodingKey,
+}
+
+/// Keys loaded at once, swapped whole on reload.
+struct KeySet {
+    signing: SigningKey,
+    verifying: HashMap<String, (Algorithm, DecodingKey)>,
+    /// Public keys of `verifying`, empty with the server secret
+    jwks: JwkSet,
+}
+
+impl KeySet {
+    fn from_secret(secret: &str) -> Self {
+        Self {
+            signing: SigningKey {
+                kid: None,
+                algorithm: Algorithm::HS256,
+                key: EncodingKey::from_secret(secret.as_ref()),
+            },
+            verifying: HashMap::from([
+                (String::new(), (Algorithm::HS256, DecodingKey::from_secret(secret.as_ref()))),
+            ]),
+            jwks: JwkSet { keys: Vec::new() },
+        }
+    }
+
+    fn from_dir(dir: &Path, signing_kid: Option<&str>) -> Result<Self, JwtKeyError> {
+        let entries = std::fs::read_dir(dir).map_err(|err| JwtKeyError::Io(dir.to_path_buf(), err))?;
+
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|err| JwtKeyError::Io(dir.to_path_buf(), err))?.path();
+            if path.extension().is_some_and(|ext| ext == "pem") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut keys = Vec::with_capacity(paths.len());
+        for path in paths {
+            let Some(kid) = path.file_stem().and_then(|stem| stem.to_str()).map(str::to_string) else {
+                continue;
+            };
+            let pem = std::fs::read(&path).map_err(|err| JwtKeyError::Io(path.clone(), err))?;
+            let key = load_key(&kid, &pem).map_err(|msg| JwtKeyError::InvalidKey(path.clone(), msg))?;
+            keys.push(key);
+        }
+
+        let kid_path = dir.join(SIGNING_KID_FILE);
+        let kid_file = match std::fs::read_to_string(&kid_path) {
+            Ok(kid) => Some(kid.trim().to_string()).filter(|kid| !kid.is_empty()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(JwtKeyError::Io(kid_path, err)),
+        };
+
+        // Kids sort as their files do, the last one signs unless told
+        // otherwise, e.g. when keys are named by date.
+        let signing = match kid_file.as_deref().or(signing_kid) {
+            Some(kid) => keys.iter().position(|(jwk, _)| jwk.common.key_id.as_deref() == Some(kid))
+                .ok_or_else(|| JwtKeyError::SigningKeyNotFound(kid.to_string()))?,
+            None => keys.len().checked_sub(1).ok_or_else(|| JwtKeyError::NoKeys(dir.to_path_buf()))?,
+        };
+        let (signing_jwk, signing_key) = &keys[signing];
+        let signing = SigningKey {
+            kid: signing_jwk.common.key_id.clone(),
+            algorithm: jwk_algorithm(signing_jwk),
+            key: signing_key.clone(),
+        };
+
+        let mut verifying = HashMap::with_capacity(keys.len());
+        for (jwk, _) in keys.iter() {
+            let key = DecodingKey::from_jwk(jwk)
+                .map_err(|err| JwtKeyError::InvalidKey(dir.to_path_buf(), err.to_string()))?;
+            verifying.insert(jwk.common.key_id.clone().unwrap_or_default(), (jwk_algorithm(jwk), key));
+        }
+
+        Ok(Self {
+            signing,
+            verifying,
+            jwks: JwkSet { keys: keys.into_iter().map(|(jwk, _)| jwk).collect() },
+        })
+    }
+}
+
+fn jwk_algorithm(jwk: &Jwk) -> Algorithm {
+    match jwk.algorithm {
+        AlgorithmParameters::OctetKeyPair(_) => Algorithm::EdDSA,
+        _ => Algorithm::RS256,
+    }
+}
+
+fn common_parameters(kid: &str, algorithm: KeyAlgorithm) -> CommonParameters {
+    CommonParameters {
+        public_key_use: Some(PublicKeyUse::Signature),
+        key_algorithm: Some(algorithm),
+        key_id: Some(kid.to_string()),
+        ..Default::default()
+    }
+}
+
+fn ed25519_jwk(kid: &str, pair: &Ed25519KeyPair) -> Jwk {
+    Jwk {
+        common: common_parameters(kid, KeyAlgorithm::EdDSA),
+        algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+            key_type: OctetKeyPairType::OctetKeyPair,
+            curve: EllipticCurve::Ed25519,
+            x: URL_SAFE_NO_PAD.encode(pair.public_key().as_ref()),
+        }),
+    }
+}
+
+fn rsa_jwk(kid: &str, pair: &RsaKeyPair) -> Jwk {
+    let public = PublicKeyComponents::<Vec<u8>>::from(pair.public());
+
+    Jwk {
+        common: common_parameters(kid, KeyAlgorithm::RS256),
+        algorithm: AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n: URL_SAFE_NO_PAD.encode(&public.n),
+            e: URL_SAFE_NO_PAD.encode(&public.e),
+        }),
+    }
+}
+
+/// Public JWK and signing key of a PEM encoded private key, PKCS#8 or, for
+/// RSA, PKCS#1.
+fn load_key(kid: &str, pem: &[u8]) -> Result<(Jwk, EncodingKey), String> {
+    let parsed = pem::parse(pem).map_err(|err| err.to_string())?;
+    let der = parsed.contents();
+
+    let rsa_pair = match parsed.tag() {
+        "PRIVATE KEY" => match Ed25519KeyPair::from_pkcs8_maybe_unchecked(der) {
+            Ok(pair) => {
+                let key = EncodingKey::from_ed_pem(pem).map_err(|err| err.to_string())?;
+                return Ok((ed25519_jwk(kid, &pair), key));
+            }
+            Err(_) => RsaKeyPair::from_pkcs8(der),
+        },
+        "RSA PRIVATE KEY" => RsaKeyPair::from_der(der),
+        tag => return Err(format!("expected a PRIVATE KEY, got {}", tag)),
+    };
+
+    let pair = rsa_pair
+        .map_err(|err| format!("neither an Ed25519 key nor an RSA key of at least 2048 bits: {}", err))?;
+    let key = EncodingKey::from_rsa_pem(pem).map_err(|err| err.to_string())?;
+
+    Ok((rsa_jwk(kid, &pair), key))
+}
+
+/// Keys of the access tokens, shared by the handlers issuing and checking
+/// them.
+pub struct JwtKeys {
+    /// Directory reloaded from, none with the server secret
+    dir: Option<PathBuf>,
+    /// Kid signing when the directory doesn't name one
+    signing_kid: Option<String>,
+    keys: RwLock<Arc<KeySet>>,
+}
+
+impl JwtKeys {
+    pub fn from_secret(secret: &str) -> Self {
+        Self {
+            dir: None,
+            signing_kid: None,
+            keys: RwLock::new(Arc::new(KeySet::from_secret(secret))),
+        }
+    }
+
+    /// Loads the keys of `dir`. The one named in its `signing_kid` file
+    /// signs, or else the one named `signing_kid`, or else the last one in
+    /// order of name.
+    pub fn from_dir(dir: PathBuf, signing_kid: Option<String>) -> Result<Self, JwtKeyError> {
+        let keys = KeySet::from_dir(&dir, signing_kid.as_deref())?;
+
+        Ok(Self {
+            dir: Some(dir),
+            signing_kid,
+            keys: RwLock::new(Arc::new(keys)),
+        })
+    }
+
+    fn current(&self) -> Arc<KeySet> {
+        self.keys.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// Reads the key directory again, keeping the keys loaded so far when it
+    /// fails. Returns the kid signing from now on.
+    pub fn reload(&self) -> Result<Option<String>, JwtKeyError> {
+        let Some(dir) = &self.dir else {
+            return Ok(None);
+        };
+
+        let keys = KeySet::from_dir(dir, self.signing_kid.as_deref())?;
+        let kid = keys.signing.kid.clone();
+        *self.keys.write().unwrap_or_else(|err| err.into_inner()) = Arc::new(keys);
+
+        Ok(kid)
+    }
+
+    /// Kid of the key signing new tokens, none with the server secret.
+    pub fn signing_kid(&self) -> Option<String> {
+        self.current().signing.kid.clone()
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> jsonwebtoken::errors::Result<String> {
+        let keys = self.current();
+        let mut header = Header::new(keys.signing.algorithm);
+        header.kid = keys.signing.kid.clone();
+
+        encode(&header, claims, &keys.signing.key)
+    }
+
+    /// Verifies a token with the key named by its `kid` and decodes it.
+    /// The algorithms of `validation` are set to the one of the key.
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        mut validation: Validation,
+    ) -> jsonwebtoken::errors::Result<TokenData<T>> {
+        let header = decode_header(token)?;
+        let keys = self.current();
+        let (algorithm, key) = keys.verifying
+            .get(header.kid.as_deref().unwrap_or_default())
+            .ok_or_else(|| jsonwebtoken::errors::Error::from(ErrorKind::InvalidKeyFormat))?;
+
+        validation.algorithms = vec![*algorithm];
+        decode(token, key, &validation)
+    }
+
+    /// Public keys tokens are verified with.
+    pub fn jwks(&self) -> JwkSet {
+        self.current().jwks.clone()
+    }
+}
@@ -0,0 +1,232 @@
+use std::{fmt::Display, time::Duration};
+
+use ldap3::{ldap_escape, Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+
+/// Result code of a bind with a wrong password or unknown DN.
+const INVALID_CREDENTIALS: u32 = 49;
+
+/// Settings of the LDAP directory users log in against.
+#[derive(Clone)]
+pub struct LdapConfig {
+    /// `ldap://` or `ldaps://` URL of the directory
+    pub url: String,
+    /// Upgrades `ldap://` connections with StartTLS
+    pub starttls: bool,
+    /// Account used to look users up, anonymous when unset
+    pub bind_dn: Option<String>,
+    pub bind_password: Option<String>,
+    /// Subtree users are searched in
+    pub base_dn: String,
+    /// Search filter finding a user, `{username}` is replaced by the escaped
+    /// username
+    pub user_filter: String,
+    /// Attribute of user entries listing their groups, either as group DNs
+    /// whose first RDN value is the group name or as plain names
+    pub group_attribute: String,
+}
+
+#[derive(Debug)]
+pub enum LdapError {
+    Ldap(ldap3::LdapError),
+    /// The user filter matched more than one entry.
+    AmbiguousUser(String),
+}
+
+impl Display for LdapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ldap(err) => write!(f, "LDAP error: {}", err),
+            Self::AmbiguousUser(username) => write!(f, "more than one LDAP entry for {}", username),
+        }
+    }
+}
+
+impl From<ldap3::LdapError> for LdapError {
+    fn from(err: ldap3::LdapError) -> Self {
+        Self::Ldap(err)
+    }
+}
+
+/// A user whose password the directory accepted.
+#[derive(Debug)]
+pub struct LdapIdentity {
+    pub dn: String,
+    pub nickname: String,
+    pub groups: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct LdapClient {
+    config: LdapConfig,
+}
+
+/// `admins` for `cn=admins,ou=groups,dc=example,dc=org`, values that are no
+/// DN are taken as the name itself. Escaped characters of the first RDN
+/// value, such as `\,` or `\2C`, are unescaped.
+fn group_name(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut name = Vec::<u8>::with_capacity(bytes.len());
+    let mut in_value = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                    .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+                match hex {
+                    Some(byte) => {
+                        name.push(byte);
+                        i += 3;
+                    }
+                    None => {
+                        name.extend(bytes.get(i + 1));
+                        i += 2;
+                    }
+                }
+                continue;
+            }
+            b',' => break,
+            b'=' if !in_value => {
+                in_value = true;
+                name.clear();
+            }
+            byte => name.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&name).trim().to_string()
+}
+
+impl LdapClient {
+    pub fn new(config: LdapConfig) -> Self {
+        Self {
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &LdapConfig {
+        &self.config
+    }
+
+    async fn connect(&self) -> Result<Ldap, LdapError> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(Duration::from_secs(10))
+            .set_starttls(self.config.starttls);
+        let (conn, ldap) = LdapConnAsync::with_settings(settings, &self.config.url).await?;
+        ldap3::drive!(conn);
+
+        Ok(ldap)
+    }
+
+    /// Looks the user up and binds as them with the password. `None` when
+    /// the directory doesn't know the user or rejects the password.
+    pub async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<LdapIdentity>, LdapError> {
+        // An empty password makes an unauthenticated bind, which directories
+        // accept for any DN.
+        if password.is_empty() {
+            return Ok(None);
+        }
+
+        let mut ldap = self.connect().await?;
+        let res = self.search_and_bind(&mut ldap, username, password).await;
+        let _ = ldap.unbind().await;
+
+        res
+    }
+
+    async fn search_and_bind(
+        &self,
+        ldap: &mut Ldap,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<LdapIdentity>, LdapError> {
+        if let Some(bind_dn) = &self.config.bind_dn {
+            ldap.simple_bind(bind_dn, self.config.bind_password.as_deref().unwrap_or_default())
+                .await?
+                .success()?;
+        }
+
+        let filter = self.config.user_filter.replace("{username}", &ldap_escape(username));
+        let (entries, _) = ldap
+            .search(
+                &self.config.base_dn,
+                Scope::Subtree,
+                &filter,
+                vec!["cn", "displayName", self.config.group_attribute.as_str()],
+            )
+            .await?
+            .success()?;
+
+        let mut entries = entries.into_iter().map(SearchEntry::construct);
+        let Some(entry) = entries.next() else {
+            return Ok(None);
+        };
+        if entries.next().is_some() {
+            return Err(LdapError::AmbiguousUser(username.to_string()));
+        }
+
+        let bind = ldap.simple_bind(&entry.dn, password).await?;
+        if bind.rc == INVALID_CREDENTIALS {
+            return Ok(None);
+        }
+        bind.success()?;
+
+        let first = |attr: &str| {
+            entry.attrs
+                .get(attr)
+                .and_then(|values| values.first())
+                .cloned()
+        };
+        let nickname = first("displayName")
+            .or_else(|| first("cn"))
+            .unwrap_or_else(|| username.to_string());
+        let groups = entry.attrs
+            .get(&self.config.group_attribute)
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|value| group_name(value))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Some(LdapIdentity {
+            dn: entry.dn,
+            nickname,
+            groups,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_name_is_the_first_rdn_value() {
+        assert_eq!(group_name("cn=admins,ou=groups,dc=example,dc=org"), "admins");
+        assert_eq!(group_name("CN = admins , OU=groups"), "admins");
+    }
+
+    #[test]
+    fn group_name_of_a_plain_name_is_the_name() {
+        assert_eq!(group_name("admins"), "admins");
+        assert_eq!(group_name(""), "");
+    }
+
+    #[test]
+    fn group_name_unescapes_the_rdn_value() {
+        assert_eq!(group_name(r"cn=Smith\, John,ou=groups,dc=example,dc=org"), "Smith, John");
+        assert_eq!(group_name(r"cn=R\2CD,ou=groups"), "R,D");
+        assert_eq!(group_name(r"cn=a\=b\\c,ou=groups"), "a=b\\c");
+        assert_eq!(group_name(r"cn=caf\C3\A9,ou=groups"), "café");
+        assert_eq!(group_name(r"cn=\+1,ou=groups"), "+1");
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fmt::Display, path::PathBuf, sync::Arc};
+
+use clap::ValueEnum;
+use futures_util::future::BoxFuture;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    AsyncFileTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+/// Ways of sending emails that can be picked with `--mailer`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum MailerBackend {
+    /// An SMTP relay
+    Smtp,
+    /// `.eml` files written to a directory, for tests and development
+    File,
+}
+
+/// Settings of the mailer notifications are sent with.
+#[derive(Clone)]
+pub struct MailerConfig {
+    pub backend: MailerBackend,
+    /// Sender of every email, e.g. `Data Repo <noreply@example.org>`
+    pub from: String,
+    /// `smtp://` or `smtps://` URL of the relay, credentials included,
+    /// required by the `smtp` backend
+    pub smtp_url: Option<String>,
+    /// Directory emails are written to, required by the `file` backend
+    pub dir: Option<PathBuf>,
+}
+
+/// A plain text email.
+#[derive(Debug)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub enum MailerError {
+    InvalidAddress(String),
+    Message(lettre::error::Error),
+    Transport(String),
+}
+
+impl Display for MailerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAddress(address) => write!(f, "invalid email address: {}", address),
+            Self::Message(err) => write!(f, "failed to build the email: {}", err),
+            Self::Transport(msg) => write!(f, "failed to send the email: {}", msg),
+        }
+    }
+}
+
+/// Whether `address` is a plain email address, such as `user@example.org`.
+pub fn is_valid_address(address: &str) -> bool {
+    address.parse::<lettre::Address>().is_ok()
+}
+
+/// Sends the emails of the server, such as verification links.
+pub trait Mailer: Send + Sync {
+    fn send(&self, email: Email) -> BoxFuture<'_, Result<(), MailerError>>;
+}
+
+/// Builds the mailer of the configured backend.
+pub fn mailer(config: MailerConfig) -> Result<Arc<dyn Mailer>, String> {
+    let from = config.from
+        .parse::<Mailbox>()
+        .map_err(|err| format!("invalid --mail-from: {}", err))?;
+
+    match config.backend {
+        MailerBackend::Smtp => {
+            let url = config.smtp_url
+                .ok_or_else(|| "the smtp mailer needs --smtp-url".to_string())?;
+            let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(&url)
+                .map_err(|err| format!("invalid --smtp-url: {}", err))?
+                .build();
+
+            Ok(Arc::new(LettreMailer { from, transport }))
+        }
+        MailerBackend::File => {
+            let dir = config.dir
+                .ok_or_else(|| "the file mailer needs --mail-dir".to_string())?;
+            let transport = AsyncFileTransport::<Tokio1Executor>::new(dir);
+
+            Ok(Arc::new(LettreMailer { from, transport }))
+        }
+    }
+}
+
+struct LettreMailer<T> {
+    from: Mailbox,
+    transport: T,
+}
+
+impl<T> Mailer for LettreMailer<T>
+where
+    T: AsyncTransport + Send + Sync,
+    T::Error: Display,
+{
+    fn send(&self, email: Email) -> BoxFuture<'_, Result<(), MailerError>> {
+        Box::pin(async move {
+            let to = email.to
+                .parse::<Mailbox>()
+                .map_err(|_| MailerError::InvalidAddress(email.to.clone()))?;
+
+            let message = Message::builder()
+                .from(self.from.clone())
+                .to(to)
+                .subject(email.subject)
+                .header(ContentType::TEXT_PLAIN)
+                .body(email.body)
+                .map_err(MailerError::Message)?;
+
+            self.transport
+                .send(message)
+                .await
+                .map_err(|err| MailerError::Transport(err.to_string()))?;
+
+            Ok(())
+        })
+    }
+}
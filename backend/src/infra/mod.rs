@@ -1,2 +1,7 @@
+pub mod auth_cache;
 pub mod db;
+pub mod jwt_keys;
+pub mod ldap;
+pub mod mailer;
+pub mod oidc;
 pub mod repositories;
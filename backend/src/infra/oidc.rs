@@ -0,0 +1,434 @@
+use std::fmt::Display;
+
+use jsonwebtoken::{
+    decode,
+    decode_header,
+    jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet},
+    Algorithm,
+    DecodingKey,
+    Validation,
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Settings of the OpenID Connect provider users log in with.
+#[derive(Clone)]
+pub struct OidcConfig {
+    /// Issuer URL, the discovery document is read from
+    /// `<issuer>/.well-known/openid-configuration`
+    pub issuer: String,
+    pub client_id: String,
+    /// Unset for public clients, which rely on PKCE alone
+    pub client_secret: Option<String>,
+    /// URL of `/auth/oidc/callback` as registered at the provider
+    pub redirect_uri: String,
+    /// Space separated scopes to request, `openid` included
+    pub scopes: String,
+    /// Claim of the ID token listing the groups of the user
+    pub groups_claim: String,
+    /// Where browsers are sent once logged in. Without it the callback
+    /// answers like `/login` does.
+    pub post_login_redirect: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum OidcError {
+    Http(reqwest::Error),
+    /// The provider answered with an error or something unexpected.
+    Provider(String),
+    InvalidIdToken(String),
+}
+
+impl Display for OidcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(err) => write!(f, "request to the identity provider failed: {}", err),
+            Self::Provider(msg) => write!(f, "identity provider error: {}", msg),
+            Self::InvalidIdToken(msg) => write!(f, "invalid ID token: {}", msg),
+        }
+    }
+}
+
+impl From<reqwest::Error> for OidcError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// The user an ID token vouches for.
+#[derive(Debug)]
+pub struct OidcIdentity {
+    pub subject: String,
+    pub username: String,
+    pub nickname: String,
+    pub avatar_uri: Option<String>,
+    pub groups: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct OidcClient {
+    config: OidcConfig,
+    http: reqwest::Client,
+}
+
+impl OidcClient {
+    pub fn new(config: OidcConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// Reads the discovery document on every use, logins are rare and the
+    /// provider is free to move its endpoints.
+    async fn discover(&self) -> Result<ProviderMetadata, OidcError> {
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.config.issuer.trim_end_matches('/'),
+        );
+        let metadata = self.http
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ProviderMetadata>()
+            .await?;
+
+        if metadata.issuer != self.config.issuer {
+            return Err(OidcError::Provider(format!(
+                "discovery document is for issuer {}", metadata.issuer
+            )));
+        }
+
+        Ok(metadata)
+    }
+
+    /// URL of the provider's login page, asking for an authorization code
+    /// bound to `state`, `nonce` and the PKCE `code_challenge`.
+    pub async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String, OidcError> {
+        let metadata = self.discover().await?;
+        let url = reqwest::Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_uri.as_str()),
+                ("scope", self.config.scopes.as_str()),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+            .map_err(|err| OidcError::Provider(format!("invalid authorization endpoint: {}", err)))?;
+
+        Ok(url.into())
+    }
+
+    /// Redeems an authorization code and returns the identity its verified
+    /// ID token vouches for.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<OidcIdentity, OidcError> {
+        let metadata = self.discover().await?;
+
+        let mut req = self.http
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.config.redirect_uri.as_str()),
+                ("client_id", self.config.client_id.as_str()),
+                ("code_verifier", code_verifier),
+            ]);
+        if let Some(client_secret) = &self.config.client_secret {
+            req = req.basic_auth(&self.config.client_id, Some(client_secret));
+        }
+
+        let res = req
+            .send()
+            .await?
+            .json::<TokenResponse>()
+            .await?;
+
+        let id_token = match res {
+            TokenResponse { id_token: Some(id_token), .. } => id_token,
+            TokenResponse { error, error_description, .. } => {
+                return Err(OidcError::Provider(format!(
+                    "no ID token: {} {}",
+                    error.unwrap_or_default(),
+                    error_description.unwrap_or_default(),
+                )));
+            }
+        };
+
+        let claims = self.verify_id_token(&metadata, &id_token).await?;
+
+        check_nonce(&claims, nonce)?;
+
+        self.identity_of(claims)
+    }
+
+    async fn verify_id_token(
+        &self,
+        metadata: &ProviderMetadata,
+        id_token: &str,
+    ) -> Result<Map<String, Value>, OidcError> {
+        let header = decode_header(id_token)
+            .map_err(|err| OidcError::InvalidIdToken(err.to_string()))?;
+
+        let jwks = self.http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<JwkSet>()
+            .await?;
+
+        let jwk = match &header.kid {
+            Some(kid) => jwks.find(kid),
+            None => jwks.keys.first(),
+        }
+            .ok_or_else(|| OidcError::InvalidIdToken("unknown signing key".to_string()))?;
+
+        let key = DecodingKey::from_jwk(jwk)
+            .map_err(|err| OidcError::InvalidIdToken(err.to_string()))?;
+
+        let mut validation = Validation::new(signing_algorithm(jwk, header.alg)?);
+        validation.set_issuer(&[&self.config.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+
+        let claims = decode::<Map<String, Value>>(id_token, &key, &validation)
+            .map_err(|err| OidcError::InvalidIdToken(err.to_string()))?
+            .claims;
+
+        Ok(claims)
+    }
+
+    fn identity_of(&self, claims: Map<String, Value>) -> Result<OidcIdentity, OidcError> {
+        let claim = |name: &str| {
+            claims
+                .get(name)
+                .and_then(Value::as_str)
+                .filter(|value| !value.is_empty())
+                .map(ToOwned::to_owned)
+        };
+
+        let subject = claim("sub")
+            .ok_or_else(|| OidcError::InvalidIdToken("no sub claim".to_string()))?;
+        let username = claim("preferred_username")
+            .or_else(|| claim("email"))
+            .unwrap_or_else(|| subject.clone());
+        let nickname = claim("name").unwrap_or_else(|| username.clone());
+
+        // Providers list groups as an array, some as a single string.
+        let groups = match claims.get(&self.config.groups_claim) {
+            Some(Value::Array(groups)) => groups
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToOwned::to_owned)
+                .collect(),
+            Some(Value::String(group)) => vec![group.clone()],
+            _ => vec![],
+        };
+
+        Ok(OidcIdentity {
+            subject,
+            username,
+            nickname,
+            avatar_uri: claim("picture"),
+            groups,
+        })
+    }
+}
+
+/// The algorithm an ID token signed with `jwk` has to use. It's the one the
+/// key is published for, or when the provider leaves that out, one of the
+/// asymmetric algorithms of its key type. The token header only gets to
+/// pick among those.
+fn signing_algorithm(jwk: &Jwk, header_alg: Algorithm) -> Result<Algorithm, OidcError> {
+    let allowed = match (jwk.common.key_algorithm, &jwk.algorithm) {
+        (Some(key_alg), _) => key_alg
+            .to_string()
+            .parse::<Algorithm>()
+            .map(|alg| vec![alg])
+            .map_err(|_| OidcError::InvalidIdToken(format!("signing key is for {}", key_alg)))?,
+        (None, AlgorithmParameters::RSA(_)) => vec![
+            Algorithm::RS256, Algorithm::RS384, Algorithm::RS512,
+            Algorithm::PS256, Algorithm::PS384, Algorithm::PS512,
+        ],
+        (None, AlgorithmParameters::EllipticCurve(params)) => match params.curve {
+            EllipticCurve::P256 => vec![Algorithm::ES256],
+            EllipticCurve::P384 => vec![Algorithm::ES384],
+            _ => vec![],
+        },
+        (None, AlgorithmParameters::OctetKeyPair(_)) => vec![Algorithm::EdDSA],
+        (None, AlgorithmParameters::OctetKey(_)) => vec![],
+    };
+
+    match allowed.contains(&header_alg) {
+        true => Ok(header_alg),
+        false => Err(OidcError::InvalidIdToken(format!(
+            "token algorithm {:?} doesn't match the signing key", header_alg
+        ))),
+    }
+}
+
+/// The ID token has to carry the nonce of the login it answers.
+fn check_nonce(claims: &Map<String, Value>, nonce: &str) -> Result<(), OidcError> {
+    match claims.get("nonce").and_then(Value::as_str) == Some(nonce) {
+        true => Ok(()),
+        false => Err(OidcError::InvalidIdToken("nonce mismatch".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn client() -> OidcClient {
+        OidcClient::new(OidcConfig {
+            issuer: "https://idp.example.com".to_string(),
+            client_id: "datarepo".to_string(),
+            client_secret: None,
+            redirect_uri: "https://datarepo.example.com/auth/oidc/callback".to_string(),
+            scopes: "openid".to_string(),
+            groups_claim: "groups".to_string(),
+            post_login_redirect: None,
+        })
+    }
+
+    fn claims(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(claims) => claims,
+            _ => unreachable!(),
+        }
+    }
+
+    fn rsa_jwk(alg: Option<&str>) -> Jwk {
+        let mut jwk = json!({ "kty": "RSA", "kid": "k1", "n": "AQAB", "e": "AQAB" });
+        if let Some(alg) = alg {
+            jwk["alg"] = json!(alg);
+        }
+        serde_json::from_value(jwk).unwrap()
+    }
+
+    #[test]
+    fn groups_claim_may_be_an_array_or_a_string() {
+        let identity = client()
+            .identity_of(claims(json!({ "sub": "s1", "groups": ["admins", 7, "devs"] })))
+            .unwrap();
+        assert_eq!(identity.groups, vec!["admins", "devs"]);
+
+        let identity = client()
+            .identity_of(claims(json!({ "sub": "s1", "groups": "admins" })))
+            .unwrap();
+        assert_eq!(identity.groups, vec!["admins"]);
+
+        let identity = client()
+            .identity_of(claims(json!({ "sub": "s1" })))
+            .unwrap();
+        assert!(identity.groups.is_empty());
+    }
+
+    #[test]
+    fn username_falls_back_to_email_then_subject() {
+        let identity = client()
+            .identity_of(claims(json!({
+                "sub": "s1",
+                "preferred_username": "alice",
+                "email": "alice@example.com",
+                "name": "Alice",
+            })))
+            .unwrap();
+        assert_eq!(identity.subject, "s1");
+        assert_eq!(identity.username, "alice");
+        assert_eq!(identity.nickname, "Alice");
+
+        let identity = client()
+            .identity_of(claims(json!({
+                "sub": "s1",
+                "preferred_username": "",
+                "email": "alice@example.com",
+            })))
+            .unwrap();
+        assert_eq!(identity.username, "alice@example.com");
+        assert_eq!(identity.nickname, "alice@example.com");
+
+        let identity = client()
+            .identity_of(claims(json!({ "sub": "s1" })))
+            .unwrap();
+        assert_eq!(identity.username, "s1");
+    }
+
+    #[test]
+    fn identity_requires_a_subject() {
+        for claims in [claims(json!({ "email": "alice@example.com" })), claims(json!({ "sub": "" }))] {
+            assert!(matches!(
+                client().identity_of(claims),
+                Err(OidcError::InvalidIdToken(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn nonce_must_match() {
+        assert!(check_nonce(&claims(json!({ "nonce": "n1" })), "n1").is_ok());
+        assert!(matches!(
+            check_nonce(&claims(json!({ "nonce": "n2" })), "n1"),
+            Err(OidcError::InvalidIdToken(_))
+        ));
+        assert!(matches!(
+            check_nonce(&claims(json!({})), "n1"),
+            Err(OidcError::InvalidIdToken(_))
+        ));
+    }
+
+    #[test]
+    fn algorithm_comes_from_the_signing_key() {
+        let jwk = rsa_jwk(Some("RS256"));
+        assert_eq!(signing_algorithm(&jwk, Algorithm::RS256).unwrap(), Algorithm::RS256);
+        assert!(signing_algorithm(&jwk, Algorithm::RS512).is_err());
+        assert!(signing_algorithm(&jwk, Algorithm::HS256).is_err());
+    }
+
+    #[test]
+    fn algorithm_without_key_alg_follows_the_key_type() {
+        let jwk = rsa_jwk(None);
+        assert_eq!(signing_algorithm(&jwk, Algorithm::PS384).unwrap(), Algorithm::PS384);
+        assert!(signing_algorithm(&jwk, Algorithm::HS256).is_err());
+        assert!(signing_algorithm(&jwk, Algorithm::ES256).is_err());
+
+        let jwk = serde_json::from_value::<Jwk>(json!({ "kty": "oct", "k": "c2VjcmV0" })).unwrap();
+        assert!(signing_algorithm(&jwk, Algorithm::HS256).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::api_key::ApiKeyModel;
+use crate::infra::db::schema::{api_keys, api_keys_permissions_rel};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::{read::scopes_of, schema::ApiKeyDB};
+
+#[derive(Insertable)]
+#[diesel(table_name = api_keys)]
+pub struct NewApiKeyDB {
+    pub user_id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub hashed_secret: String,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// Creates a key scoped to the given permissions.
+pub async fn create(
+    db: &deadpool_diesel::postgres::Pool,
+    new_key: NewApiKeyDB,
+    permission_ids: Vec<i32>,
+) -> RepoResult<ApiKeyModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let key = diesel::insert_into(api_keys::table)
+                    .values(new_key)
+                    .returning(ApiKeyDB::as_returning())
+                    .get_result(conn)?;
+
+                let rels = permission_ids
+                    .into_iter()
+                    .map(|permission_id| (
+                        api_keys_permissions_rel::key_id.eq(key.id),
+                        api_keys_permissions_rel::permission_id.eq(permission_id),
+                    ))
+                    .collect::<Vec<_>>();
+
+                diesel::insert_into(api_keys_permissions_rel::table)
+                    .values(rels)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+
+                let scopes = scopes_of(conn, key.id)?;
+
+                Ok(key.into_model(scopes))
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
@@ -0,0 +1,28 @@
+use diesel::prelude::*;
+
+use crate::infra::db::schema::api_keys;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+pub async fn delete_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    key_id: i32,
+) -> RepoResult<()> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    conn
+        .interact(move |conn| {
+            diesel::delete(
+                api_keys::table
+                    .filter(api_keys::id.eq(key_id))
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(())
+}
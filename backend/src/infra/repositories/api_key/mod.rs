@@ -0,0 +1,22 @@
+pub mod create;
+pub mod delete;
+pub mod read;
+pub mod schema;
+pub mod update;
+
+pub use schema::ApiKeyDB;
+
+pub use create::{
+    NewApiKeyDB,
+    create,
+};
+
+pub use read::{
+    try_get_by_id,
+    try_get_by_prefix,
+    get_all_by_user_id,
+};
+
+pub use update::touch_by_id;
+
+pub use delete::delete_by_id;
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+
+use crate::domain::models::api_key::ApiKeyModel;
+use crate::infra::db::schema::{api_keys, api_keys_permissions_rel, permissions};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::ApiKeyDB;
+
+pub(super) fn scopes_of(
+    conn: &mut PgConnection,
+    key_id: i32,
+) -> QueryResult<Vec<String>> {
+    api_keys_permissions_rel::table
+        .inner_join(permissions::table)
+        .filter(api_keys_permissions_rel::key_id.eq(key_id))
+        .order(permissions::name.asc())
+        .select(permissions::name)
+        .load::<String>(conn)
+}
+
+pub async fn try_get_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    key_id: i32,
+) -> RepoResult<Option<ApiKeyModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let key = api_keys::table
+                .filter(api_keys::id.eq(key_id))
+                .select(ApiKeyDB::as_select())
+                .first(conn)?;
+            let scopes = scopes_of(conn, key.id)?;
+
+            Ok(key.into_model(scopes))
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res)),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+pub async fn try_get_by_prefix(
+    db: &deadpool_diesel::postgres::Pool,
+    prefix: String,
+) -> RepoResult<Option<ApiKeyModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let key = api_keys::table
+                .filter(api_keys::prefix.eq(prefix))
+                .select(ApiKeyDB::as_select())
+                .first(conn)?;
+            let scopes = scopes_of(conn, key.id)?;
+
+            Ok(key.into_model(scopes))
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res)),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+/// Every key of a user, oldest first.
+pub async fn get_all_by_user_id(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+) -> RepoResult<Vec<ApiKeyModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let keys = api_keys::table
+                .filter(api_keys::user_id.eq(user_id))
+                .order(api_keys::id.asc())
+                .select(ApiKeyDB::as_select())
+                .load::<ApiKeyDB>(conn)?;
+
+            let key_ids = keys.iter().map(|key| key.id).collect::<Vec<i32>>();
+            let scopes = api_keys_permissions_rel::table
+                .inner_join(permissions::table)
+                .filter(api_keys_permissions_rel::key_id.eq_any(key_ids))
+                .order(permissions::name.asc())
+                .select((api_keys_permissions_rel::key_id, permissions::name))
+                .load::<(i32, String)>(conn)?;
+
+            let mut scopes_per_key = HashMap::<i32, Vec<String>>::new();
+            for (key_id, scope) in scopes {
+                scopes_per_key.entry(key_id).or_default().push(scope);
+            }
+
+            Ok(keys
+                .into_iter()
+                .map(|key| {
+                    let scopes = scopes_per_key.remove(&key.id).unwrap_or_default();
+                    key.into_model(scopes)
+                })
+                .collect())
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
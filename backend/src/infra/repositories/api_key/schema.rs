@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::api_key::ApiKeyModel;
+use crate::infra::db::schema::api_keys;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = api_keys)]                // Use the 'api_keys' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct ApiKeyDB {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub hashed_secret: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ApiKeyDB {
+    pub fn into_model(self, scopes: Vec<String>) -> ApiKeyModel {
+        ApiKeyModel {
+            id: self.id,
+            user_id: self.user_id,
+            name: self.name,
+            prefix: self.prefix,
+            hashed_secret: self.hashed_secret,
+            scopes,
+            expires_at: self.expires_at,
+            last_used_at: self.last_used_at,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
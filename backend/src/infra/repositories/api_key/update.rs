@@ -0,0 +1,36 @@
+use diesel::prelude::*;
+
+use crate::infra::db::schema::api_keys;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+/// Records that a key was just used. Writes at most once a minute per key,
+/// so busy jobs don't turn every request into an update.
+pub async fn touch_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    key_id: i32,
+) -> RepoResult<()> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let cutoff = (chrono::Utc::now() - chrono::Duration::minutes(1)).naive_utc();
+    conn
+        .interact(move |conn| {
+            diesel::update(
+                api_keys::table
+                    .filter(api_keys::id.eq(key_id))
+                    .filter(
+                        api_keys::last_used_at.is_null()
+                            .or(api_keys::last_used_at.lt(cutoff))
+                    )
+            )
+            .set(api_keys::last_used_at.eq(diesel::dsl::now))
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(())
+}
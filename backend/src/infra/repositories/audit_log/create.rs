@@ -0,0 +1,40 @@
+use diesel::prelude::*;
+
+use crate::domain::models::audit_log::AuditLogModel;
+use crate::infra::db::schema::audit_logs;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::AuditLogDB;
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = audit_logs)]
+pub struct NewAuditLogDB {
+    pub user_id: Option<i32>,
+    pub method: String,
+    pub route: String,
+    pub target_id: Option<i32>,
+    pub body_digest: Option<String>,
+    pub status: i32,
+}
+
+pub async fn create(
+    db: &deadpool_diesel::postgres::Pool,
+    new_log: NewAuditLogDB,
+) -> RepoResult<AuditLogModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(|conn| {
+            diesel::insert_into(audit_logs::table)
+                .values(new_log)
+                .returning(AuditLogDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
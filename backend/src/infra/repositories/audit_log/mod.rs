@@ -0,0 +1,15 @@
+pub mod create;
+pub mod read;
+pub mod schema;
+
+pub use schema::AuditLogDB;
+
+pub use create::{
+    NewAuditLogDB,
+    create,
+};
+
+pub use read::{
+    AuditLogsFilter,
+    get_all,
+};
@@ -0,0 +1,98 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::Deserialize;
+
+use crate::domain::models::audit_log::AuditLogModel;
+use crate::infra::db::schema::audit_logs;
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
+    default_limit,
+};
+use super::schema::AuditLogDB;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogsFilter {
+    user_id: Option<i32>,
+    method: Option<String>,
+    route: Option<String>,
+    target_id: Option<i32>,
+    status: Option<i32>,
+    created_after: Option<NaiveDateTime>,
+    created_before: Option<NaiveDateTime>,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    with_total: Option<bool>,
+}
+
+/// Lists audit logs, newest first.
+pub async fn get_all(
+    db: &deadpool_diesel::postgres::Pool,
+    filter: AuditLogsFilter,
+) -> RepoResult<Page<AuditLogModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
+        .interact(move |conn| {
+            let filtered = || {
+                let mut query = audit_logs::table
+                    .into_boxed::<diesel::pg::Pg>();
+
+                if let Some(user_id) = filter.user_id {
+                    query = query.filter(audit_logs::user_id.eq(user_id));
+                }
+                if let Some(method) = &filter.method {
+                    query = query.filter(audit_logs::method.eq(method.to_uppercase()));
+                }
+                if let Some(route) = &filter.route {
+                    query = query.filter(audit_logs::route.eq(route.clone()));
+                }
+                if let Some(target_id) = filter.target_id {
+                    query = query.filter(audit_logs::target_id.eq(target_id));
+                }
+                if let Some(status) = filter.status {
+                    query = query.filter(audit_logs::status.eq(status));
+                }
+                if let Some(created_after) = filter.created_after {
+                    query = query.filter(audit_logs::created_at.ge(created_after));
+                }
+                if let Some(created_before) = filter.created_before {
+                    query = query.filter(audit_logs::created_at.lt(created_before));
+                }
+
+                query
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(audit_logs::id.lt(cursor.id));
+            }
+
+            query
+                .order(audit_logs::id.desc())
+                .limit(limit + 1)
+                .select(AuditLogDB::as_select())
+                .load::<AuditLogDB>(conn)
+                .map(|res| (res, total))
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    let logs = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
+
+    Ok(logs)
+}
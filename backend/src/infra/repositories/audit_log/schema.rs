@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::audit_log::AuditLogModel;
+use crate::infra::db::schema::audit_logs;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = audit_logs)]              // Use the 'audit_logs' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct AuditLogDB {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub method: String,
+    pub route: String,
+    pub target_id: Option<i32>,
+    pub body_digest: Option<String>,
+    pub status: i32,
+    pub created_at: NaiveDateTime,
+}
+
+impl Into<AuditLogModel> for AuditLogDB {
+    fn into(self) -> AuditLogModel {
+        AuditLogModel {
+            id: self.id,
+            user_id: self.user_id,
+            method: self.method,
+            route: self.route,
+            target_id: self.target_id,
+            body_digest: self.body_digest,
+            status: self.status,
+            created_at: self.created_at,
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use diesel::prelude::*;
 use serde::Deserialize;
 
-use crate::domain::models::dataset::DatasetModel;
-use crate::infra::db::schema::datasets;
+use crate::domain::models::{dataset::DatasetModel, ds_acl::DatasetRole};
+use crate::infra::db::schema::{datasets, ds_acls};
 use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
 use super::schema::DatasetDB;
 
@@ -11,6 +11,8 @@ use super::schema::DatasetDB;
 pub struct NewDatasetDB {
     pub name: String,
     pub description: String,
+    pub created_by: i32,
+    pub updated_by: i32,
 }
 
 pub async fn create(
@@ -35,3 +37,40 @@ pub async fn create(
 
     Ok(res.into())
 }
+
+/// Creates a dataset and makes `owner_id` its owner, in one transaction.
+pub async fn create_owned(
+    db: &deadpool_diesel::postgres::Pool,
+    new_ds: NewDatasetDB,
+    owner_id: i32,
+) -> RepoResult<DatasetModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let ds = diesel::insert_into(datasets::table)
+                    .values(new_ds)
+                    .returning(DatasetDB::as_returning())
+                    .get_result(conn)?;
+
+                diesel::insert_into(ds_acls::table)
+                    .values((
+                        ds_acls::ds_id.eq(ds.id),
+                        ds_acls::user_id.eq(owner_id),
+                        ds_acls::role.eq(DatasetRole::Owner.as_str()),
+                    ))
+                    .execute(conn)?;
+
+                Ok(ds)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
@@ -9,6 +9,7 @@ pub use schema::DatasetDB;
 pub use create::{
     NewDatasetDB,
     create,
+    create_owned,
 };
 
 pub use read::{
@@ -5,17 +5,24 @@ use crate::domain::models::dataset::DatasetModel;
 use crate::infra::db::schema::datasets;
 use crate::infra::repositories::{
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    ds_acl::visible_ds_ids,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
 };
 use super::schema::DatasetDB;
 
 #[derive(Debug, Deserialize)]
 pub struct DatasetsFilter {
-    #[serde(default = "default_skip")]
-    skip: i64,
+    created_by: Option<i32>,
+    /// Only rows visible to this user through dataset grants. Set by the
+    /// handlers, never read from the query string.
+    #[serde(skip)]
+    pub visible_to: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -96,29 +103,54 @@ pub async fn try_get_by_name(
 pub async fn get_all(
     db: &deadpool_diesel::postgres::Pool,
     filter: DatasetsFilter,
-) -> RepoResult<Vec<DatasetModel>> {
+) -> RepoResult<Page<DatasetModel>> {
     let conn = db
         .get()
         .await
         .map_err(RepoError::Pool)?;
 
-    let res = conn
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
         .interact(move |conn| {
-            datasets::table
-                .into_boxed::<diesel::pg::Pg>()
-                .offset(filter.skip)
-                .limit(filter.limit)
+            let filtered = || {
+                let mut query = datasets::table
+                    .into_boxed::<diesel::pg::Pg>();
+
+                if let Some(user_id) = filter.visible_to {
+                    query = query.filter(datasets::id.eq_any(visible_ds_ids(user_id)));
+                }
+
+                if let Some(created_by) = filter.created_by {
+                    query = query.filter(datasets::created_by.eq(created_by));
+                }
+
+                query
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(datasets::id.gt(cursor.id));
+            }
+
+            query
+                .order(datasets::id.asc())
+                .limit(limit + 1)
                 .select(DatasetDB::as_select())
                 .load::<DatasetDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let datasets: Vec<DatasetModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let datasets = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
 
     Ok(datasets)
 }
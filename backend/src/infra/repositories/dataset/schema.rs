@@ -13,6 +13,8 @@ pub struct DatasetDB {
     pub description: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
 }
 
 impl Into<DatasetModel> for DatasetDB {
@@ -23,6 +25,8 @@ impl Into<DatasetModel> for DatasetDB {
             description: self.description,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            created_by: self.created_by,
+            updated_by: self.updated_by,
         }
     }
 }
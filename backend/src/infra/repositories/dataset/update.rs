@@ -9,6 +9,7 @@ use super::schema::DatasetDB;
 #[diesel(table_name = datasets)]
 pub struct UpdatedDatasetDB {
     pub description: Option<String>,
+    pub updated_by: i32,
 }
 
 pub async fn update_by_id(
@@ -35,3 +35,33 @@ pub async fn create(
 
     Ok(res.into())
 }
+
+pub async fn create_many(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    item_ids: Vec<i32>,
+) -> RepoResult<Vec<DatasetItemModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let new_ds_items = item_ids
+        .into_iter()
+        .map(|item_id| NewDatasetItemDB { ds_id, item_id })
+        .collect::<Vec<NewDatasetItemDB>>();
+
+    let res = conn
+        .interact(|conn| {
+            diesel::insert_into(datasets_items_rel::table)
+                .values(new_ds_items)
+                .on_conflict_do_nothing()
+                .returning(DatasetItemDB::as_returning())
+                .get_results(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into_iter().map(Into::into).collect())
+}
@@ -28,3 +28,29 @@ pub async fn delete_by_id(
 
     Ok(())
 }
+
+pub async fn delete_by_ids(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    item_ids: Vec<i32>,
+) -> RepoResult<()> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    conn
+        .interact(move |conn| {
+            diesel::delete(
+                datasets_items_rel::table
+                    .filter(datasets_items_rel::ds_id.eq(ds_id))
+                    .filter(datasets_items_rel::item_id.eq_any(item_ids))
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ pub use schema::DatasetItemDB;
 pub use create::{
     NewDatasetItemDB,
     create,
+    create_many,
 };
 
 pub use read::{
@@ -17,4 +18,4 @@ pub use read::{
     get_all,
 };
 
-pub use delete::delete_by_id;
+pub use delete::{delete_by_id, delete_by_ids};
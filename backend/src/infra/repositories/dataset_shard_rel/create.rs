@@ -35,3 +35,33 @@ pub async fn create(
 
     Ok(res.into())
 }
+
+pub async fn create_many(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    shard_ids: Vec<i32>,
+) -> RepoResult<Vec<DatasetShardModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let new_ds_shards = shard_ids
+        .into_iter()
+        .map(|shard_id| NewDatasetShardDB { ds_id, shard_id })
+        .collect::<Vec<NewDatasetShardDB>>();
+
+    let res = conn
+        .interact(|conn| {
+            diesel::insert_into(datasets_shards_rel::table)
+                .values(new_ds_shards)
+                .on_conflict_do_nothing()
+                .returning(DatasetShardDB::as_returning())
+                .get_results(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into_iter().map(Into::into).collect())
+}
@@ -28,3 +28,29 @@ pub async fn delete_by_id(
 
     Ok(())
 }
+
+pub async fn delete_by_ids(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    shard_ids: Vec<i32>,
+) -> RepoResult<()> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    conn
+        .interact(move |conn| {
+            diesel::delete(
+                datasets_shards_rel::table
+                    .filter(datasets_shards_rel::ds_id.eq(ds_id))
+                    .filter(datasets_shards_rel::shard_id.eq_any(shard_ids))
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(())
+}
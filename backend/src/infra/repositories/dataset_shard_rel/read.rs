@@ -5,7 +5,7 @@ use crate::domain::models::dataset_shard::DatasetShardModel;
 use crate::infra::db::schema::datasets_shards_rel;
 use crate::infra::repositories::{
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
 };
 use super::schema::DatasetShardDB;
@@ -14,10 +14,11 @@ use super::schema::DatasetShardDB;
 pub struct DatasetsShardsFilter {
     ds_id: Option<i32>,
     shard_id: Option<i32>,
-    #[serde(default = "default_skip")]
-    skip: i64,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -76,39 +77,58 @@ pub async fn try_get_by_id(
 pub async fn get_all(
     db: &deadpool_diesel::postgres::Pool,
     filter: DatasetsShardsFilter,
-) -> RepoResult<Vec<DatasetShardModel>> {
+) -> RepoResult<Page<DatasetShardModel>> {
     let conn = db
         .get()
         .await
         .map_err(RepoError::Pool)?;
 
-    let res = conn
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
         .interact(move |conn| {
-            let mut query = datasets_shards_rel::table
-                .into_boxed::<diesel::pg::Pg>();
+            let filtered = || {
+                let mut query = datasets_shards_rel::table
+                    .into_boxed::<diesel::pg::Pg>();
 
-            if let Some(ds_id) = filter.ds_id {
-                query = query.filter(datasets_shards_rel::ds_id.eq(ds_id));
-            }
+                if let Some(ds_id) = filter.ds_id {
+                    query = query.filter(datasets_shards_rel::ds_id.eq(ds_id));
+                }
+
+                if let Some(shard_id) = filter.shard_id {
+                    query = query.filter(datasets_shards_rel::shard_id.eq(shard_id));
+                }
+
+                query
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
 
-            if let Some(shard_id) = filter.shard_id {
-                query = query.filter(datasets_shards_rel::shard_id.eq(shard_id));
+            if let Some(cursor) = &filter.cursor {
+                let rel_id = cursor.rel_id.unwrap_or_default();
+                query = query.filter(
+                    datasets_shards_rel::ds_id.gt(cursor.id)
+                        .or(datasets_shards_rel::ds_id.eq(cursor.id).and(datasets_shards_rel::shard_id.gt(rel_id)))
+                );
             }
 
             query
-                .offset(filter.skip)
-                .limit(filter.limit)
+                .order((datasets_shards_rel::ds_id.asc(), datasets_shards_rel::shard_id.asc()))
+                .limit(limit + 1)
                 .select(DatasetShardDB::as_select())
                 .load::<DatasetShardDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let datasets_items: Vec<DatasetShardModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let datasets_shards = Page::from_rows(res, limit, total, |row| Cursor::from_ids(row.ds_id, row.shard_id))
+        .map(Into::into);
 
-    Ok(datasets_items)
+    Ok(datasets_shards)
 }
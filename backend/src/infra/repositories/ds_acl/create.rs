@@ -0,0 +1,55 @@
+use diesel::prelude::*;
+
+use crate::domain::models::ds_acl::DatasetAclModel;
+use crate::infra::db::schema::ds_acls;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::DatasetAclDB;
+
+#[derive(Insertable)]
+#[diesel(table_name = ds_acls)]
+pub struct NewDatasetAclDB {
+    pub ds_id: i32,
+    pub user_id: Option<i32>,
+    pub group_id: Option<i32>,
+    pub role: String,
+}
+
+/// Grants a role on a dataset, replacing the role the same user or group
+/// already had there.
+pub async fn grant(
+    db: &deadpool_diesel::postgres::Pool,
+    new_acl: NewDatasetAclDB,
+) -> RepoResult<DatasetAclModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let role = new_acl.role.clone();
+            let query = diesel::insert_into(ds_acls::table)
+                .values(&new_acl);
+
+            if new_acl.user_id.is_some() {
+                query
+                    .on_conflict((ds_acls::ds_id, ds_acls::user_id))
+                    .do_update()
+                    .set(ds_acls::role.eq(role))
+                    .returning(DatasetAclDB::as_returning())
+                    .get_result(conn)
+            } else {
+                query
+                    .on_conflict((ds_acls::ds_id, ds_acls::group_id))
+                    .do_update()
+                    .set(ds_acls::role.eq(role))
+                    .returning(DatasetAclDB::as_returning())
+                    .get_result(conn)
+            }
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
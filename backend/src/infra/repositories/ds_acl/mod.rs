@@ -0,0 +1,34 @@
+pub mod create;
+pub mod delete;
+pub mod read;
+pub mod schema;
+pub mod visible;
+
+pub use schema::DatasetAclDB;
+
+pub use create::{
+    NewDatasetAclDB,
+    grant,
+};
+
+pub use read::{
+    DatasetAclsFilter,
+    try_get_by_id,
+    try_get_by_grantee,
+    try_get_role,
+    get_item_roles,
+    get_shard_roles,
+    count_owners,
+    get_all,
+};
+
+pub use visible::{
+    visible_ds_ids,
+    item_visible_to,
+    item_editable_by,
+    shard_visible_to,
+    count_visible_items,
+    count_visible_shards,
+};
+
+pub use delete::delete_by_id;
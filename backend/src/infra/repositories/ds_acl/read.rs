@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+use serde::Deserialize;
+
+use crate::domain::models::ds_acl::{DatasetAclModel, DatasetRole};
+use crate::infra::db::schema::{datasets_items_rel, datasets_shards_rel, ds_acls};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
+    default_limit,
+};
+use super::{schema::DatasetAclDB, visible::granted_to};
+
+#[derive(Debug, Deserialize)]
+pub struct DatasetAclsFilter {
+    pub ds_id: Option<i32>,
+    user_id: Option<i32>,
+    group_id: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    with_total: Option<bool>,
+}
+
+/// Roles of the user on every dataset of `ds_ids`, the highest of their own
+/// grant and the grants of their groups, `None` where they hold no role.
+fn roles_on(
+    conn: &mut PgConnection,
+    user_id: i32,
+    ds_ids: Vec<i32>,
+) -> QueryResult<Vec<Option<DatasetRole>>> {
+    let grants = ds_acls::table
+        .filter(ds_acls::ds_id.eq_any(ds_ids.clone()))
+        .filter(granted_to(user_id))
+        .select(DatasetAclDB::as_select())
+        .load::<DatasetAclDB>(conn)?;
+
+    let mut roles = HashMap::<i32, DatasetRole>::new();
+    for grant in grants {
+        let grant: DatasetAclModel = grant.into();
+        let role = roles.entry(grant.ds_id).or_insert(grant.role);
+        *role = (*role).max(grant.role);
+    }
+
+    Ok(ds_ids
+        .into_iter()
+        .map(|ds_id| roles.get(&ds_id).copied())
+        .collect())
+}
+
+pub async fn try_get_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    acl_id: i32,
+) -> RepoResult<Option<DatasetAclModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            ds_acls::table
+                .filter(ds_acls::id.eq(acl_id))
+                .select(DatasetAclDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+/// The grant of a user, or of a group, on a dataset.
+pub async fn try_get_by_grantee(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    user_id: Option<i32>,
+    group_id: Option<i32>,
+) -> RepoResult<Option<DatasetAclModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let mut query = ds_acls::table
+                .filter(ds_acls::ds_id.eq(ds_id))
+                .into_boxed::<diesel::pg::Pg>();
+
+            query = match (user_id, group_id) {
+                (Some(user_id), _) => query.filter(ds_acls::user_id.eq(user_id)),
+                (None, Some(group_id)) => query.filter(ds_acls::group_id.eq(group_id)),
+                (None, None) => return Err(diesel::NotFound),
+            };
+
+            query
+                .select(DatasetAclDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+/// Role of the user on a dataset.
+pub async fn try_get_role(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    ds_id: i32,
+) -> RepoResult<Option<DatasetRole>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| roles_on(conn, user_id, vec![ds_id]))
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into_iter().flatten().next())
+}
+
+/// Role of the user on each dataset the item is linked to.
+pub async fn get_item_roles(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    item_id: i32,
+) -> RepoResult<Vec<Option<DatasetRole>>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let ds_ids = datasets_items_rel::table
+                .filter(datasets_items_rel::item_id.eq(item_id))
+                .select(datasets_items_rel::ds_id)
+                .load::<i32>(conn)?;
+
+            roles_on(conn, user_id, ds_ids)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
+
+/// Role of the user on each dataset the shard is linked to.
+pub async fn get_shard_roles(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    shard_id: i32,
+) -> RepoResult<Vec<Option<DatasetRole>>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let ds_ids = datasets_shards_rel::table
+                .filter(datasets_shards_rel::shard_id.eq(shard_id))
+                .select(datasets_shards_rel::ds_id)
+                .load::<i32>(conn)?;
+
+            roles_on(conn, user_id, ds_ids)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
+
+pub async fn count_owners(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+) -> RepoResult<i64> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            ds_acls::table
+                .filter(ds_acls::ds_id.eq(ds_id))
+                .filter(ds_acls::role.eq(DatasetRole::Owner.as_str()))
+                .count()
+                .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
+
+pub async fn get_all(
+    db: &deadpool_diesel::postgres::Pool,
+    filter: DatasetAclsFilter,
+) -> RepoResult<Page<DatasetAclModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
+        .interact(move |conn| {
+            let filtered = || {
+                let mut query = ds_acls::table
+                    .into_boxed::<diesel::pg::Pg>();
+
+                if let Some(ds_id) = filter.ds_id {
+                    query = query.filter(ds_acls::ds_id.eq(ds_id));
+                }
+                if let Some(user_id) = filter.user_id {
+                    query = query.filter(ds_acls::user_id.eq(user_id));
+                }
+                if let Some(group_id) = filter.group_id {
+                    query = query.filter(ds_acls::group_id.eq(group_id));
+                }
+
+                query
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(ds_acls::id.gt(cursor.id));
+            }
+
+            query
+                .order(ds_acls::id.asc())
+                .limit(limit + 1)
+                .select(DatasetAclDB::as_select())
+                .load::<DatasetAclDB>(conn)
+                .map(|res| (res, total))
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    let acls = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
+
+    Ok(acls)
+}
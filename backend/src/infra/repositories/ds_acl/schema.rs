@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::ds_acl::{DatasetAclModel, DatasetRole};
+use crate::infra::db::schema::ds_acls;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = ds_acls)]                 // Use the 'ds_acls' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct DatasetAclDB {
+    pub id: i32,
+    pub ds_id: i32,
+    pub user_id: Option<i32>,
+    pub group_id: Option<i32>,
+    pub role: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Into<DatasetAclModel> for DatasetAclDB {
+    fn into(self) -> DatasetAclModel {
+        DatasetAclModel {
+            id: self.id,
+            ds_id: self.ds_id,
+            user_id: self.user_id,
+            group_id: self.group_id,
+            // The column is constrained to known roles, fall back to the
+            // weakest one all the same.
+            role: self.role.parse().unwrap_or(DatasetRole::Viewer),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
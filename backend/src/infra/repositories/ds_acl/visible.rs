@@ -0,0 +1,142 @@
+use diesel::{
+    pg::Pg,
+    prelude::*,
+    sql_types::{Bool, Integer, Nullable},
+};
+
+use crate::domain::models::ds_acl::DatasetRole;
+use crate::infra::db::schema::{
+    datasets_items_rel,
+    datasets_shards_rel,
+    ds_acls,
+    ds_items,
+    ds_shards,
+    users_groups_rel,
+};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+/// Grants given to the user directly or to one of their groups.
+pub(super) fn granted_to(
+    user_id: i32,
+) -> Box<dyn BoxableExpression<ds_acls::table, Pg, SqlType = Nullable<Bool>>> {
+    Box::new(
+        ds_acls::user_id.eq(user_id).or(ds_acls::group_id.eq_any(
+            users_groups_rel::table
+                .filter(users_groups_rel::user_id.eq(user_id))
+                .select(users_groups_rel::group_id.nullable())
+        ))
+    )
+}
+
+/// Subquery of the ids of datasets the user holds any role on.
+pub fn visible_ds_ids(user_id: i32) -> ds_acls::BoxedQuery<'static, Pg, Integer> {
+    ds_acls::table
+        .filter(granted_to(user_id))
+        .select(ds_acls::ds_id)
+        .into_boxed()
+}
+
+/// Items linked to a dataset the user can see. Items not linked to any
+/// dataset are only visible to the user who created them.
+pub fn item_visible_to(
+    user_id: i32,
+) -> Box<dyn BoxableExpression<ds_items::table, Pg, SqlType = Bool>> {
+    Box::new(
+        ds_items::created_by.is_not_distinct_from(user_id)
+            .and(ds_items::id.ne_all(datasets_items_rel::table.select(datasets_items_rel::item_id)))
+            .or(ds_items::id.eq_any(
+                datasets_items_rel::table
+                    .filter(datasets_items_rel::ds_id.eq_any(visible_ds_ids(user_id)))
+                    .select(datasets_items_rel::item_id)
+            ))
+    )
+}
+
+/// Items the user can change: linked only to datasets they hold at least
+/// the editor role on, or to none at all and created by them.
+pub fn item_editable_by(
+    user_id: i32,
+) -> Box<dyn BoxableExpression<ds_items::table, Pg, SqlType = Bool>> {
+    let editable_ds_ids = ds_acls::table
+        .filter(granted_to(user_id))
+        .filter(ds_acls::role.eq_any([DatasetRole::Editor.as_str(), DatasetRole::Owner.as_str()]))
+        .select(ds_acls::ds_id)
+        .into_boxed();
+
+    Box::new(
+        ds_items::created_by.is_not_distinct_from(user_id)
+            .and(ds_items::id.ne_all(datasets_items_rel::table.select(datasets_items_rel::item_id)))
+            .or(ds_items::id.eq_any(datasets_items_rel::table.select(datasets_items_rel::item_id))
+                .and(ds_items::id.ne_all(
+                    datasets_items_rel::table
+                        .filter(datasets_items_rel::ds_id.ne_all(editable_ds_ids))
+                        .select(datasets_items_rel::item_id)
+                )))
+    )
+}
+
+/// Shards linked to a dataset the user can see, or to none at all and
+/// created by the user.
+pub fn shard_visible_to(
+    user_id: i32,
+) -> Box<dyn BoxableExpression<ds_shards::table, Pg, SqlType = Bool>> {
+    Box::new(
+        ds_shards::created_by.is_not_distinct_from(user_id)
+            .and(ds_shards::id.ne_all(datasets_shards_rel::table.select(datasets_shards_rel::shard_id)))
+            .or(ds_shards::id.eq_any(
+                datasets_shards_rel::table
+                    .filter(datasets_shards_rel::ds_id.eq_any(visible_ds_ids(user_id)))
+                    .select(datasets_shards_rel::shard_id)
+            ))
+    )
+}
+
+pub async fn count_visible_items(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    item_ids: Vec<i32>,
+) -> RepoResult<i64> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            ds_items::table
+                .filter(ds_items::id.eq_any(item_ids))
+                .filter(item_visible_to(user_id))
+                .count()
+                .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
+
+pub async fn count_visible_shards(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    shard_ids: Vec<i32>,
+) -> RepoResult<i64> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            ds_shards::table
+                .filter(ds_shards::id.eq_any(shard_ids))
+                .filter(shard_visible_to(user_id))
+                .count()
+                .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
@@ -2,7 +2,7 @@ use diesel::prelude::*;
 use serde::Deserialize;
 
 use crate::domain::models::ds_item::DatasetItemModel;
-use crate::infra::db::schema::ds_items;
+use crate::infra::db::schema::{ds_items, datasets_items_rel};
 use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
 use super::schema::DatasetItemDB;
 
@@ -11,6 +11,8 @@ use super::schema::DatasetItemDB;
 pub struct NewDatasetItemDB {
     pub typ: String,
     pub uri: String,
+    pub created_by: i32,
+    pub updated_by: i32,
 }
 
 pub async fn create(
@@ -35,3 +37,38 @@ pub async fn create(
 
     Ok(res.into())
 }
+
+pub async fn create_in_dataset(
+    db: &deadpool_diesel::postgres::Pool,
+    new_item: NewDatasetItemDB,
+    ds_id: i32,
+) -> RepoResult<DatasetItemModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let item = diesel::insert_into(ds_items::table)
+                    .values(new_item)
+                    .returning(DatasetItemDB::as_returning())
+                    .get_result(conn)?;
+
+                diesel::insert_into(datasets_items_rel::table)
+                    .values((
+                        datasets_items_rel::ds_id.eq(ds_id),
+                        datasets_items_rel::item_id.eq(item.id),
+                    ))
+                    .execute(conn)?;
+
+                Ok(item)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use diesel::prelude::*;
+
+use crate::domain::models::{
+    ds_item::DatasetItemModel,
+    ds_item_anno::DatasetItemAnnoModel,
+};
+use crate::infra::db::schema::{
+    ds_items,
+    ds_item_annos,
+    datasets_items_rel,
+    ds_version_items,
+    ds_version_item_annos,
+};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    ds_item_anno::DatasetItemAnnoDB,
+};
+use super::schema::{DatasetItemDB, VERSION_ITEM_COLUMNS};
+
+/// Loads the next `limit` items linked to a dataset, ordered by id and
+/// starting after `after_id`, together with all of their annotations. With a
+/// `version`, the items and annotations frozen in that version are read
+/// instead.
+pub async fn get_export_page(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    version: Option<i32>,
+    after_id: Option<i32>,
+    limit: i64,
+) -> RepoResult<Vec<(DatasetItemModel, Vec<DatasetItemAnnoModel>)>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let (items, annos) = conn
+        .interact(move |conn| {
+            let items = match version {
+                Some(version) => ds_version_items::table
+                    .filter(ds_version_items::version_id.eq(version))
+                    .filter(ds_version_items::item_id.gt(after_id.unwrap_or(0)))
+                    .order(ds_version_items::item_id.asc())
+                    .limit(limit)
+                    .select(VERSION_ITEM_COLUMNS)
+                    .load::<DatasetItemDB>(conn)?,
+                None => ds_items::table
+                    .filter(ds_items::id.eq_any(
+                        datasets_items_rel::table
+                            .filter(datasets_items_rel::ds_id.eq(ds_id))
+                            .select(datasets_items_rel::item_id)
+                    ))
+                    .filter(ds_items::id.gt(after_id.unwrap_or(0)))
+                    .order(ds_items::id.asc())
+                    .limit(limit)
+                    .select(DatasetItemDB::as_select())
+                    .load::<DatasetItemDB>(conn)?,
+            };
+
+            let item_ids = items
+                .iter()
+                .map(|item| item.id)
+                .collect::<Vec<i32>>();
+
+            let annos = match version {
+                Some(version) => ds_version_item_annos::table
+                    .filter(ds_version_item_annos::version_id.eq(version))
+                    .filter(ds_version_item_annos::item_id.eq_any(item_ids))
+                    .order(ds_version_item_annos::anno_id.asc())
+                    .select((
+                        ds_version_item_annos::anno_id,
+                        ds_version_item_annos::item_id,
+                        ds_version_item_annos::name,
+                        ds_version_item_annos::typ,
+                        ds_version_item_annos::uri,
+                        ds_version_item_annos::number,
+                        ds_version_item_annos::text,
+                        ds_version_item_annos::created_at,
+                        ds_version_item_annos::updated_at,
+                        ds_version_item_annos::created_by,
+                        ds_version_item_annos::updated_by,
+                    ))
+                    .load::<DatasetItemAnnoDB>(conn)?,
+                None => ds_item_annos::table
+                    .filter(ds_item_annos::item_id.eq_any(item_ids))
+                    .order(ds_item_annos::id.asc())
+                    .select(DatasetItemAnnoDB::as_select())
+                    .load::<DatasetItemAnnoDB>(conn)?,
+            };
+
+            Ok((items, annos))
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    let mut annos_per_item = HashMap::<i32, Vec<DatasetItemAnnoModel>>::new();
+    for anno in annos {
+        annos_per_item
+            .entry(anno.item_id)
+            .or_default()
+            .push(anno.into());
+    }
+
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let annos = annos_per_item.remove(&item.id).unwrap_or_default();
+            (item.into(), annos)
+        })
+        .collect())
+}
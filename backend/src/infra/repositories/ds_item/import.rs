@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+
+use diesel::prelude::*;
+
+use crate::infra::db::schema::{ds_items, ds_item_annos, datasets_items_rel};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    ds_acl::item_editable_by,
+    dataset_item_rel::NewDatasetItemDB as NewDatasetItemRelDB,
+    ds_item_anno::NewDatasetItemAnnoDB,
+};
+use super::create::NewDatasetItemDB;
+
+// Postgres caps a single statement at 65535 bind parameters, and every
+// annotation row takes eight of them.
+const ANNOS_CHUNK_SIZE: usize = 5000;
+
+pub struct ImportedItemAnnoDB {
+    pub name: String,
+    pub typ: String,
+    pub uri: Option<String>,
+    pub number: Option<f64>,
+    pub text: Option<String>,
+}
+
+pub struct ImportedItemDB {
+    pub typ: String,
+    pub uri: String,
+    pub annos: Vec<ImportedItemAnnoDB>,
+}
+
+pub struct ImportedBatchDB {
+    /// Number of distinct items written
+    pub written: usize,
+    /// Uris of existing items left untouched because the user can't edit them
+    pub refused: HashSet<String>,
+}
+
+/// Upserts a batch of items by uri, upserts their annotations by item and
+/// name and links the items to the dataset, all in one transaction, on
+/// behalf of `user_id`. Importing the same batch again changes nothing but
+/// the authors. With `editable_by`, existing items are only written when
+/// that user can edit them, see `ds_acl::item_editable_by`, and the others
+/// are returned as refused.
+pub async fn import_into_dataset(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    items: Vec<ImportedItemDB>,
+    user_id: i32,
+    editable_by: Option<i32>,
+) -> RepoResult<ImportedBatchDB> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    // The same uri may show up several times in one batch, but an upsert
+    // can't touch a row twice, so merge them first (last typ and last
+    // annotation of a name win).
+    let mut new_items = Vec::<NewDatasetItemDB>::new();
+    let mut index_per_uri = HashMap::<String, usize>::new();
+    let mut annos_per_uri = HashMap::<String, Vec<ImportedItemAnnoDB>>::new();
+    for item in items {
+        match index_per_uri.get(&item.uri) {
+            Some(&index) => new_items[index].typ = item.typ,
+            None => {
+                index_per_uri.insert(item.uri.clone(), new_items.len());
+                new_items.push(NewDatasetItemDB {
+                    typ: item.typ,
+                    uri: item.uri.clone(),
+                    created_by: user_id,
+                    updated_by: user_id,
+                });
+            }
+        }
+
+        let annos = annos_per_uri.entry(item.uri).or_default();
+        for anno in item.annos {
+            match annos.iter_mut().find(|merged| merged.name == anno.name) {
+                Some(merged) => *merged = anno,
+                None => annos.push(anno),
+            }
+        }
+    }
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                // New uris are inserted, then existing items are updated one
+                // type at a time, leaving out those the user can't edit.
+                let mut upserted = diesel::insert_into(ds_items::table)
+                    .values(&new_items)
+                    .on_conflict_do_nothing()
+                    .returning((ds_items::id, ds_items::uri))
+                    .get_results::<(i32, String)>(conn)?;
+
+                let inserted_uris = upserted
+                    .iter()
+                    .map(|(_, uri)| uri.as_str())
+                    .collect::<HashSet<&str>>();
+                let existing_uris = new_items
+                    .iter()
+                    .map(|item| item.uri.clone())
+                    .filter(|uri| !inserted_uris.contains(uri.as_str()))
+                    .collect::<Vec<String>>();
+
+                let mut query = ds_items::table
+                    .filter(ds_items::uri.eq_any(&existing_uris))
+                    .select((ds_items::id, ds_items::uri))
+                    .into_boxed();
+                if let Some(user_id) = editable_by {
+                    query = query.filter(item_editable_by(user_id));
+                }
+                let editable = query.load::<(i32, String)>(conn)?;
+
+                let mut ids_per_typ = HashMap::<&str, Vec<i32>>::new();
+                for (item_id, uri) in editable.iter() {
+                    let typ = &new_items[index_per_uri[uri]].typ;
+                    ids_per_typ.entry(typ).or_default().push(*item_id);
+                }
+                for (typ, item_ids) in ids_per_typ {
+                    diesel::update(ds_items::table.filter(ds_items::id.eq_any(item_ids)))
+                        .set((
+                            ds_items::typ.eq(typ),
+                            ds_items::updated_by.eq(user_id),
+                        ))
+                        .execute(conn)?;
+                }
+
+                let editable_uris = editable
+                    .iter()
+                    .map(|(_, uri)| uri.as_str())
+                    .collect::<HashSet<&str>>();
+                let refused = existing_uris
+                    .iter()
+                    .filter(|uri| !editable_uris.contains(uri.as_str()))
+                    .cloned()
+                    .collect::<HashSet<String>>();
+                upserted.extend(editable);
+
+                let item_ids = upserted
+                    .iter()
+                    .map(|(item_id, _)| *item_id)
+                    .collect::<Vec<i32>>();
+                let existing_annos = ds_item_annos::table
+                    .filter(ds_item_annos::item_id.eq_any(&item_ids))
+                    .select((ds_item_annos::item_id, ds_item_annos::name))
+                    .load::<(i32, String)>(conn)?
+                    .into_iter()
+                    .collect::<HashSet<(i32, String)>>();
+
+                let mut new_annos = Vec::<NewDatasetItemAnnoDB>::new();
+                let mut new_rels = Vec::<NewDatasetItemRelDB>::new();
+                for (item_id, uri) in upserted.iter() {
+                    new_rels.push(NewDatasetItemRelDB {
+                        ds_id,
+                        item_id: *item_id,
+                    });
+
+                    for anno in annos_per_uri.remove(uri).unwrap_or_default() {
+                        if existing_annos.contains(&(*item_id, anno.name.clone())) {
+                            diesel::update(
+                                ds_item_annos::table
+                                    .filter(ds_item_annos::item_id.eq(item_id))
+                                    .filter(ds_item_annos::name.eq(&anno.name))
+                            )
+                            .set((
+                                ds_item_annos::typ.eq(anno.typ),
+                                ds_item_annos::uri.eq(anno.uri),
+                                ds_item_annos::number.eq(anno.number),
+                                ds_item_annos::text.eq(anno.text),
+                                ds_item_annos::updated_by.eq(user_id),
+                            ))
+                            .execute(conn)?;
+                            continue;
+                        }
+
+                        new_annos.push(NewDatasetItemAnnoDB {
+                            item_id: *item_id,
+                            name: anno.name,
+                            typ: anno.typ,
+                            uri: anno.uri,
+                            number: anno.number,
+                            text: anno.text,
+                            created_by: user_id,
+                            updated_by: user_id,
+                        });
+                    }
+                }
+
+                for chunk in new_annos.chunks(ANNOS_CHUNK_SIZE) {
+                    diesel::insert_into(ds_item_annos::table)
+                        .values(chunk)
+                        .execute(conn)?;
+                }
+
+                diesel::insert_into(datasets_items_rel::table)
+                    .values(&new_rels)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+
+                Ok(ImportedBatchDB {
+                    written: upserted.len(),
+                    refused,
+                })
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
@@ -1,5 +1,7 @@
 pub mod create;
 pub mod delete;
+pub mod export;
+pub mod import;
 pub mod read;
 pub mod schema;
 pub mod update;
@@ -9,14 +11,26 @@ pub use schema::DatasetItemDB;
 pub use create::{
     NewDatasetItemDB,
     create,
+    create_in_dataset,
+};
+
+pub use export::get_export_page;
+
+pub use import::{
+    ImportedBatchDB,
+    ImportedItemDB,
+    ImportedItemAnnoDB,
+    import_into_dataset,
 };
 
 pub use read::{
     DatasetItemsFilter,
+    DatasetItemsOrderBy,
     get_by_id,
     try_get_by_id,
     try_get_by_uri,
     get_all,
+    get_by_ids,
 };
 
 pub use update::{
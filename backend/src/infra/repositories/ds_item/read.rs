@@ -1,22 +1,101 @@
+use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use serde::Deserialize;
 
 use crate::domain::models::ds_item::DatasetItemModel;
-use crate::infra::db::schema::{ds_items, datasets, datasets_items_rel};
+use crate::infra::db::schema::{
+    ds_items,
+    ds_item_annos,
+    datasets_items_rel,
+    ds_version_items,
+    ds_version_item_annos,
+};
 use crate::infra::repositories::{
+    ds_acl::item_visible_to,
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, CursorValue, Page, SortOrder, clamp_limit, deserialize_cursor},
     default_limit,
 };
-use super::schema::DatasetItemDB;
+use super::schema::{DatasetItemDB, VERSION_ITEM_COLUMNS};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetItemsOrderBy {
+    #[default]
+    Id,
+    Typ,
+    Uri,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl DatasetItemsOrderBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Typ => "typ",
+            Self::Uri => "uri",
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+        }
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct DatasetItemsFilter {
-    ds_id: Option<i32>,
-    #[serde(default = "default_skip")]
-    skip: i64,
+    pub ds_id: Option<i32>,
+    /// Lists the items frozen in this dataset version instead of the current
+    /// members of `ds_id`.
+    pub version: Option<i32>,
+    /// Only rows visible to this user through dataset grants. Set by the
+    /// handlers, never read from the query string.
+    #[serde(skip)]
+    pub visible_to: Option<i32>,
+    typ: Option<String>,
+    created_by: Option<i32>,
+    uri_prefix: Option<String>,
+    created_after: Option<NaiveDateTime>,
+    created_before: Option<NaiveDateTime>,
+    updated_after: Option<NaiveDateTime>,
+    updated_before: Option<NaiveDateTime>,
+    anno_name: Option<String>,
+    anno_number_min: Option<f64>,
+    anno_number_max: Option<f64>,
+    anno_text: Option<String>,
+    #[serde(default)]
+    order_by: DatasetItemsOrderBy,
+    #[serde(default)]
+    order: SortOrder,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
+}
+
+impl DatasetItemsFilter {
+    /// Whether the cursor, if any, was issued for the current `order_by`
+    /// and `order`. Following one issued for another sort would skip or
+    /// repeat rows.
+    pub fn cursor_matches_order(&self) -> bool {
+        let Some(cursor) = &self.cursor else {
+            return true;
+        };
+
+        if cursor.order_by.as_deref() != Some(self.order_by.as_str()) || cursor.order != Some(self.order) {
+            return false;
+        }
+
+        match self.order_by {
+            DatasetItemsOrderBy::Id => cursor.value.is_none(),
+            DatasetItemsOrderBy::Typ | DatasetItemsOrderBy::Uri => {
+                matches!(cursor.value, Some(CursorValue::Text(_)))
+            }
+            DatasetItemsOrderBy::CreatedAt | DatasetItemsOrderBy::UpdatedAt => {
+                matches!(cursor.value, Some(CursorValue::Timestamp(_)))
+            }
+        }
+    }
 }
 
 pub async fn get_by_id(
@@ -94,9 +173,9 @@ pub async fn try_get_by_uri(
     }
 }
 
-pub async fn get_all(
+pub async fn get_by_ids(
     db: &deadpool_diesel::postgres::Pool,
-    filter: DatasetItemsFilter,
+    item_ids: Vec<i32>,
 ) -> RepoResult<Vec<DatasetItemModel>> {
     let conn = db
         .get()
@@ -105,33 +184,307 @@ pub async fn get_all(
 
     let res = conn
         .interact(move |conn| {
-            if let Some(ds_id) = filter.ds_id {
-                ds_items::table
-                    .inner_join(datasets_items_rel::table)
-                    .inner_join(datasets::table.on(
-                        datasets::id.eq(datasets_items_rel::ds_id)
-                    ))
-                    .filter(datasets::id.eq(ds_id))
-                    .offset(filter.skip)
-                    .limit(filter.limit)
-                    .select(DatasetItemDB::as_select())
-                    .load::<DatasetItemDB>(conn)
-            } else {
-                ds_items::table
-                    .offset(filter.skip)
-                    .limit(filter.limit)
-                    .select(DatasetItemDB::as_select())
-                    .load::<DatasetItemDB>(conn)
+            ds_items::table
+                .filter(ds_items::id.eq_any(item_ids))
+                .select(DatasetItemDB::as_select())
+                .load::<DatasetItemDB>(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into_iter().map(Into::into).collect())
+}
+
+/// Escapes the `LIKE` wildcards of a user supplied prefix.
+fn like_prefix(prefix: &str) -> String {
+    let mut pattern = prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    pattern.push('%');
+    pattern
+}
+
+/// Builds the `item_id` subquery of the annotations in `$annos` (either
+/// `ds_item_annos` or the frozen `ds_version_item_annos`) matching every
+/// `anno_*` predicate of the filter.
+macro_rules! annos_matching {
+    ($annos:ident, $filter:expr) => {{
+        let mut annos = $annos::table
+            .select($annos::item_id)
+            .into_boxed::<diesel::pg::Pg>();
+
+        if let Some(anno_name) = &$filter.anno_name {
+            annos = annos.filter($annos::name.eq(anno_name.clone()));
+        }
+
+        if let Some(anno_number_min) = $filter.anno_number_min {
+            annos = annos.filter($annos::number.ge(anno_number_min));
+        }
+
+        if let Some(anno_number_max) = $filter.anno_number_max {
+            annos = annos.filter($annos::number.le(anno_number_max));
+        }
+
+        if let Some(anno_text) = &$filter.anno_text {
+            annos = annos.filter($annos::text.eq(anno_text.clone()));
+        }
+
+        annos
+    }};
+}
+
+/// Narrows a boxed query on `$items`, either `ds_items` or the frozen
+/// `ds_version_items`, to the rows matching the item column predicates of the
+/// filter.
+macro_rules! items_matching {
+    ($query:ident, $items:ident, $filter:expr) => {{
+        if let Some(typ) = &$filter.typ {
+            $query = $query.filter($items::typ.eq(typ.clone()));
+        }
+
+        if let Some(created_by) = $filter.created_by {
+            $query = $query.filter($items::created_by.eq(created_by));
+        }
+
+        if let Some(uri_prefix) = &$filter.uri_prefix {
+            $query = $query.filter($items::uri.like(like_prefix(uri_prefix)));
+        }
+
+        if let Some(created_after) = $filter.created_after {
+            $query = $query.filter($items::created_at.ge(created_after));
+        }
+
+        if let Some(created_before) = $filter.created_before {
+            $query = $query.filter($items::created_at.lt(created_before));
+        }
+
+        if let Some(updated_after) = $filter.updated_after {
+            $query = $query.filter($items::updated_at.ge(updated_after));
+        }
+
+        if let Some(updated_before) = $filter.updated_before {
+            $query = $query.filter($items::updated_at.lt(updated_before));
+        }
+
+        $query
+    }};
+}
+
+/// Sorts a boxed query on `$column` with the item id `$id` as tie breaker,
+/// and resumes after `$after`, the `(value, id)` of the previous page's last
+/// row.
+macro_rules! order_by_column {
+    ($query:ident, $column:expr, $id:expr, $after:expr, $order:expr) => {
+        match $order {
+            SortOrder::Asc => {
+                if let Some((value, id)) = $after {
+                    $query = $query.filter(
+                        $column.gt(value.clone())
+                            .or($column.eq(value).and($id.gt(id)))
+                    );
+                }
+                $query.order(($column.asc(), $id.asc()))
             }
+            SortOrder::Desc => {
+                if let Some((value, id)) = $after {
+                    $query = $query.filter(
+                        $column.lt(value.clone())
+                            .or($column.eq(value).and($id.lt(id)))
+                    );
+                }
+                $query.order(($column.desc(), $id.desc()))
+            }
+        }
+    };
+}
+
+/// Counts the rows of `$filtered` if asked to, then loads the page of them
+/// following the cursor of the filter as `DatasetItemDB`, selecting
+/// `$select`. `$items` and `$id` are the table and item id column queried.
+macro_rules! load_items_page {
+    ($conn:ident, $filtered:ident, $items:ident, $id:expr, $select:expr, $filter:ident, $limit:expr) => {{
+        let total = match $filter.with_total {
+            Some(true) => Some($filtered().count().get_result::<i64>($conn)?),
+            _ => None,
+        };
+
+        let mut query = $filtered();
+        let cursor = $filter.cursor;
+        let text_after = |cursor: Option<Cursor>| cursor.and_then(|cursor| match cursor.value {
+            Some(CursorValue::Text(value)) => Some((value, cursor.id)),
+            _ => None,
+        });
+        let timestamp_after = |cursor: Option<Cursor>| cursor.and_then(|cursor| match cursor.value {
+            Some(CursorValue::Timestamp(value)) => Some((value, cursor.id)),
+            _ => None,
+        });
+
+        let query = match $filter.order_by {
+            DatasetItemsOrderBy::Id => match $filter.order {
+                SortOrder::Asc => {
+                    if let Some(cursor) = cursor {
+                        query = query.filter($id.gt(cursor.id));
+                    }
+                    query.order($id.asc())
+                }
+                SortOrder::Desc => {
+                    if let Some(cursor) = cursor {
+                        query = query.filter($id.lt(cursor.id));
+                    }
+                    query.order($id.desc())
+                }
+            },
+            DatasetItemsOrderBy::Typ => {
+                order_by_column!(query, $items::typ, $id, text_after(cursor), $filter.order)
+            }
+            DatasetItemsOrderBy::Uri => {
+                order_by_column!(query, $items::uri, $id, text_after(cursor), $filter.order)
+            }
+            DatasetItemsOrderBy::CreatedAt => {
+                order_by_column!(query, $items::created_at, $id, timestamp_after(cursor), $filter.order)
+            }
+            DatasetItemsOrderBy::UpdatedAt => {
+                order_by_column!(query, $items::updated_at, $id, timestamp_after(cursor), $filter.order)
+            }
+        };
+
+        query
+            .limit($limit + 1)
+            .select($select)
+            .load::<DatasetItemDB>($conn)
+            .map(|res| (res, total))
+    }};
+}
+
+pub async fn get_all(
+    db: &deadpool_diesel::postgres::Pool,
+    filter: DatasetItemsFilter,
+) -> RepoResult<Page<DatasetItemModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let limit = clamp_limit(filter.limit);
+    let order_by = filter.order_by;
+    let order = filter.order;
+    let (res, total) = conn
+        .interact(move |conn| {
+            // All annotation predicates must hold for the same annotation.
+            let has_anno_filter = filter.anno_name.is_some()
+                || filter.anno_number_min.is_some()
+                || filter.anno_number_max.is_some()
+                || filter.anno_text.is_some();
+
+            // A version lists the copies of the items it froze.
+            if let Some(version) = filter.version {
+                let filtered = || {
+                    let mut query = ds_version_items::table
+                        .filter(ds_version_items::version_id.eq(version))
+                        .into_boxed::<diesel::pg::Pg>();
+                    query = items_matching!(query, ds_version_items, filter);
+
+                    if has_anno_filter {
+                        let annos = annos_matching!(ds_version_item_annos, filter)
+                            .filter(ds_version_item_annos::version_id.eq(version));
+                        query = query.filter(ds_version_items::item_id.eq_any(annos));
+                    }
+
+                    query
+                };
+
+                return load_items_page!(
+                    conn, filtered, ds_version_items, ds_version_items::item_id, VERSION_ITEM_COLUMNS,
+                    filter, limit
+                );
+            }
+
+            let filtered = || {
+                let mut query = ds_items::table
+                    .into_boxed::<diesel::pg::Pg>();
+
+                if let Some(ds_id) = filter.ds_id {
+                    query = query.filter(ds_items::id.eq_any(
+                        datasets_items_rel::table
+                            .filter(datasets_items_rel::ds_id.eq(ds_id))
+                            .select(datasets_items_rel::item_id)
+                    ));
+                }
+
+                if let Some(user_id) = filter.visible_to {
+                    query = query.filter(item_visible_to(user_id));
+                }
+
+                query = items_matching!(query, ds_items, filter);
+
+                if has_anno_filter {
+                    let annos = annos_matching!(ds_item_annos, filter);
+                    query = query.filter(ds_items::id.eq_any(annos));
+                }
+
+                query
+            };
+
+            load_items_page!(conn, filtered, ds_items, ds_items::id, DatasetItemDB::as_select(), filter, limit)
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let items: Vec<DatasetItemModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let items = Page::from_rows(res, limit, total, |row| match order_by {
+        DatasetItemsOrderBy::Id => Cursor::from_id(row.id),
+        DatasetItemsOrderBy::Typ => Cursor::from_value(row.id, CursorValue::Text(row.typ.clone())),
+        DatasetItemsOrderBy::Uri => Cursor::from_value(row.id, CursorValue::Text(row.uri.clone())),
+        DatasetItemsOrderBy::CreatedAt => Cursor::from_value(row.id, CursorValue::Timestamp(row.created_at)),
+        DatasetItemsOrderBy::UpdatedAt => Cursor::from_value(row.id, CursorValue::Timestamp(row.updated_at)),
+    }
+        .sorted(order_by.as_str(), order))
+        .map(Into::into);
 
     Ok(items)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(order_by: &str, order: &str, cursor: &Cursor) -> DatasetItemsFilter {
+        serde_json::from_value(serde_json::json!({
+            "order_by": order_by,
+            "order": order,
+            "cursor": cursor.encode(),
+        }))
+            .unwrap()
+    }
+
+    #[test]
+    fn cursors_are_followed_in_the_sort_they_were_issued_for() {
+        let text = Cursor::from_value(1, CursorValue::Text("a".to_string()));
+        let created_at = Cursor::from_value(1, CursorValue::Timestamp(NaiveDateTime::default()));
+
+        assert!(filter("id", "asc", &Cursor::from_id(1).sorted("id", SortOrder::Asc)).cursor_matches_order());
+        assert!(filter("uri", "desc", &text.clone().sorted("uri", SortOrder::Desc)).cursor_matches_order());
+        assert!(filter("created_at", "asc", &created_at.clone().sorted("created_at", SortOrder::Asc))
+            .cursor_matches_order());
+
+        let no_cursor: DatasetItemsFilter = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(no_cursor.cursor_matches_order());
+    }
+
+    #[test]
+    fn cursors_of_another_sort_are_refused() {
+        let typ = Cursor::from_value(1, CursorValue::Text("a".to_string())).sorted("typ", SortOrder::Asc);
+
+        // Same kind of value, other column
+        assert!(!filter("uri", "asc", &typ).cursor_matches_order());
+        // Same column, other direction
+        assert!(!filter("typ", "desc", &typ).cursor_matches_order());
+        // Issued before cursors recorded their sort
+        assert!(!filter("id", "asc", &Cursor::from_id(1)).cursor_matches_order());
+        // Sort forged to match, value of another kind
+        let forged = Cursor::from_value(1, CursorValue::Text("a".to_string())).sorted("created_at", SortOrder::Asc);
+        assert!(!filter("created_at", "asc", &forged).cursor_matches_order());
+    }
+}
@@ -2,7 +2,7 @@ use chrono::NaiveDateTime;
 use diesel::prelude::*;
 
 use crate::domain::models::ds_item::DatasetItemModel;
-use crate::infra::db::schema::ds_items;
+use crate::infra::db::schema::{ds_items, ds_version_items};
 
 #[derive(Queryable, Selectable, Identifiable)]
 #[diesel(table_name = ds_items)]                // Use the 'ds_items' table
@@ -13,8 +13,29 @@ pub struct DatasetItemDB {
     pub uri: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
 }
 
+/// Columns of a frozen item in the order of [`DatasetItemDB`].
+pub const VERSION_ITEM_COLUMNS: (
+    ds_version_items::item_id,
+    ds_version_items::typ,
+    ds_version_items::uri,
+    ds_version_items::created_at,
+    ds_version_items::updated_at,
+    ds_version_items::created_by,
+    ds_version_items::updated_by,
+) = (
+    ds_version_items::item_id,
+    ds_version_items::typ,
+    ds_version_items::uri,
+    ds_version_items::created_at,
+    ds_version_items::updated_at,
+    ds_version_items::created_by,
+    ds_version_items::updated_by,
+);
+
 impl Into<DatasetItemModel> for DatasetItemDB {
     fn into(self) -> DatasetItemModel {
         DatasetItemModel {
@@ -23,6 +44,8 @@ impl Into<DatasetItemModel> for DatasetItemDB {
             uri: self.uri,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            created_by: self.created_by,
+            updated_by: self.updated_by,
         }
     }
 }
@@ -10,6 +10,7 @@ use super::schema::DatasetItemDB;
 pub struct UpdatedDatasetItemDB {
     pub typ: Option<String>,
     pub uri: Option<String>,
+    pub updated_by: i32,
 }
 
 pub async fn update_by_id(
@@ -9,11 +9,14 @@ use super::schema::DatasetItemAnnoDB;
 #[derive(Deserialize, Insertable)]
 #[diesel(table_name = ds_item_annos)]
 pub struct NewDatasetItemAnnoDB {
+    pub item_id: i32,
     pub name: String,
     pub typ: String,
     pub uri: Option<String>,
     pub number: Option<f64>,
     pub text: Option<String>,
+    pub created_by: i32,
+    pub updated_by: i32,
 }
 
 pub async fn create(
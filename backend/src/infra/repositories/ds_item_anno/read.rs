@@ -2,23 +2,26 @@ use diesel::prelude::*;
 use serde::Deserialize;
 
 use crate::domain::models::ds_item_anno::DatasetItemAnnoModel;
-use crate::infra::db::schema::{ds_item_annos, datasets_items_rel, datasets};
+use crate::infra::db::schema::{ds_item_annos, datasets_items_rel};
 use crate::infra::repositories::{
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
 };
 use super::schema::DatasetItemAnnoDB;
 
 #[derive(Debug, Deserialize)]
 pub struct DatasetItemAnnosFilter {
-    ds_id: Option<i32>,
-    item_id: Option<i32>,
-    typ: Option<String>,
-    #[serde(default = "default_skip")]
-    skip: i64,
+    pub ds_id: Option<i32>,
+    pub item_id: Option<i32>,
+    pub typ: Option<String>,
+    pub name: Option<String>,
+    pub created_by: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    pub cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
-    limit: i64,
+    pub limit: i64,
+    pub with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -73,54 +76,70 @@ pub async fn try_get_by_id(
 pub async fn get_all(
     db: &deadpool_diesel::postgres::Pool,
     filter: DatasetItemAnnosFilter,
-) -> RepoResult<Vec<DatasetItemAnnoModel>> {
+) -> RepoResult<Page<DatasetItemAnnoModel>> {
     let conn = db
         .get()
         .await
         .map_err(RepoError::Pool)?;
 
-    let res = conn
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
         .interact(move |conn| {
-            let mut query = ds_item_annos::table
-                .into_boxed::<diesel::pg::Pg>();
+            let filtered = || {
+                let mut query = ds_item_annos::table
+                    .into_boxed::<diesel::pg::Pg>();
 
-            if let Some(item_id) = filter.item_id {
-                query = query.filter(ds_item_annos::item_id.eq(item_id));
-            }
+                if let Some(item_id) = filter.item_id {
+                    query = query.filter(ds_item_annos::item_id.eq(item_id));
+                }
 
-            if let Some(typ) = filter.typ {
-                query = query.filter(ds_item_annos::typ.eq(typ));
-            }
+                if let Some(typ) = &filter.typ {
+                    query = query.filter(ds_item_annos::typ.eq(typ.clone()));
+                }
+
+                if let Some(name) = &filter.name {
+                    query = query.filter(ds_item_annos::name.eq(name.clone()));
+                }
+
+                if let Some(created_by) = filter.created_by {
+                    query = query.filter(ds_item_annos::created_by.eq(created_by));
+                }
+
+                if let Some(ds_id) = filter.ds_id {
+                    query = query.filter(ds_item_annos::item_id.eq_any(
+                        datasets_items_rel::table
+                            .filter(datasets_items_rel::ds_id.eq(ds_id))
+                            .select(datasets_items_rel::item_id)
+                    ));
+                }
 
-            if let Some(ds_id) = filter.ds_id {
-                query
-                    .inner_join(datasets_items_rel::table.on(
-                        datasets_items_rel::item_id.eq(ds_item_annos::item_id)
-                    ))
-                    .inner_join(datasets::table.on(
-                        datasets::id.eq(datasets_items_rel::ds_id)
-                    ))
-                    .filter(datasets::id.eq(ds_id))
-                    .offset(filter.skip)
-                    .limit(filter.limit)
-                    .select(DatasetItemAnnoDB::as_select())
-                    .load::<DatasetItemAnnoDB>(conn)
-            } else {
                 query
-                    .offset(filter.skip)
-                    .limit(filter.limit)
-                    .select(DatasetItemAnnoDB::as_select())
-                    .load::<DatasetItemAnnoDB>(conn)
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(ds_item_annos::id.gt(cursor.id));
             }
+
+            query
+                .order(ds_item_annos::id.asc())
+                .limit(limit + 1)
+                .select(DatasetItemAnnoDB::as_select())
+                .load::<DatasetItemAnnoDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let annos: Vec<DatasetItemAnnoModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let annos = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
 
     Ok(annos)
 }
@@ -17,6 +17,8 @@ pub struct DatasetItemAnnoDB {
     pub text: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
 }
 
 impl Into<DatasetItemAnnoModel> for DatasetItemAnnoDB {
@@ -31,6 +33,8 @@ impl Into<DatasetItemAnnoModel> for DatasetItemAnnoDB {
             text: self.text,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            created_by: self.created_by,
+            updated_by: self.updated_by,
         }
     }
 }
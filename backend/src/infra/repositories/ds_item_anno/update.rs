@@ -5,14 +5,17 @@ use crate::infra::db::schema::ds_item_annos;
 use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
 use super::schema::DatasetItemAnnoDB;
 
+/// Fields left `None` are left alone, nullable ones set to `Some(None)` are
+/// cleared.
 #[derive(AsChangeset)]
 #[diesel(table_name = ds_item_annos)]
 pub struct UpdatedDatasetItemAnnoDB {
-    pub name: String,
-    pub typ: String,
-    pub uri: Option<String>,
-    pub number: Option<f64>,
-    pub text: Option<String>,
+    pub name: Option<String>,
+    pub typ: Option<String>,
+    pub uri: Option<Option<String>>,
+    pub number: Option<Option<f64>>,
+    pub text: Option<Option<String>>,
+    pub updated_by: i32,
 }
 
 pub async fn update_by_id(
@@ -10,6 +10,8 @@ use super::schema::DatasetShardDB;
 #[diesel(table_name = ds_shards)]
 pub struct NewDatasetShardDB {
     pub uri: String,
+    pub created_by: i32,
+    pub updated_by: i32,
 }
 
 pub async fn create(
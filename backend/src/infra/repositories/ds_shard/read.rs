@@ -2,21 +2,31 @@ use diesel::prelude::*;
 use serde::Deserialize;
 
 use crate::domain::models::ds_shard::DatasetShardModel;
-use crate::infra::db::schema::{ds_shards, datasets_shards_rel, datasets};
+use crate::infra::db::schema::{ds_shards, datasets_shards_rel, ds_version_shards};
 use crate::infra::repositories::{
+    ds_acl::shard_visible_to,
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
 };
-use super::schema::DatasetShardDB;
+use super::schema::{DatasetShardDB, VERSION_SHARD_COLUMNS};
 
 #[derive(Debug, Deserialize)]
 pub struct DatasetShardsFilter {
-    ds_id: Option<i32>,
-    #[serde(default = "default_skip")]
-    skip: i64,
+    pub ds_id: Option<i32>,
+    /// Lists the shards frozen in this dataset version instead of the current
+    /// members of `ds_id`.
+    pub version: Option<i32>,
+    /// Only rows visible to this user through dataset grants. Set by the
+    /// handlers, never read from the query string.
+    #[serde(skip)]
+    pub visible_to: Option<i32>,
+    created_by: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -94,9 +104,9 @@ pub async fn try_get_by_uri(
     }
 }
 
-pub async fn get_all(
+pub async fn get_by_ids(
     db: &deadpool_diesel::postgres::Pool,
-    filter: DatasetShardsFilter,
+    shard_ids: Vec<i32>,
 ) -> RepoResult<Vec<DatasetShardModel>> {
     let conn = db
         .get()
@@ -105,33 +115,102 @@ pub async fn get_all(
 
     let res = conn
         .interact(move |conn| {
-            if let Some(ds_id) = filter.ds_id {
-                ds_shards::table
-                    .inner_join(datasets_shards_rel::table)
-                    .inner_join(datasets::table.on(
-                        datasets::id.eq(datasets_shards_rel::ds_id)
-                    ))
-                    .filter(datasets::id.eq(ds_id))
-                    .offset(filter.skip)
-                    .limit(filter.limit)
-                    .select(DatasetShardDB::as_select())
-                    .load::<DatasetShardDB>(conn)
-            } else {
-                ds_shards::table
-                    .offset(filter.skip)
-                    .limit(filter.limit)
-                    .select(DatasetShardDB::as_select())
+            ds_shards::table
+                .filter(ds_shards::id.eq_any(shard_ids))
+                .select(DatasetShardDB::as_select())
+                .load::<DatasetShardDB>(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into_iter().map(Into::into).collect())
+}
+
+pub async fn get_all(
+    db: &deadpool_diesel::postgres::Pool,
+    filter: DatasetShardsFilter,
+) -> RepoResult<Page<DatasetShardModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
+        .interact(move |conn| {
+            let cursor_id = filter.cursor.as_ref().map_or(0, |cursor| cursor.id);
+
+            // A version lists the copies of the shards it froze.
+            if let Some(version) = filter.version {
+                let filtered = || {
+                    let mut query = ds_version_shards::table
+                        .filter(ds_version_shards::version_id.eq(version))
+                        .into_boxed::<diesel::pg::Pg>();
+
+                    if let Some(created_by) = filter.created_by {
+                        query = query.filter(ds_version_shards::created_by.eq(created_by));
+                    }
+
+                    query
+                };
+
+                let total = match filter.with_total {
+                    Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                    _ => None,
+                };
+
+                return filtered()
+                    .filter(ds_version_shards::shard_id.gt(cursor_id))
+                    .order(ds_version_shards::shard_id.asc())
+                    .limit(limit + 1)
+                    .select(VERSION_SHARD_COLUMNS)
                     .load::<DatasetShardDB>(conn)
+                    .map(|res| (res, total));
             }
+
+            let filtered = || {
+                let mut query = ds_shards::table
+                    .into_boxed::<diesel::pg::Pg>();
+
+                if let Some(ds_id) = filter.ds_id {
+                    query = query.filter(ds_shards::id.eq_any(
+                        datasets_shards_rel::table
+                            .filter(datasets_shards_rel::ds_id.eq(ds_id))
+                            .select(datasets_shards_rel::shard_id)
+                    ));
+                }
+
+                if let Some(user_id) = filter.visible_to {
+                    query = query.filter(shard_visible_to(user_id));
+                }
+
+                if let Some(created_by) = filter.created_by {
+                    query = query.filter(ds_shards::created_by.eq(created_by));
+                }
+
+                query
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            filtered()
+                .filter(ds_shards::id.gt(cursor_id))
+                .order(ds_shards::id.asc())
+                .limit(limit + 1)
+                .select(DatasetShardDB::as_select())
+                .load::<DatasetShardDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let shards: Vec<DatasetShardModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let shards = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
 
     Ok(shards)
 }
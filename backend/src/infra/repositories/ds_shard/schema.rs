@@ -2,7 +2,7 @@ use chrono::NaiveDateTime;
 use diesel::prelude::*;
 
 use crate::domain::models::ds_shard::DatasetShardModel;
-use crate::infra::db::schema::ds_shards;
+use crate::infra::db::schema::{ds_shards, ds_version_shards};
 
 #[derive(Queryable, Selectable, Identifiable)]
 #[diesel(table_name = ds_shards)]               // Use the 'ds_shards' table
@@ -12,8 +12,27 @@ pub struct DatasetShardDB {
     pub uri: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
 }
 
+/// Columns of a frozen shard in the order of [`DatasetShardDB`].
+pub const VERSION_SHARD_COLUMNS: (
+    ds_version_shards::shard_id,
+    ds_version_shards::uri,
+    ds_version_shards::created_at,
+    ds_version_shards::updated_at,
+    ds_version_shards::created_by,
+    ds_version_shards::updated_by,
+) = (
+    ds_version_shards::shard_id,
+    ds_version_shards::uri,
+    ds_version_shards::created_at,
+    ds_version_shards::updated_at,
+    ds_version_shards::created_by,
+    ds_version_shards::updated_by,
+);
+
 impl Into<DatasetShardModel> for DatasetShardDB {
     fn into(self) -> DatasetShardModel {
         DatasetShardModel {
@@ -21,6 +40,8 @@ impl Into<DatasetShardModel> for DatasetShardDB {
             uri: self.uri,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            created_by: self.created_by,
+            updated_by: self.updated_by,
         }
     }
 }
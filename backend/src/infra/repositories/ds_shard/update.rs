@@ -9,6 +9,7 @@ use super::schema::DatasetShardDB;
 #[diesel(table_name = ds_shards)]
 pub struct UpdatedDatasetShardDB {
     pub uri: Option<String>,
+    pub updated_by: i32,
 }
 
 pub async fn update_by_id(
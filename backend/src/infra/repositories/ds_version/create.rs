@@ -0,0 +1,156 @@
+use diesel::prelude::*;
+use diesel::sql_types::Integer;
+
+use crate::domain::models::ds_version::DatasetVersionModel;
+use crate::infra::db::schema::{
+    datasets_items_rel,
+    datasets_shards_rel,
+    ds_item_annos,
+    ds_items,
+    ds_shards,
+    ds_version_item_annos,
+    ds_version_items,
+    ds_version_shards,
+    ds_versions,
+};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::DatasetVersionDB;
+
+#[derive(Insertable)]
+#[diesel(table_name = ds_versions)]
+pub struct NewDatasetVersionDB {
+    pub ds_id: i32,
+    pub tag: String,
+}
+
+/// Freezes a copy of the current items and shards of a dataset, along with
+/// the annotations of its items, into a new version.
+pub async fn create(
+    db: &deadpool_diesel::postgres::Pool,
+    new_version: NewDatasetVersionDB,
+) -> RepoResult<DatasetVersionModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            // Every statement has to see the same membership, even if items
+            // are attached or detached while the snapshot is being copied.
+            conn.build_transaction().repeatable_read().run(|conn| {
+                let ds_id = new_version.ds_id;
+                let version_id = diesel::insert_into(ds_versions::table)
+                    .values(&new_version)
+                    .returning(ds_versions::id)
+                    .get_result::<i32>(conn)?;
+                let version_id_sql = || version_id.into_sql::<Integer>();
+
+                let num_items = diesel::insert_into(ds_version_items::table)
+                    .values(
+                        datasets_items_rel::table
+                            .inner_join(ds_items::table)
+                            .filter(datasets_items_rel::ds_id.eq(ds_id))
+                            .select((
+                                version_id_sql(),
+                                ds_items::id,
+                                ds_items::typ,
+                                ds_items::uri,
+                                ds_items::created_at,
+                                ds_items::updated_at,
+                                ds_items::created_by,
+                                ds_items::updated_by,
+                            ))
+                    )
+                    .into_columns((
+                        ds_version_items::version_id,
+                        ds_version_items::item_id,
+                        ds_version_items::typ,
+                        ds_version_items::uri,
+                        ds_version_items::created_at,
+                        ds_version_items::updated_at,
+                        ds_version_items::created_by,
+                        ds_version_items::updated_by,
+                    ))
+                    .execute(conn)?;
+
+                let num_shards = diesel::insert_into(ds_version_shards::table)
+                    .values(
+                        datasets_shards_rel::table
+                            .inner_join(ds_shards::table)
+                            .filter(datasets_shards_rel::ds_id.eq(ds_id))
+                            .select((
+                                version_id_sql(),
+                                ds_shards::id,
+                                ds_shards::uri,
+                                ds_shards::created_at,
+                                ds_shards::updated_at,
+                                ds_shards::created_by,
+                                ds_shards::updated_by,
+                            ))
+                    )
+                    .into_columns((
+                        ds_version_shards::version_id,
+                        ds_version_shards::shard_id,
+                        ds_version_shards::uri,
+                        ds_version_shards::created_at,
+                        ds_version_shards::updated_at,
+                        ds_version_shards::created_by,
+                        ds_version_shards::updated_by,
+                    ))
+                    .execute(conn)?;
+
+                let num_annos = diesel::insert_into(ds_version_item_annos::table)
+                    .values(
+                        ds_item_annos::table
+                            .inner_join(datasets_items_rel::table.on(
+                                datasets_items_rel::item_id.eq(ds_item_annos::item_id)
+                            ))
+                            .filter(datasets_items_rel::ds_id.eq(ds_id))
+                            .select((
+                                version_id_sql(),
+                                ds_item_annos::id,
+                                ds_item_annos::item_id,
+                                ds_item_annos::name,
+                                ds_item_annos::typ,
+                                ds_item_annos::uri,
+                                ds_item_annos::number,
+                                ds_item_annos::text,
+                                ds_item_annos::created_at,
+                                ds_item_annos::updated_at,
+                                ds_item_annos::created_by,
+                                ds_item_annos::updated_by,
+                            ))
+                    )
+                    .into_columns((
+                        ds_version_item_annos::version_id,
+                        ds_version_item_annos::anno_id,
+                        ds_version_item_annos::item_id,
+                        ds_version_item_annos::name,
+                        ds_version_item_annos::typ,
+                        ds_version_item_annos::uri,
+                        ds_version_item_annos::number,
+                        ds_version_item_annos::text,
+                        ds_version_item_annos::created_at,
+                        ds_version_item_annos::updated_at,
+                        ds_version_item_annos::created_by,
+                        ds_version_item_annos::updated_by,
+                    ))
+                    .execute(conn)?;
+
+                diesel::update(ds_versions::table.filter(ds_versions::id.eq(version_id)))
+                    .set((
+                        ds_versions::num_items.eq(num_items as i32),
+                        ds_versions::num_shards.eq(num_shards as i32),
+                        ds_versions::num_annos.eq(num_annos as i32),
+                    ))
+                    .returning(DatasetVersionDB::as_returning())
+                    .get_result(conn)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
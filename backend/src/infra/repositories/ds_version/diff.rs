@@ -0,0 +1,315 @@
+use std::collections::{BTreeMap, HashMap};
+
+use diesel::prelude::*;
+
+use crate::domain::models::{
+    ds_item_anno::DatasetItemAnnoModel,
+    ds_version::{
+        DatasetDiffChange,
+        DatasetDiffKind,
+        DatasetDiffModel,
+        DatasetItemAnnoDiffModel,
+    },
+};
+use crate::infra::db::schema::{
+    datasets_items_rel,
+    datasets_shards_rel,
+    ds_item_annos,
+    ds_items,
+    ds_shards,
+    ds_version_item_annos,
+    ds_version_items,
+    ds_version_shards,
+};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    ds_item_anno::DatasetItemAnnoDB,
+    pagination::{Cursor, CursorValue},
+};
+
+/// Number of member ids read from each side per round while looking for
+/// differences.
+const DIFF_SCAN_SIZE: i64 = 1000;
+
+/// One side of a diff: a frozen version, or the current state of a dataset.
+#[derive(Debug, Clone, Copy)]
+pub enum DiffSide {
+    Version(i32),
+    Current(i32),
+}
+
+fn kind_of(cursor: &Cursor) -> DatasetDiffKind {
+    match &cursor.value {
+        Some(CursorValue::Text(kind)) if kind == "shard" => DatasetDiffKind::Shard,
+        _ => DatasetDiffKind::Item,
+    }
+}
+
+fn cursor_of(kind: DatasetDiffKind, id: i32) -> Cursor {
+    let kind = match kind {
+        DatasetDiffKind::Item => "item",
+        DatasetDiffKind::Shard => "shard",
+    };
+    Cursor::from_value(id, CursorValue::Text(kind.to_string()))
+}
+
+fn member_ids(
+    conn: &mut PgConnection,
+    side: DiffSide,
+    kind: DatasetDiffKind,
+    after_id: i32,
+) -> QueryResult<Vec<i32>> {
+    match (side, kind) {
+        (DiffSide::Version(version), DatasetDiffKind::Item) => ds_version_items::table
+            .filter(ds_version_items::version_id.eq(version))
+            .filter(ds_version_items::item_id.gt(after_id))
+            .order(ds_version_items::item_id.asc())
+            .limit(DIFF_SCAN_SIZE)
+            .select(ds_version_items::item_id)
+            .load(conn),
+        (DiffSide::Version(version), DatasetDiffKind::Shard) => ds_version_shards::table
+            .filter(ds_version_shards::version_id.eq(version))
+            .filter(ds_version_shards::shard_id.gt(after_id))
+            .order(ds_version_shards::shard_id.asc())
+            .limit(DIFF_SCAN_SIZE)
+            .select(ds_version_shards::shard_id)
+            .load(conn),
+        (DiffSide::Current(ds_id), DatasetDiffKind::Item) => datasets_items_rel::table
+            .filter(datasets_items_rel::ds_id.eq(ds_id))
+            .filter(datasets_items_rel::item_id.gt(after_id))
+            .order(datasets_items_rel::item_id.asc())
+            .limit(DIFF_SCAN_SIZE)
+            .select(datasets_items_rel::item_id)
+            .load(conn),
+        (DiffSide::Current(ds_id), DatasetDiffKind::Shard) => datasets_shards_rel::table
+            .filter(datasets_shards_rel::ds_id.eq(ds_id))
+            .filter(datasets_shards_rel::shard_id.gt(after_id))
+            .order(datasets_shards_rel::shard_id.asc())
+            .limit(DIFF_SCAN_SIZE)
+            .select(datasets_shards_rel::shard_id)
+            .load(conn),
+    }
+}
+
+/// `typ` and `uri` of the members on one side, the `typ` of shards empty.
+fn member_values(
+    conn: &mut PgConnection,
+    side: DiffSide,
+    kind: DatasetDiffKind,
+    ids: &[i32],
+) -> QueryResult<HashMap<i32, (String, String)>> {
+    let values = match (side, kind) {
+        (DiffSide::Version(version), DatasetDiffKind::Item) => ds_version_items::table
+            .filter(ds_version_items::version_id.eq(version))
+            .filter(ds_version_items::item_id.eq_any(ids))
+            .select((ds_version_items::item_id, ds_version_items::typ, ds_version_items::uri))
+            .load::<(i32, String, String)>(conn)?,
+        (DiffSide::Current(_), DatasetDiffKind::Item) => ds_items::table
+            .filter(ds_items::id.eq_any(ids))
+            .select((ds_items::id, ds_items::typ, ds_items::uri))
+            .load::<(i32, String, String)>(conn)?,
+        (DiffSide::Version(version), DatasetDiffKind::Shard) => ds_version_shards::table
+            .filter(ds_version_shards::version_id.eq(version))
+            .filter(ds_version_shards::shard_id.eq_any(ids))
+            .select((ds_version_shards::shard_id, ds_version_shards::uri))
+            .load::<(i32, String)>(conn)?
+            .into_iter()
+            .map(|(id, uri)| (id, String::new(), uri))
+            .collect(),
+        (DiffSide::Current(_), DatasetDiffKind::Shard) => ds_shards::table
+            .filter(ds_shards::id.eq_any(ids))
+            .select((ds_shards::id, ds_shards::uri))
+            .load::<(i32, String)>(conn)?
+            .into_iter()
+            .map(|(id, uri)| (id, String::new(), uri))
+            .collect(),
+    };
+
+    Ok(values.into_iter().map(|(id, typ, uri)| (id, (typ, uri))).collect())
+}
+
+fn annos_per_item(
+    conn: &mut PgConnection,
+    side: DiffSide,
+    item_ids: &[i32],
+) -> QueryResult<HashMap<i32, BTreeMap<i32, DatasetItemAnnoModel>>> {
+    let annos = match side {
+        DiffSide::Version(version) => ds_version_item_annos::table
+            .filter(ds_version_item_annos::version_id.eq(version))
+            .filter(ds_version_item_annos::item_id.eq_any(item_ids))
+            .select((
+                ds_version_item_annos::anno_id,
+                ds_version_item_annos::item_id,
+                ds_version_item_annos::name,
+                ds_version_item_annos::typ,
+                ds_version_item_annos::uri,
+                ds_version_item_annos::number,
+                ds_version_item_annos::text,
+                ds_version_item_annos::created_at,
+                ds_version_item_annos::updated_at,
+                ds_version_item_annos::created_by,
+                ds_version_item_annos::updated_by,
+            ))
+            .load::<DatasetItemAnnoDB>(conn)?,
+        DiffSide::Current(_) => ds_item_annos::table
+            .filter(ds_item_annos::item_id.eq_any(item_ids))
+            .select(DatasetItemAnnoDB::as_select())
+            .load::<DatasetItemAnnoDB>(conn)?,
+    };
+
+    let mut annos_per_item = HashMap::<i32, BTreeMap<i32, DatasetItemAnnoModel>>::new();
+    for anno in annos {
+        annos_per_item
+            .entry(anno.item_id)
+            .or_default()
+            .insert(anno.id, anno.into());
+    }
+
+    Ok(annos_per_item)
+}
+
+fn same_value(a: &DatasetItemAnnoModel, b: &DatasetItemAnnoModel) -> bool {
+    a.name == b.name
+        && a.typ == b.typ
+        && a.uri == b.uri
+        && a.number == b.number
+        && a.text == b.text
+}
+
+fn diff_annos(
+    mut from: BTreeMap<i32, DatasetItemAnnoModel>,
+    mut to: BTreeMap<i32, DatasetItemAnnoModel>,
+) -> Vec<DatasetItemAnnoDiffModel> {
+    let mut anno_ids = from.keys().chain(to.keys()).copied().collect::<Vec<i32>>();
+    anno_ids.sort_unstable();
+    anno_ids.dedup();
+
+    anno_ids
+        .into_iter()
+        .filter_map(|anno_id| {
+            let (from, to) = (from.remove(&anno_id), to.remove(&anno_id));
+            let change = match (&from, &to) {
+                (Some(from), Some(to)) if same_value(from, to) => return None,
+                (Some(_), Some(_)) => DatasetDiffChange::Modified,
+                (Some(_), None) => DatasetDiffChange::Removed,
+                (None, _) => DatasetDiffChange::Added,
+            };
+
+            Some(DatasetItemAnnoDiffModel { anno_id, change, from, to })
+        })
+        .collect()
+}
+
+/// Returns the next `limit` differences between two states of a dataset,
+/// items first and then shards, each ordered by id, along with the cursor of
+/// the following page if there is one.
+pub async fn get_diff_page(
+    db: &deadpool_diesel::postgres::Pool,
+    from: DiffSide,
+    to: DiffSide,
+    cursor: Option<Cursor>,
+    limit: i64,
+) -> RepoResult<(Vec<DatasetDiffModel>, Option<Cursor>)> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let (mut kind, mut after_id) = match &cursor {
+                Some(cursor) => (kind_of(cursor), cursor.id),
+                None => (DatasetDiffKind::Item, 0),
+            };
+            let mut diffs = Vec::<DatasetDiffModel>::new();
+
+            // Both sides are walked in id order. A round only looks at ids up
+            // to the smaller last id of a full read, since the other side may
+            // still have members beyond it.
+            while diffs.len() as i64 <= limit {
+                let mut from_ids = member_ids(conn, from, kind, after_id)?;
+                let mut to_ids = member_ids(conn, to, kind, after_id)?;
+                let bound = [&from_ids, &to_ids]
+                    .into_iter()
+                    .filter(|ids| ids.len() as i64 == DIFF_SCAN_SIZE)
+                    .filter_map(|ids| ids.last().copied())
+                    .min();
+
+                if let Some(bound) = bound {
+                    from_ids.retain(|id| *id <= bound);
+                    to_ids.retain(|id| *id <= bound);
+                }
+
+                let mut members = BTreeMap::<i32, (bool, bool)>::new();
+                for id in from_ids.iter() {
+                    members.entry(*id).or_default().0 = true;
+                }
+                for id in to_ids.iter() {
+                    members.entry(*id).or_default().1 = true;
+                }
+
+                let common_ids = members
+                    .iter()
+                    .filter(|(_, (in_from, in_to))| *in_from && *in_to)
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<i32>>();
+                let from_values = member_values(conn, from, kind, &common_ids)?;
+                let to_values = member_values(conn, to, kind, &common_ids)?;
+                let (mut from_annos, mut to_annos) = if kind == DatasetDiffKind::Item {
+                    (
+                        annos_per_item(conn, from, &common_ids)?,
+                        annos_per_item(conn, to, &common_ids)?,
+                    )
+                } else {
+                    (HashMap::new(), HashMap::new())
+                };
+
+                for (id, (in_from, in_to)) in members {
+                    let diff = match (in_from, in_to) {
+                        (true, false) => Some((DatasetDiffChange::Removed, vec![])),
+                        (false, true) => Some((DatasetDiffChange::Added, vec![])),
+                        _ => {
+                            let annos = diff_annos(
+                                from_annos.remove(&id).unwrap_or_default(),
+                                to_annos.remove(&id).unwrap_or_default(),
+                            );
+                            let modified = !annos.is_empty() || from_values.get(&id) != to_values.get(&id);
+                            modified.then_some((DatasetDiffChange::Modified, annos))
+                        }
+                    };
+
+                    if let Some((change, annos)) = diff {
+                        diffs.push(DatasetDiffModel { kind, id, change, annos });
+                        if diffs.len() as i64 > limit {
+                            break;
+                        }
+                    }
+                }
+
+                match bound {
+                    Some(bound) => after_id = bound,
+                    None if kind == DatasetDiffKind::Item => {
+                        kind = DatasetDiffKind::Shard;
+                        after_id = 0;
+                    }
+                    None => break,
+                }
+            }
+
+            // One extra difference was collected to tell whether another page
+            // follows.
+            let next_cursor = if diffs.len() as i64 > limit {
+                diffs.truncate(limit as usize);
+                diffs.last().map(|diff| cursor_of(diff.kind, diff.id))
+            } else {
+                None
+            };
+
+            Ok((diffs, next_cursor))
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
@@ -0,0 +1,24 @@
+pub mod create;
+pub mod diff;
+pub mod read;
+pub mod schema;
+
+pub use schema::DatasetVersionDB;
+
+pub use create::{
+    NewDatasetVersionDB,
+    create,
+};
+
+pub use diff::{
+    DiffSide,
+    get_diff_page,
+};
+
+pub use read::{
+    DatasetVersionsFilter,
+    get_by_id,
+    try_get_by_id,
+    try_get_by_tag,
+    get_all,
+};
@@ -0,0 +1,154 @@
+use diesel::prelude::*;
+use serde::Deserialize;
+
+use crate::domain::models::ds_version::DatasetVersionModel;
+use crate::infra::db::schema::ds_versions;
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
+    default_limit,
+};
+use super::schema::DatasetVersionDB;
+
+#[derive(Debug, Deserialize)]
+pub struct DatasetVersionsFilter {
+    pub ds_id: Option<i32>,
+    pub tag: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    pub cursor: Option<Cursor>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    pub with_total: Option<bool>,
+}
+
+pub async fn get_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    version_id: i32,
+) -> RepoResult<DatasetVersionModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            ds_versions::table
+                .filter(ds_versions::id.eq(version_id))
+                .select(DatasetVersionDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
+
+pub async fn try_get_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    version_id: i32,
+) -> RepoResult<Option<DatasetVersionModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            ds_versions::table
+                .filter(ds_versions::id.eq(version_id))
+                .select(DatasetVersionDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+pub async fn try_get_by_tag(
+    db: &deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    tag: String,
+) -> RepoResult<Option<DatasetVersionModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            ds_versions::table
+                .filter(ds_versions::ds_id.eq(ds_id))
+                .filter(ds_versions::tag.eq(tag))
+                .select(DatasetVersionDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+pub async fn get_all(
+    db: &deadpool_diesel::postgres::Pool,
+    filter: DatasetVersionsFilter,
+) -> RepoResult<Page<DatasetVersionModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
+        .interact(move |conn| {
+            let filtered = || {
+                let mut query = ds_versions::table
+                    .into_boxed::<diesel::pg::Pg>();
+
+                if let Some(ds_id) = filter.ds_id {
+                    query = query.filter(ds_versions::ds_id.eq(ds_id));
+                }
+
+                if let Some(tag) = &filter.tag {
+                    query = query.filter(ds_versions::tag.eq(tag.clone()));
+                }
+
+                query
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(ds_versions::id.gt(cursor.id));
+            }
+
+            query
+                .order(ds_versions::id.asc())
+                .limit(limit + 1)
+                .select(DatasetVersionDB::as_select())
+                .load::<DatasetVersionDB>(conn)
+                .map(|res| (res, total))
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    let versions = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
+
+    Ok(versions)
+}
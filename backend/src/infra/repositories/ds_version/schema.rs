@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::ds_version::DatasetVersionModel;
+use crate::infra::db::schema::ds_versions;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = ds_versions)]             // Use the 'ds_versions' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct DatasetVersionDB {
+    pub id: i32,
+    pub ds_id: i32,
+    pub tag: String,
+    pub num_items: i32,
+    pub num_shards: i32,
+    pub num_annos: i32,
+    pub created_at: NaiveDateTime,
+}
+
+impl Into<DatasetVersionModel> for DatasetVersionDB {
+    fn into(self) -> DatasetVersionModel {
+        DatasetVersionModel {
+            id: self.id,
+            ds_id: self.ds_id,
+            tag: self.tag,
+            num_items: self.num_items,
+            num_shards: self.num_shards,
+            num_annos: self.num_annos,
+            created_at: self.created_at,
+        }
+    }
+}
@@ -1,8 +1,10 @@
 pub mod create;
 pub mod delete;
+pub mod permission;
 pub mod read;
 pub mod schema;
 pub mod update;
+pub mod user;
 
 pub use schema::GroupDB;
 
@@ -26,3 +28,7 @@ pub use update::{
 };
 
 pub use delete::{delete_by_id, delete_by_ids};
+
+pub use user::get_users;
+
+pub use permission::get_permissions;
@@ -0,0 +1,36 @@
+use diesel::prelude::*;
+
+use crate::domain::models::permission::PermissionModel;
+use crate::infra::db::schema::{
+    groups_permissions_rel,
+    permissions,
+};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    permission::PermissionDB,
+};
+
+pub async fn get_permissions(
+    db: &deadpool_diesel::postgres::Pool,
+    group_id: i32,
+) -> RepoResult<Vec<PermissionModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            permissions::table
+                .inner_join(groups_permissions_rel::table)
+                .filter(groups_permissions_rel::group_id.eq(group_id))
+                .select(PermissionDB::as_select())
+                .distinct()
+                .load::<PermissionDB>(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into_iter().map(Into::into).collect())
+}
@@ -8,17 +8,18 @@ use crate::infra::db::schema::{groups, groups_permissions_rel, permissions};
 use crate::infra::repositories::permission::PermissionDB;
 use crate::infra::repositories::{
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
 };
 use super::schema::GroupDB;
 
 #[derive(Debug, Deserialize)]
 pub struct GroupsFilter {
-    #[serde(default = "default_skip")]
-    skip: i64,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -99,29 +100,40 @@ pub async fn try_get_by_name(
 pub async fn get_all(
     db: &deadpool_diesel::postgres::Pool,
     filter: GroupsFilter,
-) -> RepoResult<Vec<GroupModel>> {
+) -> RepoResult<Page<GroupModel>> {
     let conn = db
         .get()
         .await
         .map_err(RepoError::Pool)?;
 
-    let res = conn
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
         .interact(move |conn| {
-            groups::table
-                .into_boxed::<diesel::pg::Pg>()
-                .offset(filter.skip)
-                .limit(filter.limit)
+            let total = match filter.with_total {
+                Some(true) => Some(groups::table.count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = groups::table
+                .into_boxed::<diesel::pg::Pg>();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(groups::id.gt(cursor.id));
+            }
+
+            query
+                .order(groups::id.asc())
+                .limit(limit + 1)
                 .select(GroupDB::as_select())
                 .load::<GroupDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let groups: Vec<GroupModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let groups = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
 
     Ok(groups)
 }
@@ -171,8 +183,8 @@ pub async fn get_by_ids(
 
                 Ok(group_ids
                     .into_iter()
-                    .map(|id| groups.remove(&id))
-                    .collect::<Vec<Option<GroupModel>>>())
+                    .filter_map(|id| groups.remove(&id))
+                    .collect::<Vec<GroupModel>>())
             } else {
                 let groups = query
                     .select(GroupDB::as_select())
@@ -183,7 +195,10 @@ pub async fn get_by_ids(
                     groups_map.insert(group.id, group.into());
                 }
 
-                Ok(groups.into_iter().map(Into::into).map(|v| Some(v)).collect())
+                Ok(group_ids
+                    .into_iter()
+                    .filter_map(|id| groups_map.remove(&id))
+                    .collect::<Vec<GroupModel>>())
             }
         })
         .await
@@ -0,0 +1,36 @@
+use diesel::prelude::*;
+
+use crate::domain::models::user::UserModel;
+use crate::infra::db::schema::{
+    users,
+    users_groups_rel,
+};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    user::UserDB,
+};
+
+pub async fn get_users(
+    db: &deadpool_diesel::postgres::Pool,
+    group_id: i32,
+) -> RepoResult<Vec<UserModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            users::table
+                .inner_join(users_groups_rel::table)
+                .filter(users_groups_rel::group_id.eq(group_id))
+                .select(UserDB::as_select())
+                .distinct()
+                .load::<UserDB>(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into_iter().map(Into::into).collect())
+}
@@ -5,17 +5,18 @@ use crate::domain::models::group_perm::GroupPermModel;
 use crate::infra::db::schema::groups_permissions_rel;
 use crate::infra::repositories::{
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
 };
 use super::schema::GroupPermDB;
 
 #[derive(Debug, Deserialize)]
 pub struct GroupsPermsFilter {
-    #[serde(default = "default_skip")]
-    skip: i64,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -74,29 +75,44 @@ pub async fn try_get_by_id(
 pub async fn get_all(
     db: &deadpool_diesel::postgres::Pool,
     filter: GroupsPermsFilter,
-) -> RepoResult<Vec<GroupPermModel>> {
+) -> RepoResult<Page<GroupPermModel>> {
     let conn = db
         .get()
         .await
         .map_err(RepoError::Pool)?;
 
-    let res = conn
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
         .interact(move |conn| {
-            groups_permissions_rel::table
-                .into_boxed::<diesel::pg::Pg>()
-                .offset(filter.skip)
-                .limit(filter.limit)
+            let total = match filter.with_total {
+                Some(true) => Some(groups_permissions_rel::table.count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = groups_permissions_rel::table
+                .into_boxed::<diesel::pg::Pg>();
+
+            if let Some(cursor) = &filter.cursor {
+                let rel_id = cursor.rel_id.unwrap_or_default();
+                query = query.filter(
+                    groups_permissions_rel::group_id.gt(cursor.id)
+                        .or(groups_permissions_rel::group_id.eq(cursor.id).and(groups_permissions_rel::permission_id.gt(rel_id)))
+                );
+            }
+
+            query
+                .order((groups_permissions_rel::group_id.asc(), groups_permissions_rel::permission_id.asc()))
+                .limit(limit + 1)
                 .select(GroupPermDB::as_select())
                 .load::<GroupPermDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let groups_permissions: Vec<GroupPermModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let groups_permissions = Page::from_rows(res, limit, total, |row| Cursor::from_ids(row.group_id, row.permission_id))
+        .map(Into::into);
 
     Ok(groups_permissions)
 }
@@ -0,0 +1,58 @@
+use diesel::prelude::*;
+
+use crate::domain::models::lockout::LockoutKind;
+use crate::infra::db::schema::lockouts;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::bounded_key;
+
+pub async fn delete_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    lockout_id: i32,
+) -> RepoResult<()> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    conn
+        .interact(move |conn| {
+            diesel::delete(
+                lockouts::table
+                    .filter(lockouts::id.eq(lockout_id))
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(())
+}
+
+/// Forgets the failed logins of a username or address.
+pub async fn delete_by_key(
+    db: &deadpool_diesel::postgres::Pool,
+    kind: LockoutKind,
+    key: String,
+) -> RepoResult<()> {
+    let key = bounded_key(key);
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    conn
+        .interact(move |conn| {
+            diesel::delete(
+                lockouts::table
+                    .filter(lockouts::kind.eq(kind.as_str()))
+                    .filter(lockouts::key.eq(key))
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(())
+}
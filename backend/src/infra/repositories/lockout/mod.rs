@@ -0,0 +1,50 @@
+pub mod delete;
+pub mod read;
+pub mod schema;
+pub mod update;
+
+pub use schema::LockoutDB;
+
+pub use read::{
+    LockoutsFilter,
+    get_all,
+    get_locked,
+    try_get_by_id,
+};
+
+pub use update::record_failure;
+
+pub use delete::{
+    delete_by_id,
+    delete_by_key,
+};
+
+/// Width of `lockouts.key`.
+const KEY_MAX_CHARS: usize = 255;
+
+/// Cuts a key down to what `lockouts.key` holds. Usernames that long can't
+/// belong to an account, so sharing a count among them loses nothing.
+fn bounded_key(mut key: String) -> String {
+    if let Some((end, _)) = key.char_indices().nth(KEY_MAX_CHARS) {
+        key.truncate(end);
+    }
+
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_key_keeps_short_keys() {
+        assert_eq!(bounded_key("alice".to_string()), "alice");
+        assert_eq!(bounded_key("a".repeat(KEY_MAX_CHARS)), "a".repeat(KEY_MAX_CHARS));
+    }
+
+    #[test]
+    fn bounded_key_cuts_at_a_char_boundary() {
+        assert_eq!(bounded_key("a".repeat(KEY_MAX_CHARS + 10)), "a".repeat(KEY_MAX_CHARS));
+        assert_eq!(bounded_key("é".repeat(KEY_MAX_CHARS + 1)), "é".repeat(KEY_MAX_CHARS));
+    }
+}
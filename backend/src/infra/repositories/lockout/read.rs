@@ -0,0 +1,152 @@
+use diesel::prelude::*;
+use serde::Deserialize;
+
+use crate::domain::models::lockout::{LockoutKind, LockoutModel};
+use crate::infra::db::schema::lockouts;
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
+    default_limit,
+};
+use super::{bounded_key, schema::LockoutDB};
+
+#[derive(Debug, Deserialize)]
+pub struct LockoutsFilter {
+    kind: Option<String>,
+    key: Option<String>,
+    locked: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    with_total: Option<bool>,
+}
+
+pub async fn try_get_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    lockout_id: i32,
+) -> RepoResult<Option<LockoutModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            lockouts::table
+                .filter(lockouts::id.eq(lockout_id))
+                .select(LockoutDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+/// The entries of the username and of the address refusing logins right
+/// now.
+pub async fn get_locked(
+    db: &deadpool_diesel::postgres::Pool,
+    username: String,
+    ip: String,
+) -> RepoResult<Vec<LockoutModel>> {
+    let username = bounded_key(username);
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            lockouts::table
+                .filter(lockouts::locked_until.gt(diesel::dsl::now))
+                .filter(
+                    lockouts::kind.eq(LockoutKind::Username.as_str())
+                        .and(lockouts::key.eq(username))
+                        .or(
+                            lockouts::kind.eq(LockoutKind::Ip.as_str())
+                                .and(lockouts::key.eq(ip))
+                        )
+                )
+                .select(LockoutDB::as_select())
+                .load::<LockoutDB>(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into_iter().map(Into::into).collect())
+}
+
+/// Lists the usernames and addresses with recent failed logins, latest
+/// entries first.
+pub async fn get_all(
+    db: &deadpool_diesel::postgres::Pool,
+    filter: LockoutsFilter,
+) -> RepoResult<Page<LockoutModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
+        .interact(move |conn| {
+            let filtered = || {
+                let mut query = lockouts::table
+                    .into_boxed::<diesel::pg::Pg>();
+
+                if let Some(kind) = &filter.kind {
+                    query = query.filter(lockouts::kind.eq(kind.clone()));
+                }
+                if let Some(key) = &filter.key {
+                    query = query.filter(lockouts::key.eq(key.clone()));
+                }
+                match filter.locked {
+                    Some(true) => {
+                        query = query.filter(lockouts::locked_until.gt(diesel::dsl::now));
+                    }
+                    Some(false) => {
+                        query = query.filter(
+                            lockouts::locked_until.is_null()
+                                .or(lockouts::locked_until.le(diesel::dsl::now))
+                        );
+                    }
+                    None => {}
+                }
+
+                query
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(lockouts::id.lt(cursor.id));
+            }
+
+            query
+                .order(lockouts::id.desc())
+                .limit(limit + 1)
+                .select(LockoutDB::as_select())
+                .load::<LockoutDB>(conn)
+                .map(|res| (res, total))
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    let lockouts = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
+
+    Ok(lockouts)
+}
@@ -0,0 +1,35 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::lockout::{LockoutKind, LockoutModel};
+use crate::infra::db::schema::lockouts;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = lockouts)]                // Use the 'lockouts' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct LockoutDB {
+    pub id: i32,
+    pub kind: String,
+    pub key: String,
+    pub failures: i32,
+    pub last_failure_at: NaiveDateTime,
+    pub locked_until: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Into<LockoutModel> for LockoutDB {
+    fn into(self) -> LockoutModel {
+        LockoutModel {
+            id: self.id,
+            // The column is constrained to known kinds.
+            kind: self.kind.parse().unwrap_or(LockoutKind::Username),
+            key: self.key,
+            failures: self.failures,
+            last_failure_at: self.last_failure_at,
+            locked_until: self.locked_until,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::NaiveDateTime;
+use diesel::{pg::upsert::excluded, prelude::*, sql_types::{Int4, Timestamptz}};
+
+use crate::domain::models::lockout::{LockoutKind, LockoutModel};
+use crate::infra::db::schema::lockouts;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::{bounded_key, schema::LockoutDB};
+
+/// Counts a failed login against the username or address, restarting the
+/// count when the last failure happened before `forget_before`, and refuses
+/// logins for as long as `block` says given the new count. Entries forgotten
+/// since are dropped along the way.
+pub async fn record_failure<F>(
+    db: &deadpool_diesel::postgres::Pool,
+    kind: LockoutKind,
+    key: String,
+    forget_before: NaiveDateTime,
+    block: F,
+) -> RepoResult<LockoutModel>
+where
+    F: FnOnce(i32) -> Option<chrono::Duration> + Send + 'static,
+{
+    let key = bounded_key(key);
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                diesel::delete(
+                    lockouts::table
+                        .filter(lockouts::last_failure_at.lt(forget_before))
+                )
+                .execute(conn)?;
+
+                let failures = diesel::dsl::sql::<Int4>("CASE WHEN lockouts.last_failure_at < ")
+                    .bind::<Timestamptz, _>(forget_before)
+                    .sql(" THEN 1 ELSE lockouts.failures + 1 END");
+
+                let lockout: LockoutDB = diesel::insert_into(lockouts::table)
+                    .values((
+                        lockouts::kind.eq(kind.as_str()),
+                        lockouts::key.eq(key),
+                        lockouts::failures.eq(1),
+                    ))
+                    .on_conflict((lockouts::kind, lockouts::key))
+                    .do_update()
+                    .set((
+                        lockouts::failures.eq(failures),
+                        lockouts::last_failure_at.eq(excluded(lockouts::last_failure_at)),
+                    ))
+                    .returning(LockoutDB::as_returning())
+                    .get_result(conn)?;
+
+                let locked_until = block(lockout.failures)
+                    .map(|duration| lockout.last_failure_at + duration);
+
+                diesel::update(lockouts::table.find(lockout.id))
+                    .set(lockouts::locked_until.eq(locked_until))
+                    .returning(LockoutDB::as_returning())
+                    .get_result(conn)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
@@ -1,20 +1,28 @@
+pub mod api_key;
+pub mod audit_log;
 pub mod dataset;
 pub mod dataset_item_rel;
 pub mod dataset_shard_rel;
+pub mod ds_acl;
 pub mod ds_item;
 pub mod ds_item_anno;
 pub mod ds_shard;
+pub mod ds_version;
 pub mod error;
 pub mod group;
 pub mod group_permission_rel;
+pub mod lockout;
+pub mod pagination;
+pub mod password_reset;
 pub mod permission;
+pub mod recovery_code;
+pub mod registration;
+pub mod session;
+pub mod totp_secret;
 pub mod user;
 pub mod user_group_rel;
+pub mod user_identity;
 
-fn default_skip() -> i64 {
-    0
-}
-
-fn default_limit() -> i64 {
+pub fn default_limit() -> i64 {
     20
 }
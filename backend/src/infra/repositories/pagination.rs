@@ -0,0 +1,164 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Upper bound for the `limit` of every list query.
+pub const MAX_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Value of the sort column of the last row, when a list is not ordered by id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorValue {
+    Text(String),
+    Timestamp(NaiveDateTime),
+}
+
+/// Position of the last row of a page. Rows are ordered by `id`, by
+/// `(id, rel_id)` for relation tables keyed by two ids, or by `(value, id)`
+/// for lists sorted on another column.
+///
+/// Clients only ever see it as an opaque string.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rel_id: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<CursorValue>,
+    /// Sort column of the list the cursor was issued for, set by lists
+    /// sortable several ways so that it isn't followed in another one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
+}
+
+impl Cursor {
+    pub fn from_id(id: i32) -> Self {
+        Self { id, ..Default::default() }
+    }
+
+    pub fn from_ids(id: i32, rel_id: i32) -> Self {
+        Self { id, rel_id: Some(rel_id), ..Default::default() }
+    }
+
+    pub fn from_value(id: i32, value: CursorValue) -> Self {
+        Self { id, value: Some(value), ..Default::default() }
+    }
+
+    /// Records the sort the cursor was issued for.
+    pub fn sorted(self, order_by: &str, order: SortOrder) -> Self {
+        Self { order_by: Some(order_by.to_string()), order: Some(order), ..self }
+    }
+
+    pub fn encode(&self) -> String {
+        // Serializing integers, strings and timestamps can't fail.
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let json = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+}
+
+/// Deserializes the opaque `cursor` query parameter of a filter, rejecting
+/// anything that was not produced by [`Cursor::encode`].
+pub fn deserialize_cursor<'de, D>(deserializer: D) -> Result<Option<Cursor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(cursor) => Cursor::decode(&cursor)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom("invalid cursor")),
+        None => Ok(None),
+    }
+}
+
+/// Clamps a requested page size to `1..=MAX_LIMIT`.
+pub fn clamp_limit(limit: i64) -> i64 {
+    limit.clamp(1, MAX_LIMIT)
+}
+
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor of the last item, set only when more items follow
+    pub next_cursor: Option<String>,
+    /// Number of rows matching the filter, when requested
+    pub total: Option<i64>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from rows queried with `limit + 1`, the extra row only
+    /// telling whether another page exists.
+    pub fn from_rows<F>(mut rows: Vec<T>, limit: i64, total: Option<i64>, cursor_of: F) -> Self
+    where
+        F: Fn(&T) -> Cursor,
+    {
+        let next_cursor = if rows.len() as i64 > limit {
+            rows.truncate(limit as usize);
+            rows.last().map(|row| cursor_of(row).encode())
+        } else {
+            None
+        };
+
+        Self {
+            items: rows,
+            next_cursor,
+            total,
+        }
+    }
+
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            total: self.total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_survive_encoding() {
+        let created_at = NaiveDateTime::parse_from_str("2026-10-18 12:34:56.789", "%Y-%m-%d %H:%M:%S%.f")
+            .unwrap();
+        let cursors = [
+            Cursor::from_id(7),
+            Cursor::from_ids(7, 9),
+            Cursor::from_value(7, CursorValue::Text("s3://bucket/a b".to_string())),
+            Cursor::from_value(7, CursorValue::Timestamp(created_at)).sorted("created_at", SortOrder::Desc),
+        ];
+
+        for cursor in cursors {
+            let encoded = cursor.encode();
+            assert!(encoded.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+            assert_eq!(Cursor::decode(&encoded), Some(cursor));
+        }
+    }
+
+    #[test]
+    fn foreign_cursors_are_refused() {
+        assert_eq!(Cursor::decode(""), None);
+        assert_eq!(Cursor::decode("not a cursor"), None);
+        assert_eq!(Cursor::decode(&URL_SAFE_NO_PAD.encode(b"{\"value\":1}")), None);
+        assert_eq!(Cursor::decode(&URL_SAFE_NO_PAD.encode(b"{\"id\":1,\"order\":\"up\"}")), None);
+    }
+}
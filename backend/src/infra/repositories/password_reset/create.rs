@@ -0,0 +1,49 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::password_reset::PasswordResetModel;
+use crate::infra::db::schema::password_resets;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::PasswordResetDB;
+
+#[derive(Insertable)]
+#[diesel(table_name = password_resets)]
+pub struct NewPasswordResetDB {
+    pub user_id: i32,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+}
+
+/// Creates a reset token, dropping the unused ones the user was sent before
+/// so that only the latest email works.
+pub async fn create(
+    db: &deadpool_diesel::postgres::Pool,
+    new_reset: NewPasswordResetDB,
+) -> RepoResult<PasswordResetModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(|conn| {
+            conn.transaction(|conn| {
+                diesel::delete(
+                    password_resets::table
+                        .filter(password_resets::user_id.eq(new_reset.user_id))
+                        .filter(password_resets::used_at.is_null())
+                )
+                .execute(conn)?;
+
+                diesel::insert_into(password_resets::table)
+                    .values(new_reset)
+                    .returning(PasswordResetDB::as_returning())
+                    .get_result(conn)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
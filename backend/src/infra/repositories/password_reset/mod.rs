@@ -0,0 +1,12 @@
+pub mod create;
+pub mod schema;
+pub mod update;
+
+pub use schema::PasswordResetDB;
+
+pub use create::{
+    NewPasswordResetDB,
+    create,
+};
+
+pub use update::try_consume;
@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::password_reset::PasswordResetModel;
+use crate::infra::db::schema::password_resets;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = password_resets)]         // Use the 'password_resets' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct PasswordResetDB {
+    pub id: i32,
+    pub user_id: i32,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl Into<PasswordResetModel> for PasswordResetDB {
+    fn into(self) -> PasswordResetModel {
+        PasswordResetModel {
+            id: self.id,
+            user_id: self.user_id,
+            expires_at: self.expires_at,
+            used_at: self.used_at,
+            created_at: self.created_at,
+        }
+    }
+}
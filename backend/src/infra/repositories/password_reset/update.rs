@@ -0,0 +1,69 @@
+use diesel::prelude::*;
+
+use crate::domain::models::password_reset::PasswordResetModel;
+use crate::infra::db::schema::{api_keys, password_resets, sessions, users};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::PasswordResetDB;
+
+/// Uses up the reset token and sets the password of its user, unless the
+/// token expired or was already used. Whatever else let in whoever knew the
+/// old password goes along: the other reset tokens of the user are used up,
+/// their sessions revoked and their API keys deleted.
+pub async fn try_consume(
+    db: &deadpool_diesel::postgres::Pool,
+    token_hash: String,
+    hashed_password: String,
+) -> RepoResult<Option<PasswordResetModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let reset: PasswordResetDB = diesel::update(
+                    password_resets::table
+                        .filter(password_resets::token_hash.eq(token_hash))
+                        .filter(password_resets::used_at.is_null())
+                        .filter(password_resets::expires_at.gt(diesel::dsl::now))
+                )
+                .set(password_resets::used_at.eq(diesel::dsl::now))
+                .returning(PasswordResetDB::as_returning())
+                .get_result(conn)?;
+
+                diesel::update(users::table.find(reset.user_id))
+                    .set(users::hashed_password.eq(hashed_password))
+                    .execute(conn)?;
+
+                diesel::update(
+                    password_resets::table
+                        .filter(password_resets::user_id.eq(reset.user_id))
+                        .filter(password_resets::used_at.is_null())
+                )
+                .set(password_resets::used_at.eq(diesel::dsl::now))
+                .execute(conn)?;
+
+                diesel::update(
+                    sessions::table
+                        .filter(sessions::user_id.eq(reset.user_id))
+                        .filter(sessions::revoked_at.is_null())
+                )
+                .set(sessions::revoked_at.eq(diesel::dsl::now))
+                .execute(conn)?;
+
+                diesel::delete(api_keys::table.filter(api_keys::user_id.eq(reset.user_id)))
+                    .execute(conn)?;
+
+                Ok(reset)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
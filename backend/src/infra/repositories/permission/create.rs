@@ -34,3 +34,34 @@ pub async fn create(
 
     Ok(res.into())
 }
+
+/// Creates the permissions among `names` that don't exist yet and returns
+/// how many were created.
+pub async fn create_missing(
+    db: &deadpool_diesel::postgres::Pool,
+    names: Vec<String>,
+) -> RepoResult<usize> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let new_perms = names
+        .into_iter()
+        .map(|name| NewPermissionDB { name })
+        .collect::<Vec<_>>();
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::insert_into(permissions::table)
+                .values(new_perms)
+                .on_conflict(permissions::name)
+                .do_nothing()
+                .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
@@ -9,6 +9,7 @@ pub use schema::PermissionDB;
 pub use create::{
     NewPermissionDB,
     create,
+    create_missing,
 };
 
 pub use read::{
@@ -5,17 +5,18 @@ use crate::domain::models::permission::PermissionModel;
 use crate::infra::db::schema::permissions;
 use crate::infra::repositories::{
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
 };
 use super::schema::PermissionDB;
 
 #[derive(Debug, Deserialize)]
 pub struct PermissionsFilter {
-    #[serde(default = "default_skip")]
-    skip: i64,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -96,29 +97,40 @@ pub async fn try_get_by_name(
 pub async fn get_all(
     db: &deadpool_diesel::postgres::Pool,
     filter: PermissionsFilter,
-) -> RepoResult<Vec<PermissionModel>> {
+) -> RepoResult<Page<PermissionModel>> {
     let conn = db
         .get()
         .await
         .map_err(RepoError::Pool)?;
 
-    let res = conn
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
         .interact(move |conn| {
-            permissions::table
-                .into_boxed::<diesel::pg::Pg>()
-                .offset(filter.skip)
-                .limit(filter.limit)
+            let total = match filter.with_total {
+                Some(true) => Some(permissions::table.count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = permissions::table
+                .into_boxed::<diesel::pg::Pg>();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(permissions::id.gt(cursor.id));
+            }
+
+            query
+                .order(permissions::id.asc())
+                .limit(limit + 1)
                 .select(PermissionDB::as_select())
                 .load::<PermissionDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let permissions: Vec<PermissionModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let permissions = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
 
     Ok(permissions)
 }
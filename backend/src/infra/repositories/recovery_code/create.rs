@@ -0,0 +1,44 @@
+use diesel::prelude::*;
+
+use crate::infra::db::schema::recovery_codes;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+#[derive(Insertable)]
+#[diesel(table_name = recovery_codes)]
+pub struct NewRecoveryCodeDB {
+    pub user_id: i32,
+    pub code_hash: String,
+}
+
+/// Replaces all the recovery codes of a user, used or not.
+pub async fn replace_by_user_id(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    code_hashes: Vec<String>,
+) -> RepoResult<usize> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                diesel::delete(recovery_codes::table.filter(recovery_codes::user_id.eq(user_id)))
+                    .execute(conn)?;
+
+                let new_codes: Vec<_> = code_hashes
+                    .into_iter()
+                    .map(|code_hash| NewRecoveryCodeDB { user_id, code_hash })
+                    .collect();
+                diesel::insert_into(recovery_codes::table)
+                    .values(new_codes)
+                    .execute(conn)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
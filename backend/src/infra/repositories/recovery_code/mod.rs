@@ -0,0 +1,12 @@
+pub mod create;
+pub mod read;
+pub mod update;
+
+pub use create::{
+    NewRecoveryCodeDB,
+    replace_by_user_id,
+};
+
+pub use read::count_unused_by_user_id;
+
+pub use update::try_use;
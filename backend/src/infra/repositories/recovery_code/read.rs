@@ -0,0 +1,28 @@
+use diesel::prelude::*;
+
+use crate::infra::db::schema::recovery_codes;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+pub async fn count_unused_by_user_id(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+) -> RepoResult<i64> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            recovery_codes::table
+                .filter(recovery_codes::user_id.eq(user_id))
+                .filter(recovery_codes::used_at.is_null())
+                .count()
+                .get_result::<i64>(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
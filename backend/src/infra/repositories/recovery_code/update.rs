@@ -0,0 +1,34 @@
+use diesel::prelude::*;
+
+use crate::infra::db::schema::recovery_codes;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+/// Uses up an unused recovery code of the user, returning whether there was
+/// one with that digest.
+pub async fn try_use(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    code_hash: String,
+) -> RepoResult<bool> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::update(
+                recovery_codes::table
+                    .filter(recovery_codes::user_id.eq(user_id))
+                    .filter(recovery_codes::code_hash.eq(code_hash))
+                    .filter(recovery_codes::used_at.is_null())
+            )
+            .set(recovery_codes::used_at.eq(diesel::dsl::now))
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res > 0)
+}
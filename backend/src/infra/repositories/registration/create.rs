@@ -0,0 +1,51 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::{registration::RegistrationModel, user::UserModel};
+use crate::infra::db::schema::{registrations, users};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    user::{NewUserDB, UserDB},
+};
+use super::schema::RegistrationDB;
+
+#[derive(Insertable)]
+#[diesel(table_name = registrations)]
+pub struct NewRegistrationDB {
+    pub token_hash: String,
+    pub token_expires_at: NaiveDateTime,
+}
+
+/// Creates an inactive user along with their unverified registration.
+pub async fn register(
+    db: &deadpool_diesel::postgres::Pool,
+    new_user: NewUserDB,
+    new_registration: NewRegistrationDB,
+) -> RepoResult<(UserModel, RegistrationModel)> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let (user, registration) = conn
+        .interact(|conn| {
+            conn.transaction(|conn| {
+                let user = diesel::insert_into(users::table)
+                    .values(new_user)
+                    .returning(UserDB::as_returning())
+                    .get_result(conn)?;
+
+                let registration = diesel::insert_into(registrations::table)
+                    .values((new_registration, registrations::user_id.eq(user.id)))
+                    .returning(RegistrationDB::as_returning())
+                    .get_result(conn)?;
+
+                Ok::<_, diesel::result::Error>((user, registration))
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok((user.into(), registration.into()))
+}
@@ -0,0 +1,42 @@
+use diesel::prelude::*;
+
+use crate::domain::models::registration::RegistrationStatus;
+use crate::infra::db::schema::{registrations, users};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+/// Deletes the users holding `username` or `email` whose registration was
+/// never verified and whose verification link expired, along with their
+/// registration, so that the username and email can be registered again.
+/// Returns the ids of the deleted users.
+pub async fn delete_stale(
+    db: &deadpool_diesel::postgres::Pool,
+    username: String,
+    email: String,
+) -> RepoResult<Vec<i32>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::delete(
+                users::table
+                    .filter(users::username.eq(username).or(users::email.eq(email)))
+                    .filter(users::is_active.eq(false))
+                    .filter(users::id.eq_any(
+                        registrations::table
+                            .filter(registrations::status.eq(RegistrationStatus::Unverified.as_str()))
+                            .filter(registrations::token_expires_at.le(diesel::dsl::now))
+                            .select(registrations::user_id)
+                    ))
+            )
+            .returning(users::id)
+            .get_results(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
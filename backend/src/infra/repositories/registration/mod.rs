@@ -0,0 +1,26 @@
+pub mod create;
+pub mod delete;
+pub mod read;
+pub mod schema;
+pub mod update;
+
+pub use schema::RegistrationDB;
+
+pub use create::{
+    NewRegistrationDB,
+    register,
+};
+
+pub use delete::delete_stale;
+
+pub use read::{
+    RegistrationsFilter,
+    get_all,
+    try_get_by_id,
+};
+
+pub use update::{
+    try_renew_token,
+    try_review,
+    try_verify,
+};
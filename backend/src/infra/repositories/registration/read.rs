@@ -0,0 +1,101 @@
+use diesel::prelude::*;
+use serde::Deserialize;
+
+use crate::domain::models::{registration::RegistrationModel, user::UserModel};
+use crate::infra::db::schema::{registrations, users};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
+    default_limit,
+    user::UserDB,
+};
+use super::schema::RegistrationDB;
+
+#[derive(Debug, Deserialize)]
+pub struct RegistrationsFilter {
+    status: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    with_total: Option<bool>,
+}
+
+pub async fn try_get_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    registration_id: i32,
+) -> RepoResult<Option<RegistrationModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            registrations::table
+                .filter(registrations::id.eq(registration_id))
+                .select(RegistrationDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+/// Registrations along with their user, oldest first.
+pub async fn get_all(
+    db: &deadpool_diesel::postgres::Pool,
+    filter: RegistrationsFilter,
+) -> RepoResult<Page<(RegistrationModel, UserModel)>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
+        .interact(move |conn| {
+            let filtered = || {
+                let mut query = registrations::table
+                    .inner_join(users::table.on(users::id.eq(registrations::user_id)))
+                    .into_boxed::<diesel::pg::Pg>();
+
+                if let Some(status) = &filter.status {
+                    query = query.filter(registrations::status.eq(status.clone()));
+                }
+
+                query
+            };
+
+            let total = match filter.with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
+
+            if let Some(cursor) = &filter.cursor {
+                query = query.filter(registrations::id.gt(cursor.id));
+            }
+
+            query
+                .order(registrations::id.asc())
+                .limit(limit + 1)
+                .select((RegistrationDB::as_select(), UserDB::as_select()))
+                .load::<(RegistrationDB, UserDB)>(conn)
+                .map(|res| (res, total))
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    let page = Page::from_rows(res, limit, total, |(registration, _)| Cursor::from_id(registration.id))
+        .map(|(registration, user)| (registration.into(), user.into()));
+
+    Ok(page)
+}
@@ -0,0 +1,41 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::registration::{RegistrationModel, RegistrationStatus};
+use crate::infra::db::schema::registrations;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = registrations)]           // Use the 'registrations' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct RegistrationDB {
+    pub id: i32,
+    pub user_id: i32,
+    pub status: String,
+    pub token_hash: Option<String>,
+    pub token_expires_at: NaiveDateTime,
+    pub verified_at: Option<NaiveDateTime>,
+    pub reviewed_by: Option<i32>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub reason: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Into<RegistrationModel> for RegistrationDB {
+    fn into(self) -> RegistrationModel {
+        RegistrationModel {
+            id: self.id,
+            user_id: self.user_id,
+            // The column is constrained to known statuses, fall back to the
+            // one granting nothing all the same.
+            status: self.status.parse().unwrap_or(RegistrationStatus::Unverified),
+            token_expires_at: self.token_expires_at,
+            verified_at: self.verified_at,
+            reviewed_by: self.reviewed_by,
+            reviewed_at: self.reviewed_at,
+            reason: self.reason,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::registration::{RegistrationModel, RegistrationStatus};
+use crate::infra::db::schema::{registrations, users};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::RegistrationDB;
+
+/// Marks the unverified registration holding the token as verified, unless
+/// the token expired.
+pub async fn try_verify(
+    db: &deadpool_diesel::postgres::Pool,
+    token_hash: String,
+) -> RepoResult<Option<RegistrationModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::update(
+                registrations::table
+                    .filter(registrations::token_hash.eq(token_hash))
+                    .filter(registrations::status.eq(RegistrationStatus::Unverified.as_str()))
+                    .filter(registrations::token_expires_at.gt(diesel::dsl::now))
+            )
+            .set((
+                registrations::status.eq(RegistrationStatus::Pending.as_str()),
+                registrations::token_hash.eq(None::<String>),
+                registrations::verified_at.eq(diesel::dsl::now),
+            ))
+            .returning(RegistrationDB::as_returning())
+            .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+/// Replaces the verification token of the registration of a user, as long
+/// as it is still unverified.
+pub async fn try_renew_token(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    token_hash: String,
+    token_expires_at: NaiveDateTime,
+) -> RepoResult<Option<RegistrationModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::update(
+                registrations::table
+                    .filter(registrations::user_id.eq(user_id))
+                    .filter(registrations::status.eq(RegistrationStatus::Unverified.as_str()))
+            )
+            .set((
+                registrations::token_hash.eq(token_hash),
+                registrations::token_expires_at.eq(token_expires_at),
+            ))
+            .returning(RegistrationDB::as_returning())
+            .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+/// Records the decision of an admin on a registration in one of the
+/// `reviewable` statuses, activating the user when it is approved. `None`
+/// when the registration is in another status.
+pub async fn try_review(
+    db: &deadpool_diesel::postgres::Pool,
+    registration_id: i32,
+    reviewable: &[RegistrationStatus],
+    decision: RegistrationStatus,
+    reviewed_by: i32,
+    reason: Option<String>,
+) -> RepoResult<Option<RegistrationModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let reviewable = reviewable
+        .iter()
+        .map(RegistrationStatus::as_str)
+        .collect::<Vec<&str>>();
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let registration = diesel::update(
+                    registrations::table
+                        .filter(registrations::id.eq(registration_id))
+                        .filter(registrations::status.eq_any(reviewable))
+                )
+                .set((
+                    registrations::status.eq(decision.as_str()),
+                    registrations::token_hash.eq(None::<String>),
+                    registrations::reviewed_by.eq(reviewed_by),
+                    registrations::reviewed_at.eq(diesel::dsl::now),
+                    registrations::reason.eq(reason),
+                ))
+                .returning(RegistrationDB::as_returning())
+                .get_result::<RegistrationDB>(conn)?;
+
+                if decision == RegistrationStatus::Approved {
+                    diesel::update(users::table.filter(users::id.eq(registration.user_id)))
+                        .set(users::is_active.eq(true))
+                        .execute(conn)?;
+                }
+
+                Ok(registration)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
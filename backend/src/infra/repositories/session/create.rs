@@ -0,0 +1,38 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::session::SessionModel;
+use crate::infra::db::schema::sessions;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::SessionDB;
+
+#[derive(Insertable)]
+#[diesel(table_name = sessions)]
+pub struct NewSessionDB {
+    pub user_id: i32,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+}
+
+pub async fn create(
+    db: &deadpool_diesel::postgres::Pool,
+    new_session: NewSessionDB,
+) -> RepoResult<SessionModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(|conn| {
+            diesel::insert_into(sessions::table)
+                .values(new_session)
+                .returning(SessionDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
@@ -0,0 +1,23 @@
+pub mod create;
+pub mod read;
+pub mod schema;
+pub mod update;
+
+pub use schema::SessionDB;
+
+pub use create::{
+    NewSessionDB,
+    create,
+};
+
+pub use read::{
+    SessionByToken,
+    try_get_by_id,
+    try_get_by_token_hash,
+};
+
+pub use update::{
+    try_rotate,
+    revoke_by_id,
+    revoke_all_by_user_id,
+};
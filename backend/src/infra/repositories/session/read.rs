@@ -0,0 +1,77 @@
+use diesel::prelude::*;
+
+use crate::domain::models::session::SessionModel;
+use crate::infra::db::schema::sessions;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::SessionDB;
+
+/// A session found by the digest of a refresh token.
+#[derive(Debug)]
+pub enum SessionByToken {
+    /// The token is the current refresh token of the session.
+    Current(SessionModel),
+    /// The token was already rotated out, so someone is replaying it.
+    Reused(SessionModel),
+}
+
+pub async fn try_get_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    session_id: i32,
+) -> RepoResult<Option<SessionModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            sessions::table
+                .filter(sessions::id.eq(session_id))
+                .select(SessionDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+pub async fn try_get_by_token_hash(
+    db: &deadpool_diesel::postgres::Pool,
+    token_hash: String,
+) -> RepoResult<Option<SessionByToken>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            sessions::table
+                .filter(
+                    sessions::token_hash.eq(&token_hash)
+                        .or(sessions::previous_token_hash.eq(&token_hash))
+                )
+                .select(SessionDB::as_select())
+                .first(conn)
+                .map(|session: SessionDB| {
+                    if session.token_hash == token_hash {
+                        SessionByToken::Current(session.into())
+                    } else {
+                        SessionByToken::Reused(session.into())
+                    }
+                })
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res)),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
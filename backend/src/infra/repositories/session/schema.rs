@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::session::SessionModel;
+use crate::infra::db::schema::sessions;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = sessions)]                // Use the 'sessions' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct SessionDB {
+    pub id: i32,
+    pub user_id: i32,
+    pub token_hash: String,
+    pub previous_token_hash: Option<String>,
+    pub expires_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Into<SessionModel> for SessionDB {
+    fn into(self) -> SessionModel {
+        SessionModel {
+            id: self.id,
+            user_id: self.user_id,
+            expires_at: self.expires_at,
+            revoked_at: self.revoked_at,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::session::SessionModel;
+use crate::infra::db::schema::sessions;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::SessionDB;
+
+/// Replaces the refresh token of a live session and extends it, unless the
+/// token was rotated or the session revoked in the meantime.
+pub async fn try_rotate(
+    db: &deadpool_diesel::postgres::Pool,
+    session_id: i32,
+    token_hash: String,
+    new_token_hash: String,
+    expires_at: NaiveDateTime,
+) -> RepoResult<Option<SessionModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::update(
+                sessions::table
+                    .filter(sessions::id.eq(session_id))
+                    .filter(sessions::token_hash.eq(&token_hash))
+                    .filter(sessions::revoked_at.is_null())
+            )
+            .set((
+                sessions::token_hash.eq(new_token_hash),
+                sessions::previous_token_hash.eq(token_hash.clone()),
+                sessions::expires_at.eq(expires_at),
+            ))
+            .returning(SessionDB::as_returning())
+            .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+pub async fn revoke_by_id(
+    db: &deadpool_diesel::postgres::Pool,
+    session_id: i32,
+) -> RepoResult<usize> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::update(
+                sessions::table
+                    .filter(sessions::id.eq(session_id))
+                    .filter(sessions::revoked_at.is_null())
+            )
+            .set(sessions::revoked_at.eq(diesel::dsl::now))
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
+
+/// Revokes every session of a user, returning how many were still live.
+pub async fn revoke_all_by_user_id(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+) -> RepoResult<usize> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::update(
+                sessions::table
+                    .filter(sessions::user_id.eq(user_id))
+                    .filter(sessions::revoked_at.is_null())
+                    .filter(sessions::expires_at.gt(diesel::dsl::now))
+            )
+            .set(sessions::revoked_at.eq(diesel::dsl::now))
+            .execute(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
@@ -0,0 +1,42 @@
+use diesel::{pg::upsert::excluded, prelude::*};
+
+use crate::domain::models::totp_secret::TotpSecretModel;
+use crate::infra::db::schema::totp_secrets;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::TotpSecretDB;
+
+/// Starts the enrollment of a user with a new secret, replacing the one of
+/// an enrollment they didn't confirm.
+pub async fn enroll(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    secret: String,
+) -> RepoResult<TotpSecretModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::insert_into(totp_secrets::table)
+                .values((
+                    totp_secrets::user_id.eq(user_id),
+                    totp_secrets::secret.eq(secret),
+                ))
+                .on_conflict(totp_secrets::user_id)
+                .do_update()
+                .set((
+                    totp_secrets::secret.eq(excluded(totp_secrets::secret)),
+                    totp_secrets::enabled_at.eq(None::<chrono::NaiveDateTime>),
+                    totp_secrets::last_used_step.eq(None::<i64>),
+                ))
+                .returning(TotpSecretDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
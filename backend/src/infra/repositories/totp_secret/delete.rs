@@ -0,0 +1,32 @@
+use diesel::prelude::*;
+
+use crate::infra::db::schema::{recovery_codes, totp_secrets};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+/// Turns two-factor authentication off for a user, dropping their secret
+/// and recovery codes.
+pub async fn delete_by_user_id(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+) -> RepoResult<usize> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                diesel::delete(recovery_codes::table.filter(recovery_codes::user_id.eq(user_id)))
+                    .execute(conn)?;
+
+                diesel::delete(totp_secrets::table.filter(totp_secrets::user_id.eq(user_id)))
+                    .execute(conn)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
@@ -0,0 +1,18 @@
+pub mod create;
+pub mod delete;
+pub mod read;
+pub mod schema;
+pub mod update;
+
+pub use schema::TotpSecretDB;
+
+pub use create::enroll;
+
+pub use read::try_get_by_user_id;
+
+pub use update::{
+    try_enable,
+    try_use_step,
+};
+
+pub use delete::delete_by_user_id;
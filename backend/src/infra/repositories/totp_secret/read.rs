@@ -0,0 +1,32 @@
+use diesel::prelude::*;
+
+use crate::domain::models::totp_secret::TotpSecretModel;
+use crate::infra::db::schema::totp_secrets;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::TotpSecretDB;
+
+pub async fn try_get_by_user_id(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+) -> RepoResult<Option<TotpSecretModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            totp_secrets::table
+                .filter(totp_secrets::user_id.eq(user_id))
+                .select(TotpSecretDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
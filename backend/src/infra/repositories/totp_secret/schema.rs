@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::totp_secret::TotpSecretModel;
+use crate::infra::db::schema::totp_secrets;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = totp_secrets)]            // Use the 'totp_secrets' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct TotpSecretDB {
+    pub id: i32,
+    pub user_id: i32,
+    pub secret: String,
+    pub enabled_at: Option<NaiveDateTime>,
+    pub last_used_step: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Into<TotpSecretModel> for TotpSecretDB {
+    fn into(self) -> TotpSecretModel {
+        TotpSecretModel {
+            id: self.id,
+            user_id: self.user_id,
+            secret: self.secret,
+            enabled_at: self.enabled_at,
+            last_used_step: self.last_used_step,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use diesel::prelude::*;
+
+use crate::domain::models::totp_secret::TotpSecretModel;
+use crate::infra::db::schema::{recovery_codes, totp_secrets};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    recovery_code::NewRecoveryCodeDB,
+};
+use super::schema::TotpSecretDB;
+
+/// Records that the code of `step` was used, unless the user already used
+/// one of that step or a later one.
+pub async fn try_use_step(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    step: i64,
+) -> RepoResult<Option<TotpSecretModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::update(
+                totp_secrets::table
+                    .filter(totp_secrets::user_id.eq(user_id))
+                    .filter(
+                        totp_secrets::last_used_step.is_null()
+                            .or(totp_secrets::last_used_step.lt(step))
+                    )
+            )
+            .set(totp_secrets::last_used_step.eq(step))
+            .returning(TotpSecretDB::as_returning())
+            .get_result(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
+/// Confirms the pending enrollment of a user with the code of `step`, and
+/// replaces their recovery codes.
+pub async fn try_enable(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    step: i64,
+    code_hashes: Vec<String>,
+) -> RepoResult<Option<TotpSecretModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let secret: TotpSecretDB = diesel::update(
+                    totp_secrets::table
+                        .filter(totp_secrets::user_id.eq(user_id))
+                        .filter(totp_secrets::enabled_at.is_null())
+                        .filter(
+                            totp_secrets::last_used_step.is_null()
+                                .or(totp_secrets::last_used_step.lt(step))
+                        )
+                )
+                .set((
+                    totp_secrets::enabled_at.eq(diesel::dsl::now),
+                    totp_secrets::last_used_step.eq(step),
+                ))
+                .returning(TotpSecretDB::as_returning())
+                .get_result(conn)?;
+
+                diesel::delete(recovery_codes::table.filter(recovery_codes::user_id.eq(user_id)))
+                    .execute(conn)?;
+
+                let new_codes: Vec<_> = code_hashes
+                    .into_iter()
+                    .map(|code_hash| NewRecoveryCodeDB { user_id, code_hash })
+                    .collect();
+                diesel::insert_into(recovery_codes::table)
+                    .values(new_codes)
+                    .execute(conn)?;
+
+                Ok(secret)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
@@ -2,7 +2,7 @@ use diesel::prelude::*;
 use serde::Deserialize;
 
 use crate::domain::models::user::UserModel;
-use crate::infra::db::schema::users;
+use crate::infra::db::schema::{users, users_groups_rel};
 use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
 use super::schema::UserDB;
 
@@ -13,6 +13,7 @@ pub struct NewUserDB {
     pub hashed_password: String,
     pub nickname: String,
     pub avatar_uri: String,
+    pub email: Option<String>,
 }
 
 pub async fn create(
@@ -37,3 +38,39 @@ pub async fn create(
 
     Ok(res.into())
 }
+
+/// Creates an active user member of `group_id`, all or nothing.
+pub async fn create_active_member(
+    db: &deadpool_diesel::postgres::Pool,
+    new_user: NewUserDB,
+    group_id: i32,
+) -> RepoResult<UserModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let user = diesel::insert_into(users::table)
+                    .values((new_user, users::is_active.eq(true)))
+                    .returning(UserDB::as_returning())
+                    .get_result(conn)?;
+
+                diesel::insert_into(users_groups_rel::table)
+                    .values((
+                        users_groups_rel::user_id.eq(user.id),
+                        users_groups_rel::group_id.eq(group_id),
+                    ))
+                    .execute(conn)?;
+
+                Ok(user)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
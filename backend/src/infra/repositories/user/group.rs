@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use diesel::prelude::*;
 
 use crate::domain::models::group::GroupModel;
@@ -39,28 +41,41 @@ pub async fn get_groups(
     Ok(res.into_iter().map(Into::into).collect())
 }
 
-// pub async fn batch_get_groups(
-//     db: &deadpool_diesel::postgres::Pool,
-//     user_ids: &[i32],
-// ) -> RepoResult<Vec<(i32, Vec<GroupModel>)>> {
-//     let conn = db
-//         .get()
-//         .await
-//         .map_err(RepoError::Pool)?;
+pub async fn batch_get_groups(
+    db: &deadpool_diesel::postgres::Pool,
+    user_ids: Vec<i32>,
+) -> RepoResult<Vec<Vec<GroupModel>>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
 
-//     let res = conn
-//         .interact(move |conn| {
-//             users::table
-//                 .left_join(users_groups_rel::table)
-//                 .left_join(groups::table.on(
-//                     groups::id.eq(users_groups_rel::group_id)
-//                 ))
-//                 .filter(users::id.eq_any(user_ids))
-//                 .select((users::id, Option::<GroupDB>::as_select()))
-//                 .distinct()
-//                 .load::<(i32, Option<GroupDB>)>(conn)
-//         })
-//         .await
-//         .map_err(map_interact_error)?
-//         .map_err(RepoError::Diesel)?;
-// }
+    let res = conn
+        .interact(move |conn| {
+            let users_groups = users_groups_rel::table
+                .inner_join(groups::table.on(
+                    groups::id.eq(users_groups_rel::group_id)
+                ))
+                .filter(users_groups_rel::user_id.eq_any(&user_ids))
+                .select((users_groups_rel::user_id, GroupDB::as_select()))
+                .load::<(i32, GroupDB)>(conn)?;
+
+            let mut groups_per_user = HashMap::<i32, Vec<GroupModel>>::new();
+            for (user_id, group) in users_groups {
+                groups_per_user
+                    .entry(user_id)
+                    .or_default()
+                    .push(group.into());
+            }
+
+            Ok(user_ids
+                .into_iter()
+                .map(|id| groups_per_user.remove(&id).unwrap_or_default())
+                .collect::<Vec<Vec<GroupModel>>>())
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res)
+}
@@ -11,12 +11,14 @@ pub use schema::UserDB;
 pub use create::{
     NewUserDB,
     create,
+    create_active_member,
 };
 
 pub use read::{
     UsersFilter,
     get_by_id,
     try_get_by_id,
+    try_get_by_email,
     try_get_by_username,
     get_all,
 };
@@ -30,6 +32,6 @@ pub use update::{
 
 pub use delete::{delete_by_id, delete_by_ids};
 
-pub use group::get_groups;
+pub use group::{get_groups, batch_get_groups};
 
 pub use permission::get_permissions;
@@ -1,19 +1,15 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 use diesel::prelude::*;
 use serde::Deserialize;
 
-use crate::domain::models::user::UserModel;
-use crate::infra::db::schema::{groups, groups_permissions_rel, permissions, users};
+use crate::domain::models::{group::GroupModel, user::UserModel};
+use crate::infra::db::schema::users;
 use crate::infra::repositories::{
     self,
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
-    user_group_rel::UserGroupDB,
-    group::GroupDB,
-    group_permission_rel::GroupPermDB,
-    permission::PermissionDB,
 };
 use super::schema::UserDB;
 
@@ -21,13 +17,15 @@ use super::schema::UserDB;
 pub struct UsersFilter {
     username: Option<String>,
     nickname: Option<String>,
+    email: Option<String>,
     is_active: Option<bool>,
     with_groups: Option<bool>,
     with_permissions: Option<bool>,
-    #[serde(default = "default_skip")]
-    skip: i64,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -105,67 +103,142 @@ pub async fn try_get_by_username(
     }
 }
 
+pub async fn try_get_by_email(
+    db: &deadpool_diesel::postgres::Pool,
+    email: String,
+) -> RepoResult<Option<UserModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            users::table
+                .filter(users::email.eq(email))
+                .select(UserDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
+
 pub async fn get_all(
     db: &deadpool_diesel::postgres::Pool,
     filter: UsersFilter,
-) -> RepoResult<Vec<UserModel>> {
+) -> RepoResult<Page<UserModel>> {
     let conn = db
         .get()
         .await
         .map_err(RepoError::Pool)?;
 
-    let users = conn
+    let limit = clamp_limit(filter.limit);
+    let username = filter.username;
+    let nickname = filter.nickname;
+    let email = filter.email;
+    let is_active = filter.is_active;
+    let cursor = filter.cursor;
+    let with_total = filter.with_total;
+    let (res, total) = conn
         .interact(move |conn| {
-            let mut query = users::table
-                .into_boxed::<diesel::pg::Pg>();
+            let filtered = || {
+                let mut query = users::table
+                    .into_boxed::<diesel::pg::Pg>();
 
-            if let Some(username) = filter.username {
-                query = query.filter(users::username.eq(username));
-            }
+                if let Some(username) = &username {
+                    query = query.filter(users::username.eq(username.clone()));
+                }
 
-            if let Some(nickname) = filter.nickname {
-                query = query.filter(users::nickname.eq(nickname));
-            }
+                if let Some(nickname) = &nickname {
+                    query = query.filter(users::nickname.eq(nickname.clone()));
+                }
+
+                if let Some(email) = &email {
+                    query = query.filter(users::email.eq(email.clone()));
+                }
+
+                if let Some(is_active) = is_active {
+                    query = query.filter(users::is_active.eq(is_active));
+                }
 
-            if let Some(is_active) = filter.is_active {
-                query = query.filter(users::is_active.eq(is_active));
+                query
+            };
+
+            let total = match with_total {
+                Some(true) => Some(filtered().count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = filtered();
+
+            if let Some(cursor) = &cursor {
+                query = query.filter(users::id.gt(cursor.id));
             }
 
             query
-                .offset(filter.skip)
-                .limit(filter.limit)
+                .order(users::id.asc())
+                .limit(limit + 1)
                 .select(UserDB::as_select())
                 .load::<UserDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-        let mut users: Vec<UserModel> = users
-            .into_iter()
-            .map(Into::into)
-            .collect();
-
-        let user_ids = users
-            .iter()
-            .map(|u| u.id)
-            .collect::<Vec<i32>>();
-
-        if let Some(true) = filter.with_groups {
-            let group_ids = user_ids.clone(); // TODO: get groups from user ids
-
-            let groups_per_user = repositories::group::get_by_ids(
-                db, group_ids, filter.with_permissions.unwrap_or(false)
+    let mut users: Page<UserModel> = Page::from_rows(res, limit, total, |row| Cursor::from_id(row.id))
+        .map(Into::into);
+
+    let user_ids = users
+        .items
+        .iter()
+        .map(|u| u.id)
+        .collect::<Vec<i32>>();
+
+    if let Some(true) = filter.with_groups {
+        let mut groups_per_user = super::batch_get_groups(db, user_ids).await?;
+
+        if let Some(true) = filter.with_permissions {
+            let mut group_ids = groups_per_user
+                .iter()
+                .flatten()
+                .map(|g| g.id)
+                .collect::<Vec<i32>>();
+            group_ids.sort_unstable();
+            group_ids.dedup();
+
+            let groups_with_perms = repositories::group::get_by_ids(
+                db, group_ids, true
             )
-                .await?;
+                .await?
+                .into_iter()
+                .map(|g| (g.id, g))
+                .collect::<HashMap<i32, GroupModel>>();
 
-            users
+            groups_per_user
                 .iter_mut()
-                .zip(groups_per_user)
-                .for_each(|(u, g)| {
-                    u.groups = g;
+                .flatten()
+                .for_each(|g| {
+                    if let Some(group) = groups_with_perms.get(&g.id) {
+                        g.permissions = group.permissions.clone();
+                    }
                 });
         }
 
+        users
+            .items
+            .iter_mut()
+            .zip(groups_per_user)
+            .for_each(|(u, g)| {
+                u.groups = Some(g);
+            });
+    }
+
     Ok(users)
 }
@@ -17,6 +17,7 @@ pub struct UserDB {
     pub is_active: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub email: Option<String>,
 }
 
 impl Into<UserModel> for UserDB {
@@ -28,6 +29,7 @@ impl Into<UserModel> for UserDB {
             nickname: self.nickname,
             avatar_uri: self.avatar_uri,
             is_active: self.is_active,
+            email: self.email,
             groups: None,
             created_at: self.created_at,
             updated_at: self.updated_at,
@@ -44,6 +46,7 @@ impl Into<UserModel> for (UserDB, Vec<GroupDB>) {
             nickname: self.0.nickname,
             avatar_uri: self.0.avatar_uri,
             is_active: self.0.is_active,
+            email: self.0.email,
             groups: Some(self.1.into_iter().map(|g| g.into()).collect()),
             created_at: self.0.created_at,
             updated_at: self.0.updated_at,
@@ -2,6 +2,7 @@ pub mod create;
 pub mod delete;
 pub mod read;
 pub mod schema;
+pub mod sync;
 
 pub use schema::UserGroupDB;
 
@@ -18,3 +19,5 @@ pub use read::{
 };
 
 pub use delete::delete_by_id;
+
+pub use sync::sync_by_source;
@@ -5,17 +5,18 @@ use crate::domain::models::user_group::UserGroupModel;
 use crate::infra::db::schema::users_groups_rel;
 use crate::infra::repositories::{
     error::{RepoError, RepoResult, map_interact_error},
-    default_skip,
+    pagination::{Cursor, Page, clamp_limit, deserialize_cursor},
     default_limit,
 };
 use super::schema::UserGroupDB;
 
 #[derive(Debug, Deserialize)]
 pub struct UsersGroupsFilter {
-    #[serde(default = "default_skip")]
-    skip: i64,
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    cursor: Option<Cursor>,
     #[serde(default = "default_limit")]
     limit: i64,
+    with_total: Option<bool>,
 }
 
 pub async fn get_by_id(
@@ -74,29 +75,44 @@ pub async fn try_get_by_id(
 pub async fn get_all(
     db: &deadpool_diesel::postgres::Pool,
     filter: UsersGroupsFilter,
-) -> RepoResult<Vec<UserGroupModel>> {
+) -> RepoResult<Page<UserGroupModel>> {
     let conn = db
         .get()
         .await
         .map_err(RepoError::Pool)?;
 
-    let res = conn
+    let limit = clamp_limit(filter.limit);
+    let (res, total) = conn
         .interact(move |conn| {
-            users_groups_rel::table
-                .into_boxed::<diesel::pg::Pg>()
-                .offset(filter.skip)
-                .limit(filter.limit)
+            let total = match filter.with_total {
+                Some(true) => Some(users_groups_rel::table.count().get_result::<i64>(conn)?),
+                _ => None,
+            };
+
+            let mut query = users_groups_rel::table
+                .into_boxed::<diesel::pg::Pg>();
+
+            if let Some(cursor) = &filter.cursor {
+                let rel_id = cursor.rel_id.unwrap_or_default();
+                query = query.filter(
+                    users_groups_rel::user_id.gt(cursor.id)
+                        .or(users_groups_rel::user_id.eq(cursor.id).and(users_groups_rel::group_id.gt(rel_id)))
+                );
+            }
+
+            query
+                .order((users_groups_rel::user_id.asc(), users_groups_rel::group_id.asc()))
+                .limit(limit + 1)
                 .select(UserGroupDB::as_select())
                 .load::<UserGroupDB>(conn)
+                .map(|res| (res, total))
         })
         .await
         .map_err(map_interact_error)?
         .map_err(RepoError::Diesel)?;
 
-    let users_groups: Vec<UserGroupModel> = res
-        .into_iter()
-        .map(Into::into)
-        .collect();
+    let users_groups = Page::from_rows(res, limit, total, |row| Cursor::from_ids(row.user_id, row.group_id))
+        .map(Into::into);
 
     Ok(users_groups)
 }
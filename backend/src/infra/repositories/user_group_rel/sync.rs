@@ -0,0 +1,56 @@
+use diesel::prelude::*;
+
+use crate::infra::db::schema::{groups, users_groups_rel};
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+
+/// Makes the memberships a provider synced for a user match the groups it
+/// lists now. Names matching no group are ignored, and memberships granted
+/// through the API or by another provider are left alone.
+pub async fn sync_by_source(
+    db: &deadpool_diesel::postgres::Pool,
+    user_id: i32,
+    source: String,
+    group_names: Vec<String>,
+) -> RepoResult<()> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let group_ids = groups::table
+                    .filter(groups::name.eq_any(group_names))
+                    .select(groups::id)
+                    .load::<i32>(conn)?;
+
+                diesel::delete(
+                    users_groups_rel::table
+                        .filter(users_groups_rel::user_id.eq(user_id))
+                        .filter(users_groups_rel::source.eq(&source))
+                        .filter(users_groups_rel::group_id.ne_all(&group_ids))
+                )
+                .execute(conn)?;
+
+                let rels = group_ids
+                    .iter()
+                    .map(|group_id| (
+                        users_groups_rel::user_id.eq(user_id),
+                        users_groups_rel::group_id.eq(*group_id),
+                        users_groups_rel::source.eq(&source),
+                    ))
+                    .collect::<Vec<_>>();
+
+                diesel::insert_into(users_groups_rel::table)
+                    .values(rels)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use diesel::prelude::*;
+
+use crate::domain::models::user::UserModel;
+use crate::infra::db::schema::{user_identities, users};
+use crate::infra::repositories::{
+    error::{RepoError, RepoResult, map_interact_error},
+    user::{NewUserDB, UserDB},
+};
+
+#[derive(Insertable)]
+#[diesel(table_name = user_identities)]
+pub struct NewUserIdentityDB {
+    pub provider: String,
+    pub subject: String,
+}
+
+/// Creates an active user for an identity vouched for by an external
+/// provider, linked to that identity.
+pub async fn provision(
+    db: &deadpool_diesel::postgres::Pool,
+    new_user: NewUserDB,
+    new_identity: NewUserIdentityDB,
+) -> RepoResult<UserModel> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(|conn| {
+            conn.transaction(|conn| {
+                let user = diesel::insert_into(users::table)
+                    .values((new_user, users::is_active.eq(true)))
+                    .returning(UserDB::as_returning())
+                    .get_result(conn)?;
+
+                diesel::insert_into(user_identities::table)
+                    .values((new_identity, user_identities::user_id.eq(user.id)))
+                    .execute(conn)?;
+
+                Ok(user)
+            })
+        })
+        .await
+        .map_err(map_interact_error)?
+        .map_err(RepoError::Diesel)?;
+
+    Ok(res.into())
+}
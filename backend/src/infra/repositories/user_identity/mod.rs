@@ -0,0 +1,12 @@
+pub mod create;
+pub mod read;
+pub mod schema;
+
+pub use schema::UserIdentityDB;
+
+pub use create::{
+    NewUserIdentityDB,
+    provision,
+};
+
+pub use read::try_get_by_subject;
@@ -0,0 +1,34 @@
+use diesel::prelude::*;
+
+use crate::domain::models::user_identity::UserIdentityModel;
+use crate::infra::db::schema::user_identities;
+use crate::infra::repositories::error::{RepoError, RepoResult, map_interact_error};
+use super::schema::UserIdentityDB;
+
+pub async fn try_get_by_subject(
+    db: &deadpool_diesel::postgres::Pool,
+    provider: String,
+    subject: String,
+) -> RepoResult<Option<UserIdentityModel>> {
+    let conn = db
+        .get()
+        .await
+        .map_err(RepoError::Pool)?;
+
+    let res = conn
+        .interact(move |conn| {
+            user_identities::table
+                .filter(user_identities::provider.eq(provider))
+                .filter(user_identities::subject.eq(subject))
+                .select(UserIdentityDB::as_select())
+                .first(conn)
+        })
+        .await
+        .map_err(map_interact_error)?;
+
+    match res {
+        Ok(res) => Ok(Some(res.into())),
+        Err(diesel::NotFound) => Ok(None),
+        Err(e) => Err(RepoError::Diesel(e)),
+    }
+}
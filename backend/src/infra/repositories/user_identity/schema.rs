@@ -0,0 +1,30 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::domain::models::user_identity::UserIdentityModel;
+use crate::infra::db::schema::user_identities;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = user_identities)]         // Use the 'user_identities' table
+#[diesel(check_for_backend(diesel::pg::Pg))]    // Check compatibility with PostgreSQL
+pub struct UserIdentityDB {
+    pub id: i32,
+    pub user_id: i32,
+    pub provider: String,
+    pub subject: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Into<UserIdentityModel> for UserIdentityDB {
+    fn into(self) -> UserIdentityModel {
+        UserIdentityModel {
+            id: self.id,
+            user_id: self.user_id,
+            provider: self.provider,
+            subject: self.subject,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod bootstrap;
+pub mod cli;
 pub mod domain;
 pub mod error;
 pub mod infra;
@@ -1,15 +1,30 @@
-use std::net::{SocketAddr, IpAddr, Ipv4Addr};
+use std::{net::{SocketAddr, IpAddr, Ipv4Addr}, path::PathBuf, time::Duration};
 
-use axum::http::HeaderValue;
-use clap::Parser;
+use axum::http::{HeaderName, HeaderValue};
+use clap::{CommandFactory, Parser, builder::RangedU64ValueParser, error::ErrorKind};
 use tower_http::cors::AllowOrigin;
 
 use backend::AppResult;
+use backend::cli::Command;
+use backend::domain::{
+    lockout::LockoutPolicy,
+    password::{PasswordPolicy, MAX_CLASSES},
+};
+use backend::infra::{
+    jwt_keys::JwtKeys,
+    ldap::LdapConfig,
+    mailer::{MailerBackend, MailerConfig},
+    oidc::OidcConfig,
+};
+use backend::routes::auth::authenticator::AuthBackend;
+use backend::server::AuthConfig;
 use backend::logger::setup_logging;
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
     #[clap(default_value = "0.0.0.0", long, env)]
     hostname: String,
     #[clap(default_value = "8080", long, env)]
@@ -22,8 +37,131 @@ struct Args {
     otlp_endpoint: Option<String>,
     #[clap(long, env)]
     json_log: bool,
+    /// Secret keying what only this server reads, such as audit digests and
+    /// 2FA challenges, and signing access tokens without `--jwt-key-dir`
+    #[clap(long, env, required = true)]
+    jwt_secret: Option<String>,
+    /// Directory of the RSA or Ed25519 private keys signing access tokens,
+    /// PEM encoded and named `<kid>.pem`. All of them are published at
+    /// `/.well-known/jwks.json` and accepted, `SIGHUP` reloads them
     #[clap(long, env)]
-    jwt_secret: String,
+    jwt_key_dir: Option<PathBuf>,
+    /// Kid of the key signing access tokens unless the `signing_kid` file of
+    /// the key directory names one, the last one in order of name when unset
+    #[clap(long, env, requires = "jwt_key_dir")]
+    jwt_signing_kid: Option<String>,
+    /// Seconds sessions, API keys, users and their permissions are cached
+    /// for, 0 disables the cache
+    #[clap(default_value = "30", long, env)]
+    auth_cache_ttl_secs: u64,
+    /// Check permissions against the claims of access tokens, which only
+    /// change when the token is refreshed, instead of the database
+    #[clap(long, env)]
+    trust_token_claims: bool,
+    /// Authenticators checking the credentials given to `/login`, in order.
+    /// One that can't reach its backend fails the login rather than handing
+    /// over to the next
+    #[clap(default_value = "local", long, env, value_delimiter = ',')]
+    auth_backends: Vec<AuthBackend>,
+    /// URL of the LDAP directory, required by the `ldap` backend
+    #[clap(long, env, required_if_eq("auth_backends", "ldap"), requires = "ldap_base_dn")]
+    ldap_url: Option<String>,
+    /// Upgrade `ldap://` connections with StartTLS
+    #[clap(long, env)]
+    ldap_starttls: bool,
+    /// Account used to look users up, binds anonymously when unset
+    #[clap(long, env, requires = "ldap_bind_password")]
+    ldap_bind_dn: Option<String>,
+    #[clap(long, env)]
+    ldap_bind_password: Option<String>,
+    #[clap(long, env)]
+    ldap_base_dn: Option<String>,
+    /// Filter finding the entry of a user, `{username}` is replaced by the
+    /// escaped username
+    #[clap(default_value = "(uid={username})", long, env)]
+    ldap_user_filter: String,
+    /// Attribute of user entries listing their groups, synced into the
+    /// groups of the user
+    #[clap(default_value = "memberOf", long, env)]
+    ldap_group_attribute: String,
+    /// Issuer of the OpenID Connect provider, enables `/auth/oidc/login`
+    #[clap(long, env, requires_all = ["oidc_client_id", "oidc_redirect_uri"])]
+    oidc_issuer: Option<String>,
+    #[clap(long, env)]
+    oidc_client_id: Option<String>,
+    #[clap(long, env)]
+    oidc_client_secret: Option<String>,
+    /// Public URL of `/auth/oidc/callback`
+    #[clap(long, env)]
+    oidc_redirect_uri: Option<String>,
+    #[clap(default_value = "openid profile email", long, env)]
+    oidc_scopes: String,
+    /// ID token claim whose groups are synced into the groups of the user
+    #[clap(default_value = "groups", long, env)]
+    oidc_groups_claim: String,
+    /// Where browsers are sent after logging in through the provider
+    #[clap(long, env)]
+    oidc_post_login_redirect: Option<String>,
+    /// How emails such as verification links are sent, none are when unset
+    #[clap(long, env)]
+    mailer: Option<MailerBackend>,
+    /// Sender of the emails
+    #[clap(default_value = "Data Repo <noreply@localhost>", long, env)]
+    mail_from: String,
+    /// `smtp://` or `smtps://` URL of the relay, credentials included,
+    /// required by the `smtp` mailer
+    #[clap(long, env, required_if_eq("mailer", "smtp"))]
+    smtp_url: Option<String>,
+    /// Directory emails are written to, required by the `file` mailer
+    #[clap(long, env, required_if_eq("mailer", "file"))]
+    mail_dir: Option<PathBuf>,
+    /// Let users register themselves through `/v1/users/register`, which
+    /// needs a mailer
+    #[clap(long, env, requires = "mailer")]
+    allow_registration: bool,
+    /// URL the API is reached at, for links sent by email
+    #[clap(default_value = "http://localhost:8080", long, env)]
+    public_url: String,
+    /// Minimum number of characters of the passwords users set
+    #[clap(default_value = "8", long, env)]
+    password_min_length: usize,
+    /// Minimum number of character classes mixed by the passwords users set,
+    /// among lowercase letters, uppercase letters, digits and symbols
+    #[clap(
+        default_value = "1",
+        long,
+        env,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..=MAX_CLASSES as u64),
+    )]
+    password_min_classes: usize,
+    /// File of breached passwords, one per line, refused whatever their
+    /// strength
+    #[clap(long, env)]
+    breached_passwords_file: Option<PathBuf>,
+    /// Failed logins in a row of a username or from an address before each
+    /// further one delays the next attempt
+    #[clap(default_value = "3", long, env)]
+    login_free_failures: u16,
+    /// Seconds logins are delayed by after the first failure past the free
+    /// ones, doubled by each further one
+    #[clap(default_value = "1", long, env)]
+    login_backoff_secs: u32,
+    /// Failed logins in a row locking a username out
+    #[clap(default_value = "10", long, env)]
+    login_max_failures: u16,
+    /// Failed logins in a row locking an address out
+    #[clap(default_value = "100", long, env)]
+    login_max_failures_per_ip: u16,
+    /// Seconds lockouts last, and failed logins are remembered for
+    #[clap(default_value = "900", long, env)]
+    login_lockout_secs: u32,
+    /// Header the reverse proxy sets to the address of the client, e.g.
+    /// `X-Forwarded-For`, which failed logins are counted against
+    #[clap(long, env)]
+    client_ip_header: Option<HeaderName>,
+    /// Issuer authenticator apps list the accounts enrolled in 2FA under
+    #[clap(default_value = "Data Repo", long, env)]
+    totp_issuer: String,
 }
 
 #[tokio::main]
@@ -36,6 +174,7 @@ async fn main() -> AppResult<()> {
     let args = Args::parse();
 
     let Args {
+        command,
         hostname,
         port,
         cors_allow_origin,
@@ -43,10 +182,84 @@ async fn main() -> AppResult<()> {
         otlp_endpoint,
         json_log,
         jwt_secret,
+        jwt_key_dir,
+        jwt_signing_kid,
+        auth_cache_ttl_secs,
+        trust_token_claims,
+        auth_backends,
+        ldap_url,
+        ldap_starttls,
+        ldap_bind_dn,
+        ldap_bind_password,
+        ldap_base_dn,
+        ldap_user_filter,
+        ldap_group_attribute,
+        oidc_issuer,
+        oidc_client_id,
+        oidc_client_secret,
+        oidc_redirect_uri,
+        oidc_scopes,
+        oidc_groups_claim,
+        oidc_post_login_redirect,
+        mailer,
+        mail_from,
+        smtp_url,
+        mail_dir,
+        allow_registration,
+        public_url,
+        password_min_length,
+        password_min_classes,
+        breached_passwords_file,
+        login_free_failures,
+        login_backoff_secs,
+        login_max_failures,
+        login_max_failures_per_ip,
+        login_lockout_secs,
+        client_ip_header,
+        totp_issuer,
     } = args;
 
     setup_logging(otlp_endpoint, json_log);
 
+    let mut password_policy = PasswordPolicy::new(password_min_length, password_min_classes);
+    if let Some(path) = breached_passwords_file {
+        password_policy = match password_policy.with_breached_list(&path) {
+            Ok(password_policy) => password_policy,
+            Err(err) => Args::command()
+                .error(ErrorKind::Io, format!("failed to read {}: {}", path.display(), err))
+                .exit(),
+        };
+        tracing::info!("Loaded {} breached passwords", password_policy.breached_count());
+    }
+
+    let command = command.unwrap_or(Command::Serve);
+    if !matches!(command, Command::Serve) {
+        if let Err(err) = backend::cli::run(command, database_url, password_policy).await {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let Some(jwt_secret) = jwt_secret else {
+        Args::command()
+            .error(ErrorKind::MissingRequiredArgument, "the following required argument was not provided: --jwt-secret")
+            .exit()
+    };
+
+    let jwt_keys = match jwt_key_dir {
+        Some(dir) => match JwtKeys::from_dir(dir, jwt_signing_kid) {
+            Ok(jwt_keys) => jwt_keys,
+            Err(err) => Args::command()
+                .error(ErrorKind::Io, format!("failed to load the JWT keys: {}", err))
+                .exit(),
+        },
+        None => JwtKeys::from_secret(&jwt_secret),
+    };
+    if let Some(kid) = jwt_keys.signing_kid() {
+        tracing::info!("Signing access tokens with key {}", kid);
+    }
+
     let cors_allow_origin = cors_allow_origin.map(|cors_allow_origin| {
         AllowOrigin::list(
             cors_allow_origin
@@ -55,6 +268,33 @@ async fn main() -> AppResult<()> {
         )
     });
 
+    let ldap = ldap_url.map(|url| LdapConfig {
+        url,
+        starttls: ldap_starttls,
+        bind_dn: ldap_bind_dn,
+        bind_password: ldap_bind_password,
+        base_dn: ldap_base_dn.unwrap_or_default(),
+        user_filter: ldap_user_filter,
+        group_attribute: ldap_group_attribute,
+    });
+
+    let oidc = oidc_issuer.map(|issuer| OidcConfig {
+        issuer,
+        client_id: oidc_client_id.unwrap_or_default(),
+        client_secret: oidc_client_secret,
+        redirect_uri: oidc_redirect_uri.unwrap_or_default(),
+        scopes: oidc_scopes,
+        groups_claim: oidc_groups_claim,
+        post_login_redirect: oidc_post_login_redirect,
+    });
+
+    let mailer = mailer.map(|backend| MailerConfig {
+        backend,
+        from: mail_from,
+        smtp_url,
+        dir: mail_dir,
+    });
+
     let addr = match hostname.parse() {
         Ok(ip) => SocketAddr::new(ip, port),
         Err(_) => {
@@ -67,7 +307,28 @@ async fn main() -> AppResult<()> {
         addr,
         cors_allow_origin,
         database_url,
-        jwt_secret,
+        AuthConfig {
+            jwt_secret,
+            jwt_keys,
+            backends: auth_backends,
+            ldap,
+            oidc,
+            cache_ttl: Duration::from_secs(auth_cache_ttl_secs),
+            trust_token_claims,
+            allow_registration,
+            public_url,
+            password_policy,
+            lockout_policy: LockoutPolicy {
+                free_failures: login_free_failures.into(),
+                backoff: Duration::from_secs(login_backoff_secs.into()),
+                max_failures: login_max_failures.into(),
+                max_failures_per_ip: login_max_failures_per_ip.into(),
+                lockout: Duration::from_secs(login_lockout_secs.into()),
+            },
+            client_ip_header,
+            totp_issuer,
+        },
+        mailer,
     ).await?;
 
     Ok(())
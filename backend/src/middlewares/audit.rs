@@ -0,0 +1,234 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::{FromRequestParts, MatchedPath, RawPathParams, Request},
+    http::{header, Method},
+    response::Response,
+};
+use futures_util::{future::BoxFuture, StreamExt};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tower::{Layer, Service};
+
+use crate::{
+    infra::repositories::{self, audit_log::NewAuditLogDB},
+    server::AppState,
+};
+
+/// Largest created entity response read back to find the id of the new row.
+const MAX_CREATED_BODY: u64 = 64 * 1024;
+
+/// Id of the authenticated user, attached to the response by `AuthService`
+/// so that the audit layer, which runs outside of it, can record who made
+/// the request.
+#[derive(Clone, Copy, Debug)]
+pub struct AuditActor(pub i32);
+
+/// Digest of a request body, fed as the handler reads the body and only
+/// recorded when it was read to the end. It is keyed with a key derived from
+/// the server secret, bodies carrying passwords can't be guessed back from
+/// it.
+struct BodyDigest {
+    mac: Hmac<Sha256>,
+    done: bool,
+}
+
+impl BodyDigest {
+    fn new(key: &[u8]) -> Self {
+        Self {
+            mac: Hmac::new_from_slice(key).expect("HMAC accepts any key length"),
+            done: false,
+        }
+    }
+}
+
+/// Key of the body digests, kept apart from the uses of the server secret
+/// itself.
+pub fn digest_key(secret: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts any key length");
+    mac.update(b"audit-digest");
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn is_audited(method: &Method) -> bool {
+    matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE)
+}
+
+/// The last numeric path parameter, e.g. the user of
+/// `/v1/groups/:id/users/:user_id`.
+fn path_target_id(params: &RawPathParams) -> Option<i32> {
+    params
+        .iter()
+        .filter_map(|(_, value)| value.parse::<i32>().ok())
+        .last()
+}
+
+/// Reads back the `data.id` of a successful JSON response, which is how
+/// creation handlers return the new row. The body is handed back untouched.
+async fn created_id(response: Response) -> (Response, Option<i32>) {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|typ| typ.as_bytes().starts_with(b"application/json"));
+    let is_small = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size <= MAX_CREATED_BODY);
+
+    if !response.status().is_success() || !is_json || !is_small {
+        return (response, None);
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_CREATED_BODY as usize).await else {
+        return (Response::from_parts(parts, Body::empty()), None);
+    };
+
+    let id = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|json| json["data"]["id"].as_i64())
+        .and_then(|id| i32::try_from(id).ok());
+
+    (Response::from_parts(parts, Body::from(bytes)), id)
+}
+
+/// Appends an audit log for every POST, PUT, PATCH and DELETE request that
+/// matched a route, once the handler has responded.
+#[derive(Clone)]
+pub struct AuditLayer {
+    state: AppState,
+}
+
+impl AuditLayer {
+    pub fn new(state: AppState) -> Self {
+        Self {
+            state,
+        }
+    }
+}
+
+impl<S> Layer<S> for AuditLayer {
+    type Service = AuditService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuditService {
+            inner,
+            state: self.state.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuditService<S> {
+    inner: S,
+    state: AppState,
+}
+
+impl<S> Service<Request> for AuditService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let not_ready_inner = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, not_ready_inner);
+
+        // Requests that matched no route changed nothing.
+        let route = req
+            .extensions()
+            .get::<MatchedPath>()
+            .filter(|_| is_audited(req.method()))
+            .map(|path| path.as_str().to_string());
+
+        let Some(route) = route else {
+            return Box::pin(inner.call(req));
+        };
+
+        let pg_pool = self.state.pg_pool.clone();
+        let digest_key = self.state.audit_key.clone();
+
+        Box::pin(async move {
+            let (mut parts, body) = req.into_parts();
+            let method = parts.method.to_string();
+            let target_id = RawPathParams::from_request_parts(&mut parts, &())
+                .await
+                .ok()
+                .and_then(|params| path_target_id(&params));
+
+            // The body is hashed while the handler streams it, so large
+            // imports are never buffered here.
+            let digest = Arc::new(Mutex::new(BodyDigest::new(&digest_key)));
+            let hashed = {
+                let digest = digest.clone();
+                body.into_data_stream().map(move |chunk| {
+                    if let (Ok(chunk), Ok(mut digest)) = (&chunk, digest.lock()) {
+                        digest.mac.update(chunk);
+                    }
+                    chunk
+                })
+            };
+            let finished = {
+                let digest = digest.clone();
+                futures_util::stream::poll_fn(move |_| {
+                    if let Ok(mut digest) = digest.lock() {
+                        digest.done = true;
+                    }
+                    Poll::Ready(None)
+                })
+            };
+            let req = Request::from_parts(parts, Body::from_stream(hashed.chain(finished)));
+
+            let response = inner.call(req).await?;
+
+            let user_id = response
+                .extensions()
+                .get::<AuditActor>()
+                .map(|actor| actor.0);
+            let (response, target_id) = match target_id {
+                Some(target_id) => (response, Some(target_id)),
+                None if method == Method::POST.as_str() => created_id(response).await,
+                None => (response, None),
+            };
+            let body_digest = digest
+                .lock()
+                .ok()
+                .filter(|digest| digest.done)
+                .map(|digest| hex::encode(digest.mac.clone().finalize().into_bytes()));
+
+            let res = repositories::audit_log::create(
+                &pg_pool,
+                NewAuditLogDB {
+                    user_id,
+                    method,
+                    route: route.clone(),
+                    target_id,
+                    body_digest,
+                    status: response.status().as_u16() as i32,
+                },
+            )
+                .await;
+
+            // The request already took effect, failing it now would only
+            // hide that from the client.
+            if let Err(err) = res {
+                tracing::error!("Failed to write audit log for {}: {}", route, err);
+            }
+
+            Ok(response)
+        })
+    }
+}
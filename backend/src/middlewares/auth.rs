@@ -7,19 +7,173 @@ use axum::{
 
 use axum_extra::extract::cookie::CookieJar;
 use futures_util::future::BoxFuture;
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use jsonwebtoken::Validation;
 use tower::{Layer, Service};
 use std::task::{Context, Poll};
 
 use crate::{
-    infra::repositories,
+    infra::repositories::{self, error::RepoError},
+    middlewares::audit::AuditActor,
     routes::auth::{
+        api_key,
         error::AuthError,
         login::TokenClaims,
     },
-    server::AppState, domain::models::user::UserModel,
+    server::AppState,
+    domain::{
+        models::{api_key::ApiKeyModel, user::UserModel},
+        policy::{Policy, Requirement},
+    },
 };
 
+/// Who a request authenticated as. Added to the request extensions for the
+/// checks handlers make themselves.
+#[derive(Clone, Debug)]
+pub struct Caller {
+    pub user: UserModel,
+    pub key: Option<ApiKeyModel>,
+    /// Permission ids the access token was issued with, `None` for API keys
+    pub token_perms: Option<Vec<i32>>,
+}
+
+/// How stale the recorded last use of an API key may get.
+const KEY_TOUCH_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+/// The active user a JWT or an API key belongs to, along with the key when
+/// the token is one.
+async fn authenticate(
+    state: &AppState,
+    token: &str,
+) -> Result<Caller, AuthError> {
+    let pg_pool = &state.pg_pool;
+    let now = chrono::Utc::now().naive_utc();
+
+    let (user_id, key, token_perms) = match api_key::parse(token) {
+        Some((prefix, secret)) => {
+            let key = state.auth_cache
+                .api_key(pg_pool, token, prefix.to_string(), |key| {
+                    api_key::verify(secret, &key.hashed_secret)
+                })
+                .await
+                .map_err(AuthError::RepoError)?
+                .ok_or(AuthError::InvalidToken)?;
+
+            if key.is_expired(now) {
+                return Err(AuthError::InvalidToken);
+            }
+
+            (key.user_id, Some(key), None)
+        }
+        None => {
+            let claims = state.jwt_keys
+                .decode::<TokenClaims>(token, Validation::default())
+                .map_err(|_| AuthError::InvalidToken)?
+                .claims;
+
+            let session = state.auth_cache
+                .session(pg_pool, claims.sid)
+                .await
+                .map_err(AuthError::RepoError)?
+                .filter(|session| session.user_id == claims.sub)
+                .ok_or(AuthError::InvalidToken)?;
+
+            if !session.is_live(now) {
+                return Err(AuthError::SessionRevoked);
+            }
+
+            (claims.sub, None, Some(claims.perms))
+        }
+    };
+
+    let user = state.auth_cache
+        .user(pg_pool, user_id)
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    if !user.is_active {
+        return Err(AuthError::UserNotActive);
+    }
+
+    // Uses are recorded once a minute at most, not on every request.
+    if let Some(key) = key.as_ref().filter(|key| {
+        key.last_used_at.is_none_or(|at| now - at >= KEY_TOUCH_INTERVAL)
+    }) {
+        repositories::api_key::touch_by_id(
+            pg_pool, key.id
+        )
+            .await
+            .map_err(AuthError::RepoError)?;
+        state.auth_cache.api_key_used(key.id, now);
+    }
+
+    Ok(Caller {
+        user,
+        key,
+        token_perms,
+    })
+}
+
+/// What a caller holds: their permissions, narrowed to its scopes when they
+/// used an API key.
+pub struct Grants {
+    policy: Policy,
+    scopes: Option<Policy>,
+}
+
+impl Grants {
+    pub fn allows(&self, perm: &str) -> bool {
+        self.policy.allows(perm)
+            && self.scopes.as_ref().is_none_or(|scopes| scopes.allows(perm))
+    }
+}
+
+/// What the caller holds. Access tokens are taken at their word when the
+/// server trusts their claims, which then only change when the token is
+/// refreshed.
+pub async fn grants(
+    state: &AppState,
+    caller: &Caller,
+) -> Result<Grants, RepoError> {
+    let names = match (state.trust_token_claims, &caller.token_perms) {
+        (true, Some(token_perms)) => {
+            let mut names = Vec::with_capacity(token_perms.len());
+            for perm_id in token_perms {
+                let name = state.auth_cache
+                    .permission_name(&state.pg_pool, *perm_id)
+                    .await?;
+                names.extend(name);
+            }
+            names
+        }
+        _ => state.auth_cache
+            .permissions(&state.pg_pool, caller.user.id)
+            .await?
+            .iter()
+            .map(|perm| perm.name.clone())
+            .collect(),
+    };
+
+    Ok(Grants {
+        policy: Policy::new(names),
+        // An API key only holds the permissions it was scoped to that its
+        // user still has.
+        scopes: caller.key.as_ref().map(|key| Policy::new(&key.scopes)),
+    })
+}
+
+/// Whether the caller meets `requirement`.
+async fn is_granted(
+    state: &AppState,
+    caller: &Caller,
+    requirement: &Requirement,
+) -> Result<bool, AuthError> {
+    let grants = grants(state, caller)
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    Ok(requirement.is_met(|perm| grants.allows(perm)))
+}
+
 pub async fn auth<B>(
     cookie_jar: CookieJar,
     State(state): State<AppState>,
@@ -34,35 +188,21 @@ pub async fn auth<B>(
                 .get(header::AUTHORIZATION)
                 .and_then(|auth_header| auth_header.to_str().ok())
                 .and_then(|auth_value| {
-                    if auth_value.starts_with("Bearer ") {
-                        Some(auth_value[7..].to_owned())
-                    } else {
-                        None
-                    }
+                    auth_value
+                        .strip_prefix("Bearer ")
+                        .map(ToOwned::to_owned)
                 })
         });
 
     let token = token.ok_or(AuthError::Unauthorized)?;
 
-    let claims = decode::<TokenClaims>(
-        &token,
-        &DecodingKey::from_secret(state.jwt_secret.as_ref()),
-        &Validation::default(),
-    )
-        .map_err(|_| AuthError::InvalidToken)?
-        .claims;
-
-    let user = repositories::user::get_by_id(
-        &state.pg_pool, claims.sub
-    )
-        .await
-        .map_err(AuthError::RepoError)?;
+    let caller = authenticate(&state, &token).await?;
 
-    if !user.is_active {
-        return Err(AuthError::UserNotActive);
+    if let Some(key) = &caller.key {
+        req.extensions_mut().insert(key.clone());
     }
-
-    req.extensions_mut().insert(user);
+    req.extensions_mut().insert(caller.user.clone());
+    req.extensions_mut().insert(caller);
 
     Ok(next.run(req).await)
 }
@@ -70,14 +210,38 @@ pub async fn auth<B>(
 #[derive(Clone)]
 pub struct AuthLayer {
     state: AppState,
-    permission: Option<String>,
+    requirement: Option<Requirement>,
 }
 
 impl AuthLayer {
     pub fn new(state: AppState, permission: Option<String>) -> Self {
+        Self::requiring(state, permission.map(Requirement::from))
+    }
+
+    /// Requires at least one of the permissions.
+    pub fn any_of(state: AppState, permissions: &[&str]) -> Self {
+        Self::requiring(state, Some(Requirement::AnyOf(
+            permissions.iter().map(ToString::to_string).collect(),
+        )))
+    }
+
+    /// Requires every one of the permissions.
+    pub fn all_of(state: AppState, permissions: &[&str]) -> Self {
+        Self::requiring(state, Some(Requirement::AllOf(
+            permissions.iter().map(ToString::to_string).collect(),
+        )))
+    }
+
+    fn requiring(state: AppState, requirement: Option<Requirement>) -> Self {
+        // Registered so that the permission catalogue can be reconciled
+        // with the routes on startup.
+        if let Some(requirement) = &requirement {
+            state.permission_registry.register(requirement.permissions());
+        }
+
         Self {
             state,
-            permission,
+            requirement,
         }
     }
 }
@@ -89,7 +253,7 @@ impl<S> Layer<S> for AuthLayer {
         AuthService {
             inner,
             state: self.state.clone(),
-            permission: self.permission.clone(),
+            requirement: self.requirement.clone(),
         }
     }
 }
@@ -98,7 +262,7 @@ impl<S> Layer<S> for AuthLayer {
 pub struct AuthService<S> {
     inner: S,
     state: AppState,
-    permission: Option<String>,
+    requirement: Option<Requirement>,
 }
 
 impl<S> Service<Request> for AuthService<S>
@@ -115,7 +279,7 @@ where
     }
 
     fn call(&mut self, mut req: Request) -> Self::Future {
-        let token = CookieJar::from_headers(&req.headers())
+        let token = CookieJar::from_headers(req.headers())
             .get("token")
             .map(|cookie| cookie.value().to_string())
             .or_else(|| {
@@ -123,64 +287,47 @@ where
                     .get(header::AUTHORIZATION)
                     .and_then(|auth_header| auth_header.to_str().ok())
                     .and_then(|auth_value| {
-                        if auth_value.starts_with("Bearer ") {
-                            Some(auth_value[7..].to_owned())
-                        } else {
-                            None
-                        }
+                        auth_value
+                            .strip_prefix("Bearer ")
+                            .map(ToOwned::to_owned)
                     })
             });
 
         let not_ready_inner = self.inner.clone();
         let mut inner = std::mem::replace(&mut self.inner, not_ready_inner);
-        let jwt_secret = self.state.jwt_secret.clone();
-        let pg_pool = self.state.pg_pool.clone();
-        let permission = self.permission.clone();
+        let state = self.state.clone();
+        let requirement = self.requirement.clone();
 
         Box::pin(async move {
-            let user: Result<UserModel, AuthError> = async move {
+            let user: Result<Caller, AuthError> = async move {
                 let token = token.ok_or(AuthError::Unauthorized)?;
 
-                let claims = decode::<TokenClaims>(
-                    &token,
-                    &DecodingKey::from_secret(jwt_secret.as_ref()),
-                    &Validation::default(),
-                )
-                    .map_err(|_| AuthError::InvalidToken)?
-                    .claims;
-
-                let user = repositories::user::get_by_id(
-                    &pg_pool, claims.sub
-                )
-                    .await
-                    .map_err(AuthError::RepoError)?;
-
-                if !user.is_active {
-                    return Err(AuthError::UserNotActive);
-                }
+                let caller = authenticate(&state, &token).await?;
 
-                if let Some(permission) = permission {
-                    let perms = repositories::user::get_permissions(
-                        &pg_pool, user.id
-                    )
-                        .await
-                        .map_err(AuthError::RepoError)?;
-
-                    if !perms.iter().any(|perm| perm.name == permission) {
+                if let Some(requirement) = requirement {
+                    if !is_granted(&state, &caller, &requirement).await? {
                         return Err(AuthError::PermissionDenied);
                     }
                 }
 
-                Ok(user)
+                Ok(caller)
             }
                 .await;
 
             match user {
-                Ok(user) => {
-                    req.extensions_mut().insert(user);
-                    inner.call(req).await
+                Ok(caller) => {
+                    let actor = AuditActor(caller.user.id);
+                    req.extensions_mut().insert(caller.user.clone());
+                    if let Some(key) = &caller.key {
+                        req.extensions_mut().insert(key.clone());
+                    }
+                    req.extensions_mut().insert(caller);
+
+                    let mut response = inner.call(req).await?;
+                    response.extensions_mut().insert(actor);
+                    Ok(response)
                 }
-                Err(err) => return Ok(err.into_response()),
+                Err(err) => Ok(err.into_response()),
             }
         })
     }
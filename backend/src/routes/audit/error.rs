@@ -0,0 +1,26 @@
+use axum::{response::IntoResponse, http::StatusCode, Json};
+use serde_json::json;
+
+use crate::infra::repositories::error::RepoError;
+
+#[derive(Debug)]
+pub enum AuditError {
+    RepoError(RepoError),
+}
+
+impl IntoResponse for AuditError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, code, err_msg) = match self {
+            Self::RepoError(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                50001,
+                format!("Internal server error."),
+            ),
+        };
+        (
+            status,
+            Json(json!({"code": code, "msg": err_msg})),
+        )
+            .into_response()
+    }
+}
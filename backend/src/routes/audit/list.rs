@@ -0,0 +1,76 @@
+use axum::{extract::{State, Query}, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    infra::repositories::{self, audit_log::AuditLogsFilter},
+    server::AppState,
+};
+use super::{error::AuditError, schema::AuditLogSchema};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogSearchQuery {
+    /// Id of the user who made the request
+    pub user_id: Option<i32>,
+    /// HTTP method
+    pub method: Option<String>,
+    /// Route template, e.g. `/v1/datasets/:id`
+    pub route: Option<String>,
+    /// Id of the entity the request targeted
+    pub target_id: Option<i32>,
+    /// HTTP status of the response
+    pub status: Option<i32>,
+    /// Written at or after, e.g. 2024-01-31T00:00:00
+    pub created_after: Option<String>,
+    /// Written strictly before
+    pub created_before: Option<String>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
+    pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListAuditLogsResponse {
+    code: i32,
+    data: Option<Vec<AuditLogSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
+    msg: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/audit",
+    params(AuditLogSearchQuery),
+    responses(
+        (status = 200, description = "Audit logs, newest first", body = ListAuditLogsResponse),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_audit_logs(
+    State(state): State<AppState>,
+    Query(params): Query<AuditLogsFilter>,
+) -> Result<Json<ListAuditLogsResponse>, AuditError> {
+    let logs = repositories::audit_log::get_all(
+        &state.pg_pool, params
+    )
+        .await
+        .map_err(AuditError::RepoError)?;
+
+    let logs = logs.map(AuditLogSchema::from);
+
+    Ok(Json(ListAuditLogsResponse {
+        code: 0,
+        data: Some(logs.items),
+        next_cursor: logs.next_cursor,
+        total: logs.total,
+        msg: None,
+    }))
+}
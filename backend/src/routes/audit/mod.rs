@@ -0,0 +1,17 @@
+use axum::{routing::get, Router};
+
+use crate::{middlewares::auth::AuthLayer, server::AppState};
+
+pub mod error;
+pub mod list;
+pub mod schema;
+
+pub fn audit_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list::list_audit_logs)
+                .layer(AuthLayer::new(state.clone(), Some("audit.read".to_string()))),
+        )
+        .with_state(state)
+}
@@ -0,0 +1,38 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::models::audit_log::AuditLogModel;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogSchema {
+    pub id: i32,
+    /// Null when the request was not authenticated
+    pub user_id: Option<i32>,
+    pub method: String,
+    /// Route template, e.g. `/v1/datasets/:id`
+    pub route: String,
+    /// Last numeric path parameter, or the id of the created row
+    pub target_id: Option<i32>,
+    /// Hex HMAC-SHA256 of the request body keyed with the server secret, null
+    /// when there was none or the handler did not read it
+    pub body_digest: Option<String>,
+    pub status: i32,
+    #[schema(value_type = String)]
+    created_at: NaiveDateTime,
+}
+
+impl From<AuditLogModel> for AuditLogSchema {
+    fn from(log: AuditLogModel) -> Self {
+        Self {
+            id: log.id,
+            user_id: log.user_id,
+            method: log.method,
+            route: log.route,
+            target_id: log.target_id,
+            body_digest: log.body_digest,
+            status: log.status,
+            created_at: log.created_at,
+        }
+    }
+}
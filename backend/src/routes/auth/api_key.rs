@@ -0,0 +1,67 @@
+use argon2::{
+    password_hash::SaltString,
+    Argon2,
+    PasswordHash,
+    PasswordHasher,
+    PasswordVerifier,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand_core::{OsRng, RngCore};
+
+use super::error::AuthError;
+
+/// Marks a bearer token as an API key rather than a JWT.
+pub const API_KEY_PREFIX: &str = "drk_";
+
+/// Length of the public part of a key, which finds it in the database.
+const KEY_ID_LEN: usize = 8;
+
+/// A freshly generated API key, the secret is only ever shown once.
+pub struct NewApiKey {
+    /// Full key handed to the client, `drk_<prefix><secret>`
+    pub key: String,
+    pub prefix: String,
+    pub hashed_secret: String,
+}
+
+pub fn generate() -> Result<NewApiKey, AuthError> {
+    let mut id = [0u8; KEY_ID_LEN / 2];
+    OsRng.fill_bytes(&mut id);
+    let prefix = hex::encode(id);
+
+    let mut secret = [0u8; 32];
+    OsRng.fill_bytes(&mut secret);
+    let secret = URL_SAFE_NO_PAD.encode(secret);
+
+    let salt = SaltString::generate(&mut OsRng);
+    let hashed_secret = Argon2::default()
+        .hash_password(secret.as_bytes(), &salt)
+        .map_err(|_| AuthError::InternalServerError("failed to hash the api key".to_string()))?
+        .to_string();
+
+    Ok(NewApiKey {
+        key: format!("{}{}{}", API_KEY_PREFIX, prefix, secret),
+        prefix,
+        hashed_secret,
+    })
+}
+
+/// Splits an API key into its prefix and secret, `None` when the token is
+/// not an API key.
+pub fn parse(token: &str) -> Option<(&str, &str)> {
+    let key = token.strip_prefix(API_KEY_PREFIX)?;
+    if key.len() <= KEY_ID_LEN || !key.is_char_boundary(KEY_ID_LEN) {
+        return None;
+    }
+
+    Some(key.split_at(KEY_ID_LEN))
+}
+
+pub fn verify(secret: &str, hashed_secret: &str) -> bool {
+    match PasswordHash::new(hashed_secret) {
+        Ok(parsed_hash) => Argon2::default()
+            .verify_password(secret.as_bytes(), &parsed_hash)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
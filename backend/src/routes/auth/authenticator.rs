@@ -0,0 +1,307 @@
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use clap::ValueEnum;
+use futures_util::future::BoxFuture;
+
+use crate::{
+    domain::models::user::UserModel,
+    infra::{
+        ldap::{LdapClient, LdapConfig},
+        repositories,
+    },
+    server::AppState,
+};
+use super::{error::AuthError, identity::{find_or_provision, ExternalIdentity}};
+
+/// Value of `user_identities.provider` for users of the LDAP directory,
+/// whose subject is their DN.
+pub const LDAP_PROVIDER: &str = "ldap";
+
+/// Value of `users_groups_rel.source` for memberships synced from the
+/// LDAP groups of the user.
+pub const LDAP_GROUP_SOURCE: &str = "ldap";
+
+/// Checks the credentials given to `/login`. Authenticators are tried in the
+/// configured order until one of them knows the user.
+pub trait Authenticator: Send + Sync {
+    /// The user the credentials belong to, `None` when they don't match.
+    /// An error ends the login without trying the next authenticators.
+    fn authenticate<'a>(
+        &'a self,
+        state: &'a AppState,
+        username: &'a str,
+        password: &'a str,
+    ) -> BoxFuture<'a, Result<Option<UserModel>, AuthError>>;
+}
+
+/// Tries `authenticators` in order until one of them accepts the
+/// credentials. One rejecting them hands over to the next, but one unable
+/// to check them fails the login, lest a local account shadowed by a
+/// directory that is down lets its stale password in.
+pub async fn authenticate(
+    authenticators: &[Box<dyn Authenticator>],
+    state: &AppState,
+    username: &str,
+    password: &str,
+) -> Result<Option<UserModel>, AuthError> {
+    for authenticator in authenticators {
+        let user = authenticator
+            .authenticate(state, username, password)
+            .await?;
+        if user.is_some() {
+            return Ok(user);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Authenticators that can be enabled with `--auth-backends`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum AuthBackend {
+    /// The argon2 password hash of the user
+    Local,
+    /// A bind to the LDAP directory
+    Ldap,
+}
+
+/// Builds the authenticators of the enabled backends, in order.
+pub fn authenticators(
+    backends: &[AuthBackend],
+    ldap: Option<LdapConfig>,
+) -> Result<Vec<Box<dyn Authenticator>>, String> {
+    backends
+        .iter()
+        .map(|backend| -> Result<Box<dyn Authenticator>, String> {
+            match backend {
+                AuthBackend::Local => Ok(Box::new(LocalAuthenticator)),
+                AuthBackend::Ldap => {
+                    let config = ldap
+                        .clone()
+                        .ok_or_else(|| "the ldap backend needs --ldap-url".to_string())?;
+                    Ok(Box::new(LdapAuthenticator::new(config)))
+                }
+            }
+        })
+        .collect()
+}
+
+pub struct LocalAuthenticator;
+
+impl Authenticator for LocalAuthenticator {
+    fn authenticate<'a>(
+        &'a self,
+        state: &'a AppState,
+        username: &'a str,
+        password: &'a str,
+    ) -> BoxFuture<'a, Result<Option<UserModel>, AuthError>> {
+        Box::pin(async move {
+            let Some(user) = repositories::user::try_get_by_username(
+                &state.pg_pool, username.to_string()
+            )
+                .await
+                .map_err(AuthError::RepoError)?
+            else {
+                return Ok(None);
+            };
+
+            let is_valid = match PasswordHash::new(&user.hashed_password) {
+                Ok(parsed_hash) => Argon2::default()
+                    .verify_password(password.as_bytes(), &parsed_hash)
+                    .is_ok(),
+                Err(_) => false,
+            };
+
+            Ok(is_valid.then_some(user))
+        })
+    }
+}
+
+pub struct LdapAuthenticator {
+    client: LdapClient,
+}
+
+impl LdapAuthenticator {
+    pub fn new(config: LdapConfig) -> Self {
+        Self {
+            client: LdapClient::new(config),
+        }
+    }
+}
+
+impl Authenticator for LdapAuthenticator {
+    fn authenticate<'a>(
+        &'a self,
+        state: &'a AppState,
+        username: &'a str,
+        password: &'a str,
+    ) -> BoxFuture<'a, Result<Option<UserModel>, AuthError>> {
+        Box::pin(async move {
+            let identity = match self.client.authenticate(username, password).await {
+                Ok(Some(identity)) => identity,
+                Ok(None) => return Ok(None),
+                Err(err) => {
+                    tracing::error!("LDAP authentication of {} failed: {}", username, err);
+                    return Err(AuthError::AuthBackendUnavailable);
+                }
+            };
+
+            let user = find_or_provision(state, ExternalIdentity {
+                provider: LDAP_PROVIDER.to_string(),
+                subject: identity.dn.clone(),
+                username: username.to_string(),
+                nickname: identity.nickname,
+                avatar_uri: None,
+            })
+                .await?;
+
+            let Some(user) = user else {
+                tracing::warn!("LDAP user {} clashes with an existing user", identity.dn);
+                return Ok(None);
+            };
+
+            repositories::user_group_rel::sync_by_source(
+                &state.pg_pool, user.id, LDAP_GROUP_SOURCE.to_string(), identity.groups
+            )
+                .await
+                .map_err(AuthError::RepoError)?;
+
+            state.auth_cache.invalidate_user(user.id);
+
+            Ok(Some(user))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, sync::Arc, time::Duration};
+
+    use deadpool_diesel::postgres::{Manager, Pool};
+
+    use crate::{
+        bootstrap::PermissionRegistry,
+        domain::{lockout::LockoutPolicy, password::PasswordPolicy},
+        infra::{auth_cache::AuthCache, jwt_keys::JwtKeys},
+    };
+    use super::*;
+
+    enum Stub {
+        Rejects,
+        Accepts(i32),
+        Fails,
+        /// Must not be reached
+        Unused,
+    }
+
+    impl Authenticator for Stub {
+        fn authenticate<'a>(
+            &'a self,
+            _state: &'a AppState,
+            username: &'a str,
+            _password: &'a str,
+        ) -> BoxFuture<'a, Result<Option<UserModel>, AuthError>> {
+            Box::pin(async move {
+                match self {
+                    Self::Rejects => Ok(None),
+                    Self::Accepts(id) => Ok(Some(UserModel {
+                        id: *id,
+                        username: username.to_string(),
+                        hashed_password: String::new(),
+                        nickname: username.to_string(),
+                        avatar_uri: String::new(),
+                        is_active: true,
+                        email: None,
+                        groups: None,
+                        created_at: Default::default(),
+                        updated_at: Default::default(),
+                    })),
+                    Self::Fails => Err(AuthError::AuthBackendUnavailable),
+                    Self::Unused => panic!("authenticator tried after a failure"),
+                }
+            })
+        }
+    }
+
+    /// State whose database is never connected to, the stubs don't use it.
+    fn state() -> AppState {
+        let manager = Manager::new("postgres://localhost/unused", deadpool_diesel::Runtime::Tokio1);
+
+        AppState {
+            pg_pool: Pool::builder(manager).build().unwrap(),
+            jwt_secret: "secret".to_string(),
+            audit_key: Arc::from(&b"secret"[..]),
+            jwt_keys: Arc::new(JwtKeys::from_secret("secret")),
+            auth_cache: Arc::new(AuthCache::new(Duration::from_secs(1))),
+            trust_token_claims: false,
+            authenticators: Arc::from(Vec::<Box<dyn Authenticator>>::new()),
+            oidc: None,
+            permission_registry: Arc::new(PermissionRegistry::default()),
+            mailer: None,
+            allow_registration: false,
+            public_url: "http://localhost".to_string(),
+            password_policy: Arc::new(PasswordPolicy::new(8, 1)),
+            lockout_policy: LockoutPolicy {
+                free_failures: 3,
+                backoff: Duration::from_secs(1),
+                max_failures: 10,
+                max_failures_per_ip: 50,
+                lockout: Duration::from_secs(60),
+            },
+            client_ip_header: None,
+            totp_issuer: "datarepo".to_string(),
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn login(authenticators: Vec<Box<dyn Authenticator>>) -> Result<Option<UserModel>, AuthError> {
+        block_on(async {
+            authenticate(&authenticators, &state(), "alice", "password").await
+        })
+    }
+
+    fn unreachable_ldap() -> LdapAuthenticator {
+        LdapAuthenticator::new(LdapConfig {
+            url: "ldap://127.0.0.1:1".to_string(),
+            starttls: false,
+            bind_dn: None,
+            bind_password: None,
+            base_dn: "dc=example,dc=org".to_string(),
+            user_filter: "(uid={username})".to_string(),
+            group_attribute: "memberOf".to_string(),
+        })
+    }
+
+    #[test]
+    fn rejected_credentials_fall_back_to_the_next_authenticator() {
+        let user = login(vec![Box::new(Stub::Rejects), Box::new(Stub::Accepts(7))]).unwrap();
+        assert_eq!(user.map(|user| user.id), Some(7));
+
+        let user = login(vec![Box::new(Stub::Rejects), Box::new(Stub::Rejects)]).unwrap();
+        assert!(user.is_none());
+    }
+
+    #[test]
+    fn first_accepting_authenticator_wins() {
+        let user = login(vec![Box::new(Stub::Accepts(1)), Box::new(Stub::Unused)]).unwrap();
+        assert_eq!(user.map(|user| user.id), Some(1));
+    }
+
+    #[test]
+    fn failing_authenticator_ends_the_login() {
+        let res = login(vec![Box::new(Stub::Fails), Box::new(Stub::Unused)]);
+        assert!(matches!(res, Err(AuthError::AuthBackendUnavailable)));
+    }
+
+    #[test]
+    fn unreachable_ldap_does_not_fall_back_to_local_accounts() {
+        let res = login(vec![Box::new(unreachable_ldap()), Box::new(Stub::Unused)]);
+        assert!(matches!(res, Err(AuthError::AuthBackendUnavailable)));
+    }
+}
@@ -1,4 +1,4 @@
-use axum::{response::IntoResponse, http::StatusCode, Json};
+use axum::{response::IntoResponse, http::{header, StatusCode}, Json};
 use serde_json::json;
 
 use crate::infra::repositories::error::RepoError;
@@ -10,12 +10,28 @@ pub enum AuthError {
     UserNotActive,
     PermissionDenied,
     InvalidToken,
+    SessionRevoked,
+    OidcNotConfigured,
+    OidcFailed(String),
+    /// Too many failed logins in a row, with the seconds to wait
+    LoginThrottled(u64),
+    /// Locked out after even more failed logins, with the seconds to wait
+    LoginLocked(u64),
+    /// Wrong code of the authenticator app or recovery code
+    InvalidTwoFactorCode,
+    /// An authenticator couldn't check the credentials, such as an
+    /// unreachable directory
+    AuthBackendUnavailable,
     InternalServerError(String),
     RepoError(RepoError),
 }
 
 impl IntoResponse for AuthError {
     fn into_response(self) -> axum::response::Response {
+        let retry_after = match &self {
+            Self::LoginThrottled(secs) | Self::LoginLocked(secs) => Some(*secs),
+            _ => None,
+        };
         let (status, code, err_msg) = match self {
             Self::InvalidCredentials => (
                 StatusCode::BAD_REQUEST,
@@ -42,6 +58,41 @@ impl IntoResponse for AuthError {
                 10005,
                 format!("Invalid token"),
             ),
+            Self::SessionRevoked => (
+                StatusCode::UNAUTHORIZED,
+                10008,
+                format!("Session revoked"),
+            ),
+            Self::OidcNotConfigured => (
+                StatusCode::NOT_FOUND,
+                10009,
+                format!("OpenID Connect is not configured"),
+            ),
+            Self::OidcFailed(msg) => (
+                StatusCode::UNAUTHORIZED,
+                10010,
+                format!("OpenID Connect login failed: {}", msg),
+            ),
+            Self::LoginThrottled(secs) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                10011,
+                format!("Too many failed logins, retry in {} seconds", secs),
+            ),
+            Self::LoginLocked(secs) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                10012,
+                format!("Locked out after too many failed logins, retry in {} seconds", secs),
+            ),
+            Self::InvalidTwoFactorCode => (
+                StatusCode::BAD_REQUEST,
+                10013,
+                format!("Invalid two-factor authentication code"),
+            ),
+            Self::AuthBackendUnavailable => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                10014,
+                format!("Credentials can't be checked right now"),
+            ),
             Self::InternalServerError(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 10006,
@@ -53,10 +104,14 @@ impl IntoResponse for AuthError {
                 format!("Internal server error."),
             ),
         };
-        (
+        let mut response = (
             status,
             Json(json!({"code": code, "msg": err_msg})),
         )
-            .into_response()
+            .into_response();
+        if let Some(secs) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, secs.into());
+        }
+        response
     }
 }
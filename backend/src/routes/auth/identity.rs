@@ -0,0 +1,76 @@
+use crate::{
+    domain::models::user::UserModel,
+    infra::repositories::{self, user::NewUserDB, user_identity::NewUserIdentityDB},
+    server::AppState,
+};
+use super::error::AuthError;
+
+/// A user as told by an external provider, LDAP or OIDC.
+pub struct ExternalIdentity {
+    pub provider: String,
+    pub subject: String,
+    pub username: String,
+    pub nickname: String,
+    pub avatar_uri: Option<String>,
+}
+
+/// The user linked to the identity, provisioned on its first login. `None`
+/// when the username is taken by a user the identity isn't linked to.
+pub async fn find_or_provision(
+    state: &AppState,
+    identity: ExternalIdentity,
+) -> Result<Option<UserModel>, AuthError> {
+    let linked = repositories::user_identity::try_get_by_subject(
+        &state.pg_pool, identity.provider.clone(), identity.subject.clone()
+    )
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    if let Some(linked) = linked {
+        let user = repositories::user::try_get_by_id(
+            &state.pg_pool, linked.user_id
+        )
+            .await
+            .map_err(AuthError::RepoError)?
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        if !user.is_active {
+            return Err(AuthError::UserNotActive);
+        }
+
+        return Ok(Some(user));
+    }
+
+    // Local accounts are never taken over by an identity claiming the same
+    // username.
+    let taken = repositories::user::try_get_by_username(
+        &state.pg_pool, identity.username.clone()
+    )
+        .await
+        .map_err(AuthError::RepoError)?
+        .is_some();
+
+    if taken {
+        return Ok(None);
+    }
+
+    let user = repositories::user_identity::provision(
+        &state.pg_pool,
+        NewUserDB {
+            username: identity.username,
+            // Matches no password, the provider checks it.
+            hashed_password: String::new(),
+            nickname: identity.nickname,
+            avatar_uri: identity.avatar_uri.unwrap_or_default(),
+            email: None,
+        },
+        NewUserIdentityDB {
+            provider: identity.provider,
+            subject: identity.subject,
+        },
+    )
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    Ok(Some(user))
+}
@@ -0,0 +1,23 @@
+use axum::{Json, extract::State};
+use jsonwebtoken::jwk::JwkSet;
+use tracing::instrument;
+
+use crate::server::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (
+            status = 200,
+            description = "JSON Web Key Set of the public keys access tokens are verified with, \
+                by the `kid` of their header. Empty when tokens are signed with the server secret.",
+        ),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn jwks(
+    State(state): State<AppState>,
+) -> Json<JwkSet> {
+    Json(state.jwt_keys.jwks())
+}
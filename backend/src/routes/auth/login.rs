@@ -1,13 +1,20 @@
-use argon2::{Argon2, PasswordHash, PasswordVerifier};
-use axum::{Json, extract::State, response::IntoResponse, http::header};
-use axum_extra::extract::cookie::{Cookie, SameSite};
-use jsonwebtoken::{encode, EncodingKey, Header};
+use axum::{Json, extract::State, response::IntoResponse};
 use serde::{Serialize, Deserialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
-use crate::{infra::repositories, server::AppState};
-use super::error::AuthError;
+use crate::{
+    domain::models::lockout::LockoutKind,
+    infra::repositories,
+    server::AppState,
+    utils::extractors::client_ip::ClientIp,
+};
+use super::{
+    authenticator::authenticate,
+    error::AuthError,
+    token::start_session,
+    two_factor::challenge,
+};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct LoginRequest {
@@ -18,13 +25,22 @@ pub struct LoginRequest {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
     pub code: i32,
+    /// Access token, also set as the `token` cookie
     pub data: Option<String>,
+    /// Exchanged for new tokens at `/refresh`, also set as the
+    /// `refresh_token` cookie
+    pub refresh_token: Option<String>,
+    /// Set instead of the tokens when the user enabled 2FA, to be sent to
+    /// `/login/2fa` along with a code
+    pub challenge_token: Option<String>,
     pub msg: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
     pub sub: i32,
+    /// Session the token was issued for
+    pub sid: i32,
     pub perms: Vec<i32>,
     pub iat: usize,
     pub exp: usize,
@@ -38,78 +54,108 @@ pub struct TokenClaims {
         content_type = "application/x-www-form-urlencoded",
     ),
     responses(
-        (status = 200, description = "Login successfully", body = LoginResponse),
+        (
+            status = 200,
+            description = "Login successfully, or a challenge token when 2FA is enabled",
+            body = LoginResponse,
+        ),
+        (
+            status = TOO_MANY_REQUESTS,
+            description = "Too many failed logins of the username or from the address",
+        ),
+        (
+            status = SERVICE_UNAVAILABLE,
+            description = "An authenticator couldn't check the credentials",
+        ),
     )
 )]
-#[instrument(skip(state))]
+#[instrument(skip(state, user, ip), fields(username = %user.username, ip = %ip))]
 pub async fn login(
     State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
     Json(user): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, AuthError> {
-    let user_in_db = repositories::user::try_get_by_username(
-        &state.pg_pool, user.username.clone()
+    check_lockouts(&state, &user.username, ip.to_string()).await?;
+
+    let user_in_db = authenticate(
+        &state.authenticators, &state, &user.username, &user.password
     )
-        .await
-        .map_err(AuthError::RepoError)?
-        .ok_or(AuthError::InvalidCredentials)?;
-
-    let is_valid = match PasswordHash::new(&user_in_db.hashed_password) {
-        Ok(parsed_hash) => Argon2::default()
-            .verify_password(user.password.as_bytes(), &parsed_hash)
-            .map_or(false, |_| true),
-        Err(_) => false,
-    };
+        .await?;
 
-    if !is_valid {
+    let Some(user_in_db) = user_in_db else {
+        record_failure(&state, LockoutKind::Username, user.username).await?;
+        record_failure(&state, LockoutKind::Ip, ip.to_string()).await?;
         return Err(AuthError::InvalidCredentials);
+    };
+
+    let totp_secret = repositories::totp_secret::try_get_by_user_id(&state.pg_pool, user_in_db.id)
+        .await
+        .map_err(AuthError::RepoError)?;
+    // Failed logins of the username are only forgotten once the code is
+    // verified too, lest logging in again resets the count of wrong codes.
+    if totp_secret.is_some_and(|secret| secret.is_enabled()) {
+        return challenge(&state, user_in_db.id, user.username);
     }
 
-    let perms = repositories::user::get_permissions(
-        &state.pg_pool, user_in_db.id
-    )
+    // Failures from the address keep counting, lest an attacker holding an
+    // account resets them by logging in now and then.
+    repositories::lockout::delete_by_key(&state.pg_pool, LockoutKind::Username, user.username)
         .await
-        .map_err(AuthError::RepoError)?
-        .into_iter()
-        .map(|perm| perm.id)
-        .collect();
-
-    let now = chrono::Utc::now();
-    let iat = now.timestamp() as usize;
-    let exp = (now + chrono::Duration::minutes(60)).timestamp() as usize;
-    let claims = TokenClaims {
-        sub: user_in_db.id,
-        perms,
-        iat,
-        exp,
-    };
+        .map_err(AuthError::RepoError)?;
 
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(state.jwt_secret.as_ref()),
+    start_session(&state, user_in_db.id).await
+}
+
+/// Refuses the login while the username or the address is blocked, before
+/// spending time on the credentials.
+pub(super) async fn check_lockouts(state: &AppState, username: &str, ip: String) -> Result<(), AuthError> {
+    let lockouts = repositories::lockout::get_locked(&state.pg_pool, username.to_string(), ip)
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let blocking = lockouts
+        .iter()
+        .filter_map(|lockout| lockout.locked_until.map(|until| (lockout, until)))
+        .max_by_key(|(_, until)| *until);
+
+    match blocking {
+        Some((lockout, until)) => {
+            // Rounded up so that retrying right on time succeeds.
+            let secs = ((until - now).num_milliseconds().max(0) as u64).div_ceil(1000);
+            match state.lockout_policy.is_locked_out(lockout.kind, lockout.failures) {
+                true => Err(AuthError::LoginLocked(secs)),
+                false => Err(AuthError::LoginThrottled(secs)),
+            }
+        }
+        None => Ok(()),
+    }
+}
+
+pub(super) async fn record_failure(state: &AppState, kind: LockoutKind, key: String) -> Result<(), AuthError> {
+    let policy = state.lockout_policy;
+    let now = chrono::Utc::now().naive_utc();
+    let forget_before = chrono::Duration::from_std(policy.lockout)
+        .ok()
+        .and_then(|lockout| now.checked_sub_signed(lockout))
+        .unwrap_or(chrono::NaiveDateTime::MIN);
+
+    let lockout = repositories::lockout::record_failure(
+        &state.pg_pool,
+        kind,
+        key,
+        forget_before,
+        move |failures| {
+            policy.block(kind, failures)
+                .and_then(|block| chrono::Duration::from_std(block).ok())
+        },
     )
-        .map_err(|_| AuthError::InternalServerError("failed to encode the token".to_string()))?
-        .to_owned();
-
-    let cookie = Cookie::build(("token", token.clone()))
-        .path("/")
-        .max_age(time::Duration::days(7))
-        .same_site(SameSite::Lax)
-        .http_only(true);
-
-    let mut response = Json(LoginResponse {
-        code: 0,
-        data: Some(token),
-        msg: None,
-    })
-        .into_response();
-
-    response
-        .headers_mut()
-        .insert(
-            header::SET_COOKIE,
-            cookie.to_string().parse().unwrap(),
-        );
-
-    Ok(response)
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    if state.lockout_policy.is_locked_out(kind, lockout.failures) {
+        tracing::warn!("Locked out {} {} after {} failed logins", kind.as_str(), lockout.key, lockout.failures);
+    }
+
+    Ok(())
 }
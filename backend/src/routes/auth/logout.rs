@@ -1,10 +1,15 @@
-use axum::{Json, response::IntoResponse, http::header};
-use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum::{Json, extract::State, response::IntoResponse, http::{header, HeaderMap}};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use jsonwebtoken::Validation;
 use serde::Serialize;
 use tracing::instrument;
 use utoipa::ToSchema;
 
-use super::error::AuthError;
+use crate::{
+    infra::repositories::{self, session::SessionByToken},
+    server::AppState,
+};
+use super::{error::AuthError, login::TokenClaims, token::{hash_opaque_token, REFRESH_COOKIE_PATHS}};
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LogoutResponse {
@@ -12,20 +17,78 @@ pub struct LogoutResponse {
     pub msg: Option<String>,
 }
 
+/// Session of the access token or, once it expired, of the refresh token the
+/// request carries.
+async fn session_of(
+    state: &AppState,
+    cookie_jar: &CookieJar,
+    headers: &HeaderMap,
+) -> Result<Option<i32>, AuthError> {
+    let token = cookie_jar
+        .get("token")
+        .map(|cookie| cookie.value().to_string())
+        .or_else(|| {
+            headers
+                .get(header::AUTHORIZATION)
+                .and_then(|auth_header| auth_header.to_str().ok())
+                .and_then(|auth_value| {
+                    auth_value
+                        .strip_prefix("Bearer ")
+                        .map(ToOwned::to_owned)
+                })
+        });
+
+    // An expired access token still names its session.
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+
+    let claims = token.and_then(|token| {
+        state.jwt_keys
+            .decode::<TokenClaims>(&token, validation)
+            .ok()
+    });
+
+    if let Some(claims) = claims {
+        return Ok(Some(claims.claims.sid));
+    }
+
+    let Some(refresh_token) = cookie_jar.get("refresh_token") else {
+        return Ok(None);
+    };
+
+    let session = repositories::session::try_get_by_token_hash(
+        &state.pg_pool, hash_opaque_token(refresh_token.value())
+    )
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    Ok(match session {
+        Some(SessionByToken::Current(session) | SessionByToken::Reused(session)) => Some(session.id),
+        None => None,
+    })
+}
+
 #[utoipa::path(
     get,
     path = "/logout",
     responses(
-        (status = 200, description = "Logout successfully", body = LogoutResponse),
+        (status = 200, description = "Logout successfully, the session is revoked", body = LogoutResponse),
     )
 )]
-#[instrument]
-pub async fn logout() -> Result<impl IntoResponse, AuthError> {
-    let cookie = Cookie::build(("token", ""))
-        .path("/")
-        .max_age(time::Duration::hours(-1))
-        .same_site(SameSite::Lax)
-        .http_only(true);
+#[instrument(skip(state, cookie_jar, headers))]
+pub async fn logout(
+    State(state): State<AppState>,
+    cookie_jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthError> {
+    if let Some(session_id) = session_of(&state, &cookie_jar, &headers).await? {
+        repositories::session::revoke_by_id(
+            &state.pg_pool, session_id
+        )
+            .await
+            .map_err(AuthError::RepoError)?;
+        state.auth_cache.invalidate_session(session_id);
+    }
 
     let mut response = Json(LogoutResponse {
         code: 0,
@@ -33,12 +96,25 @@ pub async fn logout() -> Result<impl IntoResponse, AuthError> {
     })
         .into_response();
 
-    response
-        .headers_mut()
-        .insert(
-            header::SET_COOKIE,
-            cookie.to_string().parse().unwrap(),
-        );
+    // The refresh token was set for `/` before it was scoped to its paths.
+    let cookies = std::iter::once(("token", "/"))
+        .chain(REFRESH_COOKIE_PATHS.map(|path| ("refresh_token", path)))
+        .chain(std::iter::once(("refresh_token", "/")));
+
+    for (name, path) in cookies {
+        let cookie = Cookie::build((name, ""))
+            .path(path)
+            .max_age(time::Duration::hours(-1))
+            .same_site(SameSite::Lax)
+            .http_only(true);
+
+        response
+            .headers_mut()
+            .append(
+                header::SET_COOKIE,
+                cookie.to_string().parse().unwrap(),
+            );
+    }
 
     Ok(response)
 }
@@ -1,3 +1,11 @@
+pub mod api_key;
+pub mod authenticator;
 pub mod error;
+pub mod identity;
+pub mod jwks;
 pub mod login;
 pub mod logout;
+pub mod oidc;
+pub mod refresh;
+pub mod token;
+pub mod two_factor;
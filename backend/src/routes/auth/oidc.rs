@@ -0,0 +1,271 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::instrument;
+use utoipa::IntoParams;
+
+use crate::{
+    infra::repositories,
+    server::AppState,
+};
+use super::{
+    error::AuthError,
+    identity::{find_or_provision, ExternalIdentity},
+    token::start_session,
+    two_factor::challenge,
+};
+
+/// Cookie carrying the pending login from `/auth/oidc/login` to the callback.
+const LOGIN_COOKIE: &str = "oidc_login";
+
+/// How long users have to log in at the provider.
+const LOGIN_MINUTES: i64 = 10;
+
+/// Value of `users_groups_rel.source` for memberships synced from the
+/// provider's groups claim.
+pub const GROUP_SOURCE: &str = "oidc";
+
+/// The pending login, signed with the server secret so that the callback
+/// can trust it without keeping any state.
+#[derive(Debug, Serialize, Deserialize)]
+struct LoginClaims {
+    state: String,
+    nonce: String,
+    code_verifier: String,
+    exp: usize,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set by the provider when the login failed or was denied
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn login_cookie(value: String, max_age: time::Duration) -> Cookie<'static> {
+    Cookie::build((LOGIN_COOKIE, value))
+        .path("/auth/oidc")
+        .max_age(max_age)
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .build()
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/login",
+    responses(
+        (status = 303, description = "Redirect to the login page of the identity provider"),
+        (status = NOT_FOUND, description = "OpenID Connect is not configured"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn oidc_login(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AuthError> {
+    let oidc = state.oidc.as_ref().ok_or(AuthError::OidcNotConfigured)?;
+
+    let claims = LoginClaims {
+        state: random_token(),
+        nonce: random_token(),
+        code_verifier: random_token(),
+        exp: (chrono::Utc::now() + chrono::Duration::minutes(LOGIN_MINUTES)).timestamp() as usize,
+    };
+    let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(claims.code_verifier.as_bytes()));
+
+    let url = oidc
+        .authorization_url(&claims.state, &claims.nonce, &code_challenge)
+        .await
+        .map_err(|err| AuthError::OidcFailed(err.to_string()))?;
+
+    let pending_login = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(state.jwt_secret.as_ref()),
+    )
+        .map_err(|_| AuthError::InternalServerError("failed to encode the login state".to_string()))?;
+
+    let cookie = login_cookie(pending_login, time::Duration::minutes(LOGIN_MINUTES));
+
+    Ok((CookieJar::new().add(cookie), Redirect::to(&url)))
+}
+
+/// The login started by `/auth/oidc/login` in this browser, which the
+/// `state` the provider sent back has to belong to.
+fn pending_login(
+    cookie_jar: &CookieJar,
+    jwt_secret: &str,
+    state: Option<&str>,
+) -> Result<LoginClaims, AuthError> {
+    let pending_login = cookie_jar
+        .get(LOGIN_COOKIE)
+        .and_then(|cookie| {
+            decode::<LoginClaims>(
+                cookie.value(),
+                &DecodingKey::from_secret(jwt_secret.as_ref()),
+                &Validation::default(),
+            )
+                .ok()
+        })
+        .ok_or_else(|| AuthError::OidcFailed("no pending login".to_string()))?
+        .claims;
+
+    if state != Some(pending_login.state.as_str()) {
+        return Err(AuthError::OidcFailed("state mismatch".to_string()));
+    }
+
+    Ok(pending_login)
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/callback",
+    params(OidcCallbackQuery),
+    responses(
+        (status = 200, description = "Login successfully, or a challenge token when 2FA is enabled", body = LoginResponse),
+        (status = 303, description = "Login successfully, redirect to the configured page"),
+        (status = UNAUTHORIZED, description = "The login failed at the provider or could not be verified"),
+        (status = NOT_FOUND, description = "OpenID Connect is not configured"),
+    )
+)]
+#[instrument(skip(state, cookie_jar, query))]
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    cookie_jar: CookieJar,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<Response, AuthError> {
+    let oidc = state.oidc.as_ref().ok_or(AuthError::OidcNotConfigured)?;
+
+    if let Some(error) = query.error {
+        return Err(AuthError::OidcFailed(format!(
+            "{} {}", error, query.error_description.unwrap_or_default()
+        )));
+    }
+
+    let pending_login = pending_login(&cookie_jar, &state.jwt_secret, query.state.as_deref())?;
+    let code = query
+        .code
+        .ok_or_else(|| AuthError::OidcFailed("no authorization code".to_string()))?;
+
+    let identity = oidc
+        .exchange_code(&code, &pending_login.code_verifier, &pending_login.nonce)
+        .await
+        .map_err(|err| AuthError::OidcFailed(err.to_string()))?;
+
+    let username = identity.username.clone();
+    let user = find_or_provision(&state, ExternalIdentity {
+        provider: oidc.config().issuer.clone(),
+        subject: identity.subject,
+        username: identity.username,
+        nickname: identity.nickname,
+        avatar_uri: identity.avatar_uri,
+    })
+        .await?
+        .ok_or_else(|| AuthError::OidcFailed(format!("username {} is already taken", username)))?;
+    let user_id = user.id;
+    let groups = identity.groups;
+
+    repositories::user_group_rel::sync_by_source(
+        &state.pg_pool, user_id, GROUP_SOURCE.to_string(), groups
+    )
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    state.auth_cache.invalidate_user(user_id);
+
+    let totp_secret = repositories::totp_secret::try_get_by_user_id(&state.pg_pool, user_id)
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    // The provider vouches for the password only, the code is asked for as
+    // after `/login`. The challenge token is answered rather than carried
+    // through the redirect, which would leave it in the history.
+    let mut response = if totp_secret.is_some_and(|secret| secret.is_enabled()) {
+        challenge(&state, user_id, user.username)?
+    } else {
+        let mut response = start_session(&state, user_id).await?;
+
+        if let Some(url) = &oidc.config().post_login_redirect {
+            let cookies = response.headers().get_all(header::SET_COOKIE).iter().cloned().collect::<Vec<_>>();
+            response = Redirect::to(url).into_response();
+            for cookie in cookies {
+                response.headers_mut().append(header::SET_COOKIE, cookie);
+            }
+        }
+
+        response
+    };
+
+    let cleared = login_cookie(String::new(), time::Duration::hours(-1));
+    response
+        .headers_mut()
+        .append(
+            header::SET_COOKIE,
+            cleared.to_string().parse().unwrap(),
+        );
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "secret";
+
+    fn jar_with_login(state: &str, secret: &str) -> CookieJar {
+        let claims = LoginClaims {
+            state: state.to_string(),
+            nonce: "nonce".to_string(),
+            code_verifier: "verifier".to_string(),
+            exp: (chrono::Utc::now() + chrono::Duration::minutes(LOGIN_MINUTES)).timestamp() as usize,
+        };
+        let value = encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_ref()))
+            .unwrap();
+
+        CookieJar::new().add(login_cookie(value, time::Duration::minutes(LOGIN_MINUTES)))
+    }
+
+    fn failure(res: Result<LoginClaims, AuthError>) -> String {
+        match res {
+            Err(AuthError::OidcFailed(msg)) => msg,
+            res => panic!("unexpected result: {:?}", res.map(|claims| claims.state)),
+        }
+    }
+
+    #[test]
+    fn state_must_match_the_pending_login() {
+        let jar = jar_with_login("s1", SECRET);
+
+        let claims = pending_login(&jar, SECRET, Some("s1")).unwrap();
+        assert_eq!(claims.nonce, "nonce");
+
+        assert_eq!(failure(pending_login(&jar, SECRET, Some("s2"))), "state mismatch");
+        assert_eq!(failure(pending_login(&jar, SECRET, None)), "state mismatch");
+    }
+
+    #[test]
+    fn pending_login_must_be_signed_by_the_server() {
+        let jar = jar_with_login("s1", "other secret");
+        assert_eq!(failure(pending_login(&jar, SECRET, Some("s1"))), "no pending login");
+
+        assert_eq!(failure(pending_login(&CookieJar::new(), SECRET, Some("s1"))), "no pending login");
+    }
+}
@@ -0,0 +1,89 @@
+use axum::{Json, extract::State, response::IntoResponse};
+use axum_extra::extract::cookie::CookieJar;
+use serde::Deserialize;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    infra::repositories::{self, session::SessionByToken},
+    server::AppState,
+};
+use super::{error::AuthError, token::{hash_opaque_token, rotate_session}};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RefreshRequest {
+    /// Read from the `refresh_token` cookie when omitted
+    pub refresh_token: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/refresh",
+    request_body(
+        content = Option<RefreshRequest>,
+        description = "The refresh token, unless sent as the `refresh_token` cookie",
+    ),
+    responses(
+        (
+            status = 200,
+            description = "New access token, and the refresh token replacing the one sent",
+            body = LoginResponse,
+        ),
+        (status = UNAUTHORIZED, description = "Invalid refresh token, or its session was revoked"),
+    )
+)]
+#[instrument(skip(state, cookie_jar, req))]
+pub async fn refresh(
+    State(state): State<AppState>,
+    cookie_jar: CookieJar,
+    req: Option<Json<RefreshRequest>>,
+) -> Result<impl IntoResponse, AuthError> {
+    let refresh_token = req
+        .and_then(|Json(req)| req.refresh_token)
+        .or_else(|| {
+            cookie_jar
+                .get("refresh_token")
+                .map(|cookie| cookie.value().to_string())
+        })
+        .ok_or(AuthError::Unauthorized)?;
+
+    let session = repositories::session::try_get_by_token_hash(
+        &state.pg_pool, hash_opaque_token(&refresh_token)
+    )
+        .await
+        .map_err(AuthError::RepoError)?
+        .ok_or(AuthError::InvalidToken)?;
+
+    let session = match session {
+        SessionByToken::Current(session) => session,
+        SessionByToken::Reused(session) => {
+            // Either the client or whoever stole the token already used it,
+            // there is no telling which one is presenting it now.
+            tracing::warn!("Refresh token of session {} reused, revoking it", session.id);
+            repositories::session::revoke_by_id(
+                &state.pg_pool, session.id
+            )
+                .await
+                .map_err(AuthError::RepoError)?;
+            state.auth_cache.invalidate_session(session.id);
+
+            return Err(AuthError::SessionRevoked);
+        }
+    };
+
+    if !session.is_live(chrono::Utc::now().naive_utc()) {
+        return Err(AuthError::SessionRevoked);
+    }
+
+    let user = repositories::user::get_by_id(
+        &state.pg_pool, session.user_id
+    )
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    if !user.is_active {
+        return Err(AuthError::UserNotActive);
+    }
+
+    rotate_session(&state, user.id, session.id, &refresh_token).await
+}
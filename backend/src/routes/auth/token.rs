@@ -0,0 +1,151 @@
+use axum::{Json, response::{IntoResponse, Response}, http::header};
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    infra::repositories::{self, session::NewSessionDB},
+    server::AppState,
+};
+use super::{error::AuthError, login::{LoginResponse, TokenClaims}};
+
+/// Lifetime of an access token, which can't be revoked before it expires
+/// other than by revoking its whole session.
+pub const ACCESS_TOKEN_MINUTES: i64 = 15;
+
+/// Lifetime of a refresh token. Every refresh rotates the token and extends
+/// the session by as much.
+pub const REFRESH_TOKEN_DAYS: i64 = 7;
+
+/// Paths the `refresh_token` cookie is set for, the only endpoints reading
+/// it, so that it doesn't ride along with every other request.
+pub const REFRESH_COOKIE_PATHS: [&str; 2] = ["/refresh", "/logout"];
+
+/// Random, URL safe token, handed out as refresh token or in emailed links.
+pub fn new_opaque_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Opaque tokens carry enough entropy that an unsalted digest is enough,
+/// and it lets sessions and the like be looked up by token.
+pub fn hash_opaque_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn refresh_expires_at() -> chrono::NaiveDateTime {
+    (chrono::Utc::now() + chrono::Duration::days(REFRESH_TOKEN_DAYS)).naive_utc()
+}
+
+/// Opens a session for a user who just logged in and issues its tokens.
+pub async fn start_session(
+    state: &AppState,
+    user_id: i32,
+) -> Result<Response, AuthError> {
+    let refresh_token = new_opaque_token();
+    let session = repositories::session::create(
+        &state.pg_pool,
+        NewSessionDB {
+            user_id,
+            token_hash: hash_opaque_token(&refresh_token),
+            expires_at: refresh_expires_at(),
+        },
+    )
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    issue_tokens(state, user_id, session.id, refresh_token).await
+}
+
+/// Rotates the refresh token of a session and issues new tokens, failing
+/// when the session was revoked or the token rotated concurrently.
+pub async fn rotate_session(
+    state: &AppState,
+    user_id: i32,
+    session_id: i32,
+    refresh_token: &str,
+) -> Result<Response, AuthError> {
+    let new_refresh_token = new_opaque_token();
+    repositories::session::try_rotate(
+        &state.pg_pool,
+        session_id,
+        hash_opaque_token(refresh_token),
+        hash_opaque_token(&new_refresh_token),
+        refresh_expires_at(),
+    )
+        .await
+        .map_err(AuthError::RepoError)?
+        .ok_or(AuthError::SessionRevoked)?;
+    // The cached copy would still expire when the session did before.
+    state.auth_cache.invalidate_session(session_id);
+
+    issue_tokens(state, user_id, session_id, new_refresh_token).await
+}
+
+async fn issue_tokens(
+    state: &AppState,
+    user_id: i32,
+    session_id: i32,
+    refresh_token: String,
+) -> Result<Response, AuthError> {
+    let perms = repositories::user::get_permissions(
+        &state.pg_pool, user_id
+    )
+        .await
+        .map_err(AuthError::RepoError)?
+        .into_iter()
+        .map(|perm| perm.id)
+        .collect();
+
+    let now = chrono::Utc::now();
+    let iat = now.timestamp() as usize;
+    let exp = (now + chrono::Duration::minutes(ACCESS_TOKEN_MINUTES)).timestamp() as usize;
+    let claims = TokenClaims {
+        sub: user_id,
+        sid: session_id,
+        perms,
+        iat,
+        exp,
+    };
+
+    let token = state.jwt_keys
+        .encode(&claims)
+        .map_err(|_| AuthError::InternalServerError("failed to encode the token".to_string()))?
+        .to_owned();
+
+    let token_cookie = Cookie::build(("token", token.clone()))
+        .path("/")
+        .max_age(time::Duration::minutes(ACCESS_TOKEN_MINUTES))
+        .same_site(SameSite::Lax)
+        .http_only(true);
+
+    let refresh_cookies = REFRESH_COOKIE_PATHS.map(|path| {
+        Cookie::build(("refresh_token", refresh_token.clone()))
+            .path(path)
+            .max_age(time::Duration::days(REFRESH_TOKEN_DAYS))
+            .same_site(SameSite::Lax)
+            .http_only(true)
+    });
+
+    let mut response = Json(LoginResponse {
+        code: 0,
+        data: Some(token),
+        refresh_token: Some(refresh_token),
+        challenge_token: None,
+        msg: None,
+    })
+        .into_response();
+
+    for cookie in std::iter::once(token_cookie).chain(refresh_cookies) {
+        response
+            .headers_mut()
+            .append(
+                header::SET_COOKIE,
+                cookie.to_string().parse().unwrap(),
+            );
+    }
+
+    Ok(response)
+}
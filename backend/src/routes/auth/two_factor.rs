@@ -0,0 +1,166 @@
+use axum::{Json, extract::State, response::{IntoResponse, Response}};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Serialize, Deserialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::{
+        models::{lockout::LockoutKind, totp_secret::TotpSecretModel},
+        totp,
+    },
+    infra::repositories::{self, error::RepoError},
+    server::AppState,
+    utils::extractors::client_ip::ClientIp,
+};
+use super::{
+    error::AuthError,
+    login::{check_lockouts, record_failure, LoginResponse},
+    token::{hash_opaque_token, start_session},
+};
+
+/// Lifetime of the challenge token handed out by `/login` to users who
+/// still have to give a code.
+pub const CHALLENGE_MINUTES: i64 = 5;
+
+/// Audience of challenge tokens, which keeps them from passing for access
+/// tokens and the other way around.
+const CHALLENGE_AUDIENCE: &str = "2fa";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChallengeClaims {
+    sub: i32,
+    /// Username given to `/login`, which failed codes are counted against
+    username: String,
+    aud: String,
+    iat: usize,
+    exp: usize,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct LoginTwoFactorRequest {
+    /// Challenge token returned by `/login`
+    pub challenge_token: String,
+    /// Code of the authenticator app, or a recovery code
+    pub code: String,
+}
+
+/// Answers a login with a correct password when the user enabled 2FA: no
+/// session is opened until `/login/2fa` gets a code.
+pub fn challenge(state: &AppState, user_id: i32, username: String) -> Result<Response, AuthError> {
+    let now = chrono::Utc::now();
+    let claims = ChallengeClaims {
+        sub: user_id,
+        username,
+        aud: CHALLENGE_AUDIENCE.to_string(),
+        iat: now.timestamp() as usize,
+        exp: (now + chrono::Duration::minutes(CHALLENGE_MINUTES)).timestamp() as usize,
+    };
+
+    let challenge_token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(state.jwt_secret.as_ref()),
+    )
+        .map_err(|_| AuthError::InternalServerError("failed to encode the challenge token".to_string()))?;
+
+    Ok(Json(LoginResponse {
+        code: 0,
+        data: None,
+        refresh_token: None,
+        challenge_token: Some(challenge_token),
+        msg: Some("Two-factor authentication required".to_string()),
+    })
+        .into_response())
+}
+
+/// Checks a code of the authenticator app, or else a recovery code, of a
+/// user who enabled 2FA. Either is used up when it matches.
+pub async fn verify_code(
+    state: &AppState,
+    secret: &TotpSecretModel,
+    code: &str,
+) -> Result<bool, RepoError> {
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    if let Some(step) = totp::verify(&secret.secret, code, now, secret.last_used_step) {
+        // Loses to a concurrent request using the same code.
+        let used = repositories::totp_secret::try_use_step(&state.pg_pool, secret.user_id, step).await?;
+        return Ok(used.is_some());
+    }
+
+    let recovery_code = totp::normalize_recovery_code(code);
+    if recovery_code.is_empty() {
+        return Ok(false);
+    }
+
+    repositories::recovery_code::try_use(
+        &state.pg_pool, secret.user_id, hash_opaque_token(&recovery_code)
+    ).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/login/2fa",
+    request_body = LoginTwoFactorRequest,
+    responses(
+        (status = 200, description = "Login successfully", body = LoginResponse),
+        (status = BAD_REQUEST, description = "Invalid code"),
+        (status = UNAUTHORIZED, description = "Invalid or expired challenge token"),
+        (
+            status = TOO_MANY_REQUESTS,
+            description = "Too many failed logins of the username or from the address",
+        ),
+    )
+)]
+#[instrument(skip(state, ip, request), fields(ip = %ip))]
+pub async fn login_two_factor(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    Json(request): Json<LoginTwoFactorRequest>,
+) -> Result<Response, AuthError> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[CHALLENGE_AUDIENCE]);
+    let claims = decode::<ChallengeClaims>(
+        &request.challenge_token,
+        &DecodingKey::from_secret(state.jwt_secret.as_ref()),
+        &validation,
+    )
+        .map_err(|_| AuthError::InvalidToken)?
+        .claims;
+
+    // Codes are guessed at as passwords are, and count as failed logins.
+    check_lockouts(&state, &claims.username, ip.to_string()).await?;
+
+    let user = repositories::user::try_get_by_id(&state.pg_pool, claims.sub)
+        .await
+        .map_err(AuthError::RepoError)?
+        .ok_or(AuthError::InvalidToken)?;
+    if !user.is_active {
+        return Err(AuthError::UserNotActive);
+    }
+
+    let secret = repositories::totp_secret::try_get_by_user_id(&state.pg_pool, user.id)
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    let verified = match secret.filter(|secret| secret.is_enabled()) {
+        Some(secret) => verify_code(&state, &secret, &request.code)
+            .await
+            .map_err(AuthError::RepoError)?,
+        // 2FA was reset since the challenge, the password alone is no
+        // longer enough to tell.
+        None => return Err(AuthError::InvalidToken),
+    };
+
+    if !verified {
+        record_failure(&state, LockoutKind::Username, claims.username).await?;
+        record_failure(&state, LockoutKind::Ip, ip.to_string()).await?;
+        return Err(AuthError::InvalidTwoFactorCode);
+    }
+
+    repositories::lockout::delete_by_key(&state.pg_pool, LockoutKind::Username, claims.username)
+        .await
+        .map_err(AuthError::RepoError)?;
+
+    start_session(&state, user.id).await
+}
@@ -0,0 +1,155 @@
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories::{self, error::{RepoError, RepoResult}},
+    middlewares::auth::{self, Caller},
+    server::AppState,
+};
+
+/// Global permission whose holders see and manage every dataset regardless
+/// of dataset grants.
+pub const ACL_BYPASS_PERMISSION: &str = "datasets.admin";
+
+/// Why a caller was refused by a dataset grant check. Each area maps it onto
+/// its own error type.
+#[derive(Debug)]
+pub enum AccessError {
+    /// The caller holds no role on the dataset, which is reported as if it
+    /// did not exist.
+    Hidden,
+    /// The caller can see the dataset but their role is too weak.
+    Denied,
+    RepoError(RepoError),
+}
+
+/// Whether the caller holds the bypass permission, through the API key it
+/// used as well when it used one.
+async fn bypasses_acls(
+    state: &AppState,
+    caller: &Caller,
+) -> RepoResult<bool> {
+    let grants = auth::grants(state, caller).await?;
+
+    Ok(grants.allows(ACL_BYPASS_PERMISSION))
+}
+
+/// Value for the `visible_to` field of list filters, `None` when the caller
+/// can see everything.
+pub async fn visible_to(
+    state: &AppState,
+    caller: &Caller,
+) -> RepoResult<Option<i32>> {
+    if bypasses_acls(state, caller).await? {
+        return Ok(None);
+    }
+
+    Ok(Some(caller.user.id))
+}
+
+pub async fn require_dataset_role(
+    state: &AppState,
+    caller: &Caller,
+    ds_id: i32,
+    role: DatasetRole,
+) -> Result<(), AccessError> {
+    if bypasses_acls(state, caller).await.map_err(AccessError::RepoError)? {
+        return Ok(());
+    }
+
+    let granted = repositories::ds_acl::try_get_role(
+        &state.pg_pool, caller.user.id, ds_id
+    )
+        .await
+        .map_err(AccessError::RepoError)?;
+
+    match granted {
+        None => Err(AccessError::Hidden),
+        Some(granted) if granted < role => Err(AccessError::Denied),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Checks the roles held on the datasets an item or shard is linked to.
+/// Seeing it takes a role on any of them, while changing it, which shows in
+/// all of them, takes `role` on every one. Unlinked items and shards are
+/// left to the user who created them.
+fn check_linked(
+    granted: Vec<Option<DatasetRole>>,
+    is_creator: bool,
+    role: DatasetRole,
+) -> Result<(), AccessError> {
+    if granted.is_empty() {
+        return match is_creator {
+            true => Ok(()),
+            false => Err(AccessError::Hidden),
+        };
+    }
+
+    if granted.iter().all(Option::is_none) {
+        return Err(AccessError::Hidden);
+    }
+
+    let allowed = match role {
+        DatasetRole::Viewer => true,
+        _ => granted.iter().all(|granted| granted.is_some_and(|granted| granted >= role)),
+    };
+
+    if !allowed {
+        return Err(AccessError::Denied);
+    }
+
+    Ok(())
+}
+
+pub async fn require_item_role(
+    state: &AppState,
+    caller: &Caller,
+    item_id: i32,
+    role: DatasetRole,
+) -> Result<(), AccessError> {
+    if bypasses_acls(state, caller).await.map_err(AccessError::RepoError)? {
+        return Ok(());
+    }
+
+    let granted = repositories::ds_acl::get_item_roles(
+        &state.pg_pool, caller.user.id, item_id
+    )
+        .await
+        .map_err(AccessError::RepoError)?;
+
+    let is_creator = match granted.is_empty() {
+        true => repositories::ds_item::try_get_by_id(&state.pg_pool, item_id)
+            .await
+            .map_err(AccessError::RepoError)?
+            .is_some_and(|item| item.created_by == Some(caller.user.id)),
+        false => false,
+    };
+
+    check_linked(granted, is_creator, role)
+}
+
+pub async fn require_shard_role(
+    state: &AppState,
+    caller: &Caller,
+    shard_id: i32,
+    role: DatasetRole,
+) -> Result<(), AccessError> {
+    if bypasses_acls(state, caller).await.map_err(AccessError::RepoError)? {
+        return Ok(());
+    }
+
+    let granted = repositories::ds_acl::get_shard_roles(
+        &state.pg_pool, caller.user.id, shard_id
+    )
+        .await
+        .map_err(AccessError::RepoError)?;
+
+    let is_creator = match granted.is_empty() {
+        true => repositories::ds_shard::try_get_by_id(&state.pg_pool, shard_id)
+            .await
+            .map_err(AccessError::RepoError)?
+            .is_some_and(|shard| shard.created_by == Some(caller.user.id)),
+        false => false,
+    };
+
+    check_linked(granted, is_creator, role)
+}
@@ -0,0 +1,275 @@
+use axum::{extract::{State, Query}, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories::{
+        self,
+        ds_acl::{DatasetAclsFilter, NewDatasetAclDB},
+    },
+    middlewares::auth::Caller,
+    server::AppState,
+    utils::extractors::{json::JsonExtractor, path::PathExtractor},
+};
+use super::{
+    access::require_dataset_role,
+    error::DatasetError,
+    schema::{DatasetAclSchema, DatasetRoleSchema},
+};
+
+/// Grants a role to exactly one of a user or a group. Granting again
+/// replaces the previous role.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DatasetAclGrantRequest {
+    pub user_id: Option<i32>,
+    pub group_id: Option<i32>,
+    pub role: DatasetRoleSchema,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetAclResponse {
+    pub code: i32,
+    pub data: Option<DatasetAclSchema>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteDatasetAclResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DatasetAclSearchQuery {
+    /// Only grants of this user
+    pub user_id: Option<i32>,
+    /// Only grants of this group
+    pub group_id: Option<i32>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
+    pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListDatasetAclsResponse {
+    code: i32,
+    data: Option<Vec<DatasetAclSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
+    msg: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/datasets/{id}/acls",
+    params(
+        ("id", Path, description = "Dataset id")
+    ),
+    request_body = DatasetAclGrantRequest,
+    responses(
+        (
+            status = 200,
+            description = "Dataset role granted successfully",
+            body = DatasetAclResponse,
+        ),
+        (status = BAD_REQUEST, description = "Invalid grant, or the last owner would be demoted"),
+        (status = FORBIDDEN, description = "Only owners manage grants"),
+        (status = NOT_FOUND, description = "Dataset not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn grant_dataset_acl(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    JsonExtractor(grant): JsonExtractor<DatasetAclGrantRequest>,
+) -> Result<Json<DatasetAclResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Owner).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    match (grant.user_id, grant.group_id) {
+        (Some(user_id), None) => {
+            repositories::user::try_get_by_id(
+                &state.pg_pool, user_id
+            )
+                .await
+                .map_err(DatasetError::RepoError)?
+                .ok_or(DatasetError::InvalidGrant(format!("user {} not found", user_id)))?;
+        }
+        (None, Some(group_id)) => {
+            repositories::group::try_get_by_id(
+                &state.pg_pool, group_id
+            )
+                .await
+                .map_err(DatasetError::RepoError)?
+                .ok_or(DatasetError::InvalidGrant(format!("group {} not found", group_id)))?;
+        }
+        _ => {
+            return Err(DatasetError::InvalidGrant(
+                "exactly one of user_id and group_id is required".to_string()
+            ));
+        }
+    }
+
+    let role = DatasetRole::from(grant.role);
+    let current = repositories::ds_acl::try_get_by_grantee(
+        &state.pg_pool, ds_id, grant.user_id, grant.group_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    if current.is_some_and(|current| current.role == DatasetRole::Owner) && role != DatasetRole::Owner {
+        let owners = repositories::ds_acl::count_owners(
+            &state.pg_pool, ds_id
+        )
+            .await
+            .map_err(DatasetError::RepoError)?;
+
+        if owners <= 1 {
+            return Err(DatasetError::LastOwner);
+        }
+    }
+
+    let acl = repositories::ds_acl::grant(
+        &state.pg_pool,
+        NewDatasetAclDB {
+            ds_id,
+            user_id: grant.user_id,
+            group_id: grant.group_id,
+            role: role.as_str().to_string(),
+        },
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    Ok(Json(DatasetAclResponse {
+        code: 0,
+        data: Some(DatasetAclSchema::from(acl)),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/datasets/{id}/acls",
+    params(
+        ("id", Path, description = "Dataset id"),
+        DatasetAclSearchQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Dataset grants query successfully",
+            body = ListDatasetAclsResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_dataset_acls(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    Query(mut params): Query<DatasetAclsFilter>,
+) -> Result<Json<ListDatasetAclsResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Viewer).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    params.ds_id = Some(ds_id);
+
+    let acls = repositories::ds_acl::get_all(
+        &state.pg_pool, params
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    let acls = acls.map(DatasetAclSchema::from);
+
+    Ok(Json(ListDatasetAclsResponse {
+        code: 0,
+        data: Some(acls.items),
+        next_cursor: acls.next_cursor,
+        total: acls.total,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/datasets/{id}/acls/{acl_id}",
+    params(
+        ("id", Path, description = "Dataset id"),
+        ("acl_id", Path, description = "Dataset grant id"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Dataset grant revoked successfully",
+            body = DeleteDatasetAclResponse,
+        ),
+        (status = BAD_REQUEST, description = "The last owner can't be revoked"),
+        (status = FORBIDDEN, description = "Only owners manage grants"),
+        (status = NOT_FOUND, description = "Dataset grant not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn revoke_dataset_acl(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor((ds_id, acl_id)): PathExtractor<(i32, i32)>,
+) -> Result<Json<DeleteDatasetAclResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Owner).await?;
+
+    let acl = repositories::ds_acl::try_get_by_id(
+        &state.pg_pool, acl_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .filter(|acl| acl.ds_id == ds_id)
+        .ok_or(DatasetError::AclNotFound)?;
+
+    if acl.role == DatasetRole::Owner {
+        let owners = repositories::ds_acl::count_owners(
+            &state.pg_pool, ds_id
+        )
+            .await
+            .map_err(DatasetError::RepoError)?;
+
+        if owners <= 1 {
+            return Err(DatasetError::LastOwner);
+        }
+    }
+
+    repositories::ds_acl::delete_by_id(
+        &state.pg_pool, acl_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    Ok(Json(DeleteDatasetAclResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
@@ -1,9 +1,10 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::user::UserModel,
     infra::repositories::{self, dataset::NewDatasetDB},
     server::AppState,
     utils::extractors::json::JsonExtractor,
@@ -16,11 +17,13 @@ pub struct DatasetCreationRequest {
     pub description: String,
 }
 
-impl Into<NewDatasetDB> for DatasetCreationRequest {
-    fn into(self) -> NewDatasetDB {
+impl DatasetCreationRequest {
+    fn into_new_ds(self, user_id: i32) -> NewDatasetDB {
         NewDatasetDB {
             name: self.name,
             description: self.description,
+            created_by: user_id,
+            updated_by: user_id,
         }
     }
 }
@@ -39,7 +42,7 @@ pub struct DatasetCreationResponse {
     responses(
         (
             status = 200,
-            description = "Dataset created successfully",
+            description = "Dataset created successfully, the caller becoming its owner",
             body = DatasetCreationResponse,
         ),
     )
@@ -47,6 +50,7 @@ pub struct DatasetCreationResponse {
 #[instrument(skip(state))]
 pub async fn create_dataset(
     State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
     JsonExtractor(new_ds): JsonExtractor<DatasetCreationRequest>,
 ) -> Result<Json<DatasetCreationResponse>, DatasetError> {
     let ds_in_db = repositories::dataset::try_get_by_name(
@@ -59,8 +63,8 @@ pub async fn create_dataset(
         return Err(DatasetError::Duplicate);
     }
 
-    let created_ds = repositories::dataset::create(
-        &state.pg_pool, new_ds.into()
+    let created_ds = repositories::dataset::create_owned(
+        &state.pg_pool, new_ds.into_new_ds(user.id), user.id
     )
         .await
         .map_err(DatasetError::RepoError)?;
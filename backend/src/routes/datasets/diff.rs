@@ -0,0 +1,169 @@
+use axum::{
+    body::Body,
+    extract::{State, Query},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories::{
+        self,
+        default_limit,
+        ds_version::DiffSide,
+        pagination::{Cursor, MAX_LIMIT, clamp_limit, deserialize_cursor},
+    },
+    middlewares::auth::Caller,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::{access::require_dataset_role, error::DatasetError, schema::DatasetDiffSchema};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DatasetDiffFormat {
+    #[default]
+    Json,
+    Jsonl,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DatasetDiffQuery {
+    /// Version id to diff from
+    pub from: i32,
+    /// Version id to diff to, default: the current state of the dataset
+    pub to: Option<i32>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    #[serde(default, deserialize_with = "deserialize_cursor")]
+    #[param(value_type = Option<String>)]
+    pub cursor: Option<Cursor>,
+    /// Limit, default: 20, max: 100
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    /// json for one page, or jsonl to stream every difference, default: json
+    #[serde(default)]
+    #[param(inline)]
+    pub format: DatasetDiffFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetDiffResponse {
+    code: i32,
+    data: Option<Vec<DatasetDiffSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    msg: Option<String>,
+}
+
+async fn diff_side(
+    state: &AppState,
+    ds_id: i32,
+    version: Option<i32>,
+) -> Result<DiffSide, DatasetError> {
+    let Some(version) = version else {
+        return Ok(DiffSide::Current(ds_id));
+    };
+
+    repositories::ds_version::try_get_by_id(
+        &state.pg_pool, version
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .filter(|version| version.ds_id == ds_id)
+        .ok_or(DatasetError::VersionNotFound)?;
+
+    Ok(DiffSide::Version(version))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/datasets/{id}/diff",
+    params(
+        ("id", Path, description = "Dataset id"),
+        DatasetDiffQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Items and shards added, removed or with changed annotations, \
+                items first, each ordered by id",
+            body = DatasetDiffResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset or dataset version not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn diff_dataset(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    Query(params): Query<DatasetDiffQuery>,
+) -> Result<Response, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Viewer).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    let from = diff_side(&state, ds_id, Some(params.from)).await?;
+    let to = diff_side(&state, ds_id, params.to).await?;
+
+    if let DatasetDiffFormat::Json = params.format {
+        let (diffs, next_cursor) = repositories::ds_version::get_diff_page(
+            &state.pg_pool, from, to, params.cursor, clamp_limit(params.limit)
+        )
+            .await
+            .map_err(DatasetError::RepoError)?;
+
+        return Ok(Json(DatasetDiffResponse {
+            code: 0,
+            data: Some(diffs.into_iter().map(DatasetDiffSchema::from).collect()),
+            next_cursor: next_cursor.map(|cursor| cursor.encode()),
+            msg: None,
+        })
+            .into_response());
+    }
+
+    // Streams one page at a time, `None` marking the end of the diff.
+    let stream = futures_util::stream::unfold(Some(params.cursor), move |cursor| {
+        let state = state.clone();
+        async move {
+            let cursor = cursor?;
+            let page = repositories::ds_version::get_diff_page(
+                &state.pg_pool, from, to, cursor, MAX_LIMIT
+            ).await;
+
+            match page {
+                Ok((diffs, next_cursor)) => {
+                    let mut chunk = Vec::new();
+                    for diff in diffs {
+                        // Serializing plain strings and numbers can't fail.
+                        serde_json::to_writer(&mut chunk, &DatasetDiffSchema::from(diff))
+                            .unwrap_or_default();
+                        chunk.push(b'\n');
+                    }
+                    Some((Ok(chunk), next_cursor.map(Some)))
+                }
+                Err(err) => {
+                    tracing::error!("Failed to diff dataset {}: {}", ds_id, err);
+                    Some((Err(std::io::Error::other(err.to_string())), None))
+                }
+            }
+        }
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
@@ -2,11 +2,23 @@ use axum::{response::IntoResponse, http::StatusCode, Json};
 use serde_json::json;
 
 use crate::infra::repositories::error::RepoError;
+use super::access::AccessError;
 
 #[derive(Debug)]
 pub enum DatasetError {
     NotFound,
     Duplicate,
+    ItemNotFound,
+    ShardNotFound,
+    InvalidImport(String),
+    RouteNotFound,
+    ExportFailed(String),
+    VersionNotFound,
+    DuplicateVersion,
+    AccessDenied,
+    AclNotFound,
+    InvalidGrant(String),
+    LastOwner,
     RepoError(RepoError),
 }
 
@@ -23,6 +35,61 @@ impl IntoResponse for DatasetError {
                 40002,
                 format!("Dataset already exists."),
             ),
+            Self::ItemNotFound => (
+                StatusCode::NOT_FOUND,
+                40004,
+                format!("Dataset item not found."),
+            ),
+            Self::ShardNotFound => (
+                StatusCode::NOT_FOUND,
+                40005,
+                format!("Dataset shard not found."),
+            ),
+            Self::InvalidImport(msg) => (
+                StatusCode::BAD_REQUEST,
+                40006,
+                format!("Invalid import body: {}", msg),
+            ),
+            Self::RouteNotFound => (
+                StatusCode::NOT_FOUND,
+                40007,
+                format!("Route not found."),
+            ),
+            Self::ExportFailed(msg) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                40008,
+                format!("Failed to export dataset: {}", msg),
+            ),
+            Self::VersionNotFound => (
+                StatusCode::NOT_FOUND,
+                40009,
+                format!("Dataset version not found."),
+            ),
+            Self::DuplicateVersion => (
+                StatusCode::BAD_REQUEST,
+                40010,
+                format!("Dataset version tag already exists."),
+            ),
+            Self::AccessDenied => (
+                StatusCode::FORBIDDEN,
+                40011,
+                format!("Dataset role too weak for this operation."),
+            ),
+            Self::AclNotFound => (
+                StatusCode::NOT_FOUND,
+                40012,
+                format!("Dataset grant not found."),
+            ),
+            Self::InvalidGrant(msg) => (
+                StatusCode::BAD_REQUEST,
+                40013,
+                format!("Invalid dataset grant: {}", msg),
+            ),
+            Self::LastOwner => (
+                StatusCode::BAD_REQUEST,
+                40014,
+                format!("A dataset must keep at least one owner."),
+            ),
             Self::RepoError(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 40003,
@@ -36,3 +103,13 @@ impl IntoResponse for DatasetError {
             .into_response()
     }
 }
+
+impl From<AccessError> for DatasetError {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::Hidden => Self::NotFound,
+            AccessError::Denied => Self::AccessDenied,
+            AccessError::RepoError(err) => Self::RepoError(err),
+        }
+    }
+}
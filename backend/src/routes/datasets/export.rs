@@ -0,0 +1,383 @@
+use std::{io, sync::Arc};
+
+use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use axum::{
+    body::Body,
+    extract::{State, Query},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use bytes::Bytes;
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use futures_util::Stream;
+use parquet::arrow::ArrowWriter;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    domain::models::{
+        ds_acl::DatasetRole,
+        ds_item::DatasetItemModel,
+        ds_item_anno::DatasetItemAnnoModel,
+    },
+    infra::repositories,
+    middlewares::auth::Caller,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::{access::require_dataset_role, error::DatasetError};
+
+/// Number of items loaded from the database per chunk of the response body.
+const EXPORT_PAGE_SIZE: i64 = 1000;
+
+const CSV_HEADER: [&str; 8] = [
+    "id", "uri", "typ", "anno_name", "anno_typ", "anno_uri", "anno_number", "anno_text",
+];
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Jsonl,
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Jsonl => "application/x-ndjson",
+            Self::Csv => "text/csv",
+            Self::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    /// Output format, defaults to jsonl
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Dataset version id, defaults to the current items and annotations
+    pub version: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExportItemAnnoRecord {
+    pub name: String,
+    pub typ: String,
+    pub uri: Option<String>,
+    pub number: Option<f64>,
+    pub text: Option<String>,
+}
+
+/// One JSONL line. CSV and Parquet exports are flattened to one row per
+/// annotation, using the same columns as the CSV import plus the item `id`;
+/// items without annotations get a single row with empty `anno_*` columns.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExportItemRecord {
+    pub id: i32,
+    pub uri: String,
+    pub typ: String,
+    #[schema(value_type = String)]
+    pub created_at: NaiveDateTime,
+    #[schema(value_type = String)]
+    pub updated_at: NaiveDateTime,
+    pub annotations: Vec<ExportItemAnnoRecord>,
+}
+
+type ExportPage = Vec<(DatasetItemModel, Vec<DatasetItemAnnoModel>)>;
+
+impl From<(DatasetItemModel, Vec<DatasetItemAnnoModel>)> for ExportItemRecord {
+    fn from((item, annos): (DatasetItemModel, Vec<DatasetItemAnnoModel>)) -> Self {
+        Self {
+            id: item.id,
+            uri: item.uri,
+            typ: item.typ,
+            created_at: item.created_at,
+            updated_at: item.updated_at,
+            annotations: annos
+                .into_iter()
+                .map(|anno| ExportItemAnnoRecord {
+                    name: anno.name,
+                    typ: anno.typ,
+                    uri: anno.uri,
+                    number: anno.number,
+                    text: anno.text,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Flat view of a page shared by the CSV and Parquet encoders.
+struct ExportRow<'a> {
+    item: &'a DatasetItemModel,
+    anno: Option<&'a DatasetItemAnnoModel>,
+}
+
+fn flatten(page: &ExportPage) -> Vec<ExportRow<'_>> {
+    page.iter()
+        .flat_map(|(item, annos)| {
+            let rows: Vec<ExportRow> = if annos.is_empty() {
+                vec![ExportRow { item, anno: None }]
+            } else {
+                annos.iter().map(|anno| ExportRow { item, anno: Some(anno) }).collect()
+            };
+            rows
+        })
+        .collect()
+}
+
+fn parquet_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("uri", DataType::Utf8, false),
+        Field::new("typ", DataType::Utf8, false),
+        Field::new("anno_name", DataType::Utf8, true),
+        Field::new("anno_typ", DataType::Utf8, true),
+        Field::new("anno_uri", DataType::Utf8, true),
+        Field::new("anno_number", DataType::Float64, true),
+        Field::new("anno_text", DataType::Utf8, true),
+    ]))
+}
+
+/// Turns pages of items into chunks of the response body.
+enum ExportEncoder {
+    Jsonl,
+    Csv { header_written: bool },
+    Parquet(Box<ArrowWriter<Vec<u8>>>),
+}
+
+impl ExportEncoder {
+    fn new(format: ExportFormat) -> io::Result<Self> {
+        Ok(match format {
+            ExportFormat::Jsonl => Self::Jsonl,
+            ExportFormat::Csv => Self::Csv { header_written: false },
+            ExportFormat::Parquet => Self::Parquet(Box::new(
+                ArrowWriter::try_new(Vec::new(), parquet_schema(), None)
+                    .map_err(io_error)?,
+            )),
+        })
+    }
+
+    fn encode(&mut self, page: ExportPage) -> io::Result<Bytes> {
+        match self {
+            Self::Jsonl => {
+                let mut buf = Vec::new();
+                for entry in page {
+                    serde_json::to_writer(&mut buf, &ExportItemRecord::from(entry))
+                        .map_err(io_error)?;
+                    buf.push(b'\n');
+                }
+                Ok(buf.into())
+            }
+            Self::Csv { header_written } => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                if !*header_written {
+                    writer.write_record(CSV_HEADER).map_err(io_error)?;
+                    *header_written = true;
+                }
+
+                for row in flatten(&page) {
+                    let anno = row.anno;
+                    writer
+                        .write_record([
+                            row.item.id.to_string().as_str(),
+                            &row.item.uri,
+                            &row.item.typ,
+                            anno.map_or("", |anno| &anno.name),
+                            anno.map_or("", |anno| &anno.typ),
+                            anno.and_then(|anno| anno.uri.as_deref()).unwrap_or(""),
+                            &anno.and_then(|anno| anno.number).map(|n| n.to_string()).unwrap_or_default(),
+                            anno.and_then(|anno| anno.text.as_deref()).unwrap_or(""),
+                        ])
+                        .map_err(io_error)?;
+                }
+
+                writer
+                    .into_inner()
+                    .map(Into::into)
+                    .map_err(|err| io_error(err.into_error()))
+            }
+            Self::Parquet(writer) => {
+                let rows = flatten(&page);
+                let columns: Vec<ArrayRef> = vec![
+                    Arc::new(Int32Array::from_iter_values(rows.iter().map(|row| row.item.id))),
+                    Arc::new(StringArray::from_iter_values(rows.iter().map(|row| &row.item.uri))),
+                    Arc::new(StringArray::from_iter_values(rows.iter().map(|row| &row.item.typ))),
+                    Arc::new(StringArray::from_iter(rows.iter().map(|row| row.anno.map(|anno| &anno.name)))),
+                    Arc::new(StringArray::from_iter(rows.iter().map(|row| row.anno.map(|anno| &anno.typ)))),
+                    Arc::new(StringArray::from_iter(rows.iter().map(|row| row.anno.and_then(|anno| anno.uri.as_ref())))),
+                    Arc::new(Float64Array::from_iter(rows.iter().map(|row| row.anno.and_then(|anno| anno.number)))),
+                    Arc::new(StringArray::from_iter(rows.iter().map(|row| row.anno.and_then(|anno| anno.text.as_ref())))),
+                ];
+                let batch = RecordBatch::try_new(parquet_schema(), columns)
+                    .map_err(io_error)?;
+
+                // Every page becomes its own row group, so the bytes written
+                // so far can be handed to the client straight away.
+                writer.write(&batch).map_err(io_error)?;
+                writer.flush().map_err(io_error)?;
+                Ok(std::mem::take(writer.inner_mut()).into())
+            }
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<Bytes> {
+        match self {
+            Self::Jsonl => Ok(Bytes::new()),
+            // An empty dataset still gets its header line.
+            Self::Csv { header_written: false } => self.encode(vec![]),
+            Self::Csv { .. } => Ok(Bytes::new()),
+            Self::Parquet(writer) => {
+                writer.finish().map_err(io_error)?;
+                Ok(std::mem::take(writer.inner_mut()).into())
+            }
+        }
+    }
+}
+
+/// Errors raised once the body has started streaming can no longer change the
+/// status code, so they are plain I/O errors that abort the response.
+fn io_error<E: std::fmt::Display>(err: E) -> io::Error {
+    io::Error::other(err.to_string())
+}
+
+struct ExportState {
+    pg_pool: deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    version: Option<i32>,
+    after_id: Option<i32>,
+    encoder: ExportEncoder,
+    done: bool,
+}
+
+impl ExportState {
+    async fn next_chunk(&mut self) -> io::Result<Bytes> {
+        let page = repositories::ds_item::get_export_page(
+            &self.pg_pool, self.ds_id, self.version, self.after_id, EXPORT_PAGE_SIZE
+        )
+            .await
+            .map_err(|err| {
+                tracing::error!("Failed to export dataset {}: {}", self.ds_id, err);
+                io_error(err)
+            })?;
+
+        if (page.len() as i64) < EXPORT_PAGE_SIZE {
+            self.done = true;
+        }
+        self.after_id = page.last().map(|(item, _)| item.id).or(self.after_id);
+
+        let mut chunk = Vec::from(self.encoder.encode(page)?);
+        if self.done {
+            chunk.extend_from_slice(&self.encoder.finish()?);
+        }
+
+        Ok(chunk.into())
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/datasets/{id}/export",
+    params(
+        ("id", Path, description = "Dataset id"),
+        ExportQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Every item linked to the dataset with its annotations, as a chunked body",
+            body = ExportItemRecord,
+            content_type = "application/x-ndjson",
+        ),
+        (status = NOT_FOUND, description = "Dataset or dataset version not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn export_dataset(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    Query(params): Query<ExportQuery>,
+) -> Result<Response, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Viewer).await?;
+
+    let format = params.format;
+    let stream = export_items(state.pg_pool, ds_id, params.version, format).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"dataset-{}.{}\"", ds_id, format.extension()),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response())
+}
+
+/// Streams the items of the dataset, or of one of its versions, encoded in
+/// `format`; shared by the route and the CLI.
+pub async fn export_items(
+    pg_pool: deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    version: Option<i32>,
+    format: ExportFormat,
+) -> Result<impl Stream<Item = io::Result<Bytes>>, DatasetError> {
+    repositories::dataset::try_get_by_id(
+        &pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    if let Some(version) = version {
+        repositories::ds_version::try_get_by_id(
+            &pg_pool, version
+        )
+            .await
+            .map_err(DatasetError::RepoError)?
+            .filter(|version| version.ds_id == ds_id)
+            .ok_or(DatasetError::VersionNotFound)?;
+    }
+
+    let encoder = ExportEncoder::new(format)
+        .map_err(|err| DatasetError::ExportFailed(err.to_string()))?;
+    let export = ExportState {
+        pg_pool,
+        ds_id,
+        version,
+        after_id: None,
+        encoder,
+        done: false,
+    };
+
+    Ok(futures_util::stream::unfold(export, |mut export| async move {
+        if export.done {
+            return None;
+        }
+
+        let chunk = export.next_chunk().await;
+        if chunk.is_err() {
+            export.done = true;
+        }
+        Some((chunk, export))
+    }))
+}
@@ -1,14 +1,16 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories,
+    middlewares::auth::Caller,
     server::AppState,
     utils::extractors::path::PathExtractor,
 };
-use super::{error::DatasetError, schema::DatasetSchema};
+use super::{access::require_dataset_role, error::DatasetError, schema::DatasetSchema};
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GetDatasetResponse {
@@ -31,8 +33,11 @@ pub struct GetDatasetResponse {
 #[instrument(skip(state))]
 pub async fn get_dataset(
     State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
     PathExtractor(ds_id): PathExtractor<i32>,
 ) -> Result<Json<GetDatasetResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Viewer).await?;
+
     let ds = repositories::dataset::get_by_id(
         &state.pg_pool, ds_id
     )
@@ -0,0 +1,706 @@
+use axum::{
+    body::Body,
+    extract::{State, Query, RawPathParams, Request},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+    Extension,
+};
+use bytes::Bytes;
+use clap::ValueEnum;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories::{
+        self,
+        ds_item::{ImportedItemDB, ImportedItemAnnoDB},
+    },
+    middlewares::auth::Caller,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::{access::{require_dataset_role, visible_to}, error::DatasetError};
+
+/// Number of records written per transaction.
+const IMPORT_BATCH_SIZE: usize = 1000;
+/// Upper bound on the per-line errors echoed back to the client.
+const MAX_REPORTED_ERRORS: usize = 1000;
+/// Matches the `VARCHAR(255)` columns of `ds_items` and `ds_item_annos`.
+const MAX_FIELD_LEN: usize = 255;
+/// Upper bound on a single NDJSON line or CSV row, which has to be buffered
+/// whole before it can be parsed.
+const MAX_RECORD_LEN: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Ndjson,
+    Csv,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportQuery {
+    /// Input format, defaults to the request content type (ndjson if unknown)
+    pub format: Option<ImportFormat>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportItemAnnoRecord {
+    pub name: String,
+    pub typ: String,
+    pub uri: Option<String>,
+    pub number: Option<f64>,
+    pub text: Option<String>,
+}
+
+/// One NDJSON line. CSV rows use the columns `uri`, `typ` and optionally
+/// `anno_name`, `anno_typ`, `anno_uri`, `anno_number`, `anno_text`; repeat a
+/// uri on several rows to attach several annotations to it. Annotations
+/// replace those of the item with the same name, so that importing the same
+/// records again changes nothing.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportItemRecord {
+    pub uri: String,
+    pub typ: String,
+    #[serde(default)]
+    pub annotations: Vec<ImportItemAnnoRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportLineError {
+    pub line: usize,
+    pub msg: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ImportReport {
+    /// Number of non-empty records read, excluding the CSV header
+    pub total: usize,
+    pub imported: usize,
+    pub failed: usize,
+    /// The first errors encountered, in line order. When reading the input
+    /// broke off, the last one names the line it broke off at: the lines
+    /// before it were handled, it and the ones after weren't read.
+    pub errors: Vec<ImportLineError>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportDatasetItemsResponse {
+    pub code: i32,
+    pub data: Option<ImportReport>,
+    pub msg: Option<String>,
+}
+
+impl ImportItemRecord {
+    fn validate(&self) -> Result<(), String> {
+        if self.uri.is_empty() {
+            return Err("uri must not be empty".to_string());
+        }
+
+        let fields = [("uri", Some(&self.uri)), ("typ", Some(&self.typ))]
+            .into_iter()
+            .chain(self.annotations.iter().flat_map(|anno| [
+                ("annotation name", Some(&anno.name)),
+                ("annotation typ", Some(&anno.typ)),
+                ("annotation uri", anno.uri.as_ref()),
+            ]));
+
+        for (field, value) in fields {
+            if value.is_some_and(|value| value.len() > MAX_FIELD_LEN) {
+                return Err(format!("{} is longer than {} bytes", field, MAX_FIELD_LEN));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Into<ImportedItemDB> for ImportItemRecord {
+    fn into(self) -> ImportedItemDB {
+        ImportedItemDB {
+            typ: self.typ,
+            uri: self.uri,
+            annos: self.annotations
+                .into_iter()
+                .map(|anno| ImportedItemAnnoDB {
+                    name: anno.name,
+                    typ: anno.typ,
+                    uri: anno.uri,
+                    number: anno.number,
+                    text: anno.text,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Column positions of a CSV import, resolved from its header line.
+struct CsvColumns {
+    uri: usize,
+    typ: usize,
+    anno_name: Option<usize>,
+    anno_typ: Option<usize>,
+    anno_uri: Option<usize>,
+    anno_number: Option<usize>,
+    anno_text: Option<usize>,
+}
+
+impl CsvColumns {
+    fn from_header(header: &csv::StringRecord) -> Result<Self, String> {
+        let position = |name: &str| header
+            .iter()
+            .position(|col| col.trim_start_matches('\u{feff}').trim() == name);
+
+        Ok(Self {
+            uri: position("uri").ok_or("missing `uri` column in CSV header")?,
+            typ: position("typ").ok_or("missing `typ` column in CSV header")?,
+            anno_name: position("anno_name"),
+            anno_typ: position("anno_typ"),
+            anno_uri: position("anno_uri"),
+            anno_number: position("anno_number"),
+            anno_text: position("anno_text"),
+        })
+    }
+
+    fn parse(&self, row: &csv::StringRecord) -> Result<ImportItemRecord, String> {
+        let get = |idx: Option<usize>| idx
+            .and_then(|idx| row.get(idx))
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned);
+
+        let uri = get(Some(self.uri)).ok_or("missing uri")?;
+        let typ = get(Some(self.typ)).ok_or("missing typ")?;
+
+        let annotations = match get(self.anno_name) {
+            Some(name) => {
+                let number = get(self.anno_number)
+                    .map(|number| number.parse::<f64>())
+                    .transpose()
+                    .map_err(|err| format!("invalid anno_number: {}", err))?;
+
+                vec![ImportItemAnnoRecord {
+                    name,
+                    typ: get(self.anno_typ).ok_or("missing anno_typ")?,
+                    uri: get(self.anno_uri),
+                    number,
+                    text: get(self.anno_text),
+                }]
+            }
+            None => vec![],
+        };
+
+        Ok(ImportItemRecord { uri, typ, annotations })
+    }
+}
+
+/// Splits a CSV stream into rows as its chunks arrive, quoted fields
+/// spanning several lines included.
+struct CsvRows {
+    reader: csv_core::Reader,
+    output: Vec<u8>,
+    ends: Vec<usize>,
+    nout: usize,
+    nend: usize,
+    /// Newlines read so far, quoted ones included
+    newlines: usize,
+    /// Whether the row being read has begun, which its output doesn't tell
+    /// when it opens with a quote
+    started: bool,
+    /// Line the row being read starts at
+    line_no: usize,
+}
+
+impl CsvRows {
+    fn new() -> Self {
+        Self {
+            reader: csv_core::Reader::new(),
+            output: vec![0; 1024],
+            ends: vec![0; 16],
+            nout: 0,
+            nend: 0,
+            newlines: 0,
+            started: false,
+            line_no: 1,
+        }
+    }
+
+    /// Line the row being read starts at, or the next one does.
+    fn line(&self) -> usize {
+        match self.started {
+            true => self.line_no,
+            false => self.newlines + 1,
+        }
+    }
+
+    /// Next complete row of `input`, consuming what was read of it, along
+    /// with the line it starts at. With `eof` the stream ended and the last
+    /// row is flushed.
+    fn next_row(&mut self, input: &mut &[u8], eof: bool) -> Result<Option<(usize, csv::ByteRecord)>, ImportLineError> {
+        use csv_core::ReadRecordResult;
+
+        loop {
+            // Blank lines and the rest of a CRLF are skipped between rows,
+            // so that the next one starts at its first byte.
+            if !self.started {
+                let skipped = input.iter().take_while(|b| matches!(b, b'\r' | b'\n')).count();
+                self.newlines += input[..skipped].iter().filter(|b| **b == b'\n').count();
+                *input = &input[skipped..];
+                self.line_no = self.newlines + 1;
+            }
+            if input.is_empty() && !eof {
+                return Ok(None);
+            }
+
+            let (res, nin, nout, nend) = self.reader.read_record(
+                input, &mut self.output[self.nout..], &mut self.ends[self.nend..]
+            );
+            self.newlines += input[..nin].iter().filter(|b| **b == b'\n').count();
+            self.started |= nin > 0;
+            *input = &input[nin..];
+            self.nout += nout;
+            self.nend += nend;
+
+            match res {
+                ReadRecordResult::InputEmpty | ReadRecordResult::End => return Ok(None),
+                ReadRecordResult::OutputFull | ReadRecordResult::OutputEndsFull => {
+                    // Empty fields take no output but an end each.
+                    if self.nout + self.nend >= MAX_RECORD_LEN {
+                        return Err(ImportLineError {
+                            line: self.line_no,
+                            msg: format!("row is longer than {} bytes", MAX_RECORD_LEN),
+                        });
+                    }
+                    match res {
+                        ReadRecordResult::OutputFull => self.output.resize(self.output.len() * 2, 0),
+                        _ => self.ends.resize(self.ends.len() * 2, 0),
+                    }
+                }
+                ReadRecordResult::Record => {
+                    let mut row = csv::ByteRecord::new();
+                    let mut start = 0;
+                    for &end in &self.ends[..self.nend] {
+                        row.push_field(&self.output[start..end]);
+                        start = end;
+                    }
+                    self.nout = 0;
+                    self.nend = 0;
+                    self.started = false;
+
+                    return Ok(Some((self.line_no, row)));
+                }
+            }
+        }
+    }
+}
+
+struct Importer {
+    pg_pool: deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    user_id: i32,
+    editable_by: Option<i32>,
+    csv_columns: Option<CsvColumns>,
+    batch: Vec<(usize, ImportItemRecord)>,
+    report: ImportReport,
+}
+
+impl Importer {
+    fn new(
+        pg_pool: deadpool_diesel::postgres::Pool,
+        ds_id: i32,
+        user_id: i32,
+        editable_by: Option<i32>,
+    ) -> Self {
+        Self {
+            pg_pool,
+            ds_id,
+            user_id,
+            editable_by,
+            csv_columns: None,
+            batch: Vec::with_capacity(IMPORT_BATCH_SIZE),
+            report: ImportReport::default(),
+        }
+    }
+
+    fn report_error(&mut self, line: usize, msg: String) {
+        self.report.failed += 1;
+        if self.report.errors.len() < MAX_REPORTED_ERRORS {
+            self.report.errors.push(ImportLineError { line, msg });
+        }
+    }
+
+    async fn push_line(&mut self, line_no: usize, line: &[u8]) -> Result<(), DatasetError> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(());
+        }
+
+        let record = serde_json::from_slice::<ImportItemRecord>(line)
+            .map_err(|err| format!("invalid JSON: {}", err));
+
+        self.push_record(line_no, record).await
+    }
+
+    async fn push_csv_row(&mut self, line_no: usize, row: csv::ByteRecord) -> Result<(), DatasetError> {
+        let row = csv::StringRecord::from_byte_record(row)
+            .map_err(|err| format!("invalid UTF-8: {}", err.utf8_error()));
+
+        match &self.csv_columns {
+            Some(columns) => {
+                let record = row.and_then(|row| columns.parse(&row));
+                self.push_record(line_no, record).await
+            }
+            // A malformed CSV header makes every following row meaningless.
+            None => {
+                let columns = row.and_then(|row| CsvColumns::from_header(&row))
+                    .map_err(DatasetError::InvalidImport)?;
+                self.csv_columns = Some(columns);
+                Ok(())
+            }
+        }
+    }
+
+    async fn push_record(
+        &mut self,
+        line_no: usize,
+        record: Result<ImportItemRecord, String>,
+    ) -> Result<(), DatasetError> {
+        self.report.total += 1;
+        match record.and_then(|record| record.validate().map(|_| record)) {
+            Ok(record) => self.batch.push((line_no, record)),
+            Err(msg) => self.report_error(line_no, msg),
+        }
+
+        if self.batch.len() >= IMPORT_BATCH_SIZE {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), DatasetError> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+
+        let batch = std::mem::take(&mut self.batch);
+        let lines = batch
+            .iter()
+            .map(|(line_no, record)| (*line_no, record.uri.clone()))
+            .collect::<Vec<(usize, String)>>();
+        let items = batch
+            .into_iter()
+            .map(|(_, record)| record.into())
+            .collect::<Vec<ImportedItemDB>>();
+
+        match repositories::ds_item::import_into_dataset(
+            &self.pg_pool, self.ds_id, items, self.user_id, self.editable_by
+        ).await {
+            Ok(imported) => {
+                for (line_no, uri) in lines {
+                    match imported.refused.contains(&uri) {
+                        true => self.report_error(line_no, "item exists in a dataset you can't edit".to_string()),
+                        false => self.report.imported += 1,
+                    }
+                }
+            }
+            Err(err) => {
+                tracing::error!("Failed to import batch into dataset {}: {}", self.ds_id, err);
+                for (line_no, _) in lines {
+                    self.report_error(line_no, "failed to write the batch containing this line".to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Answers 404 to the `/:id/items<action>` paths other than `:import` before
+/// the permission check, since the route captures all of them.
+pub async fn require_import_action(params: RawPathParams, req: Request, next: Next) -> Response {
+    let is_import = params
+        .iter()
+        .any(|(name, value)| name == "action" && value == ":import");
+    if !is_import {
+        return DatasetError::RouteNotFound.into_response();
+    }
+
+    next.run(req).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/datasets/{id}/items:import",
+    params(
+        ("id", Path, description = "Dataset id"),
+        ImportQuery,
+    ),
+    request_body(
+        content = ImportItemRecord,
+        description = "Newline-delimited JSON records, or CSV with a header line",
+        content_type = "application/x-ndjson",
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Dataset items imported, see the report for failed lines",
+            body = ImportDatasetItemsResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset not found"),
+    )
+)]
+#[instrument(skip(state, headers, body))]
+pub async fn import_dataset_items(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor((ds_id, _action)): PathExtractor<(i32, String)>,
+    Query(params): Query<ImportQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Json<ImportDatasetItemsResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Editor).await?;
+
+    let format = params.format.unwrap_or_else(|| {
+        let is_csv = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("csv"));
+
+        if is_csv { ImportFormat::Csv } else { ImportFormat::Ndjson }
+    });
+
+    // Existing items of other datasets are only overwritten and linked
+    // here when the caller can edit them wherever they are linked.
+    let editable_by = visible_to(&state, &caller)
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    let report = import_items(
+        state.pg_pool, ds_id, caller.user.id, editable_by, format, body.into_data_stream()
+    )
+        .await?;
+
+    Ok(Json(ImportDatasetItemsResponse {
+        code: 0,
+        data: Some(report),
+        msg: None,
+    }))
+}
+
+/// Imports the records read from `input` into the dataset on behalf of
+/// `user_id`, shared by the route and the CLI. With `editable_by`, records
+/// of existing items that user can't edit fail instead.
+pub async fn import_items<S, E>(
+    pg_pool: deadpool_diesel::postgres::Pool,
+    ds_id: i32,
+    user_id: i32,
+    editable_by: Option<i32>,
+    format: ImportFormat,
+    input: S,
+) -> Result<ImportReport, DatasetError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    repositories::dataset::try_get_by_id(
+        &pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    let mut importer = Importer::new(pg_pool, ds_id, user_id, editable_by);
+
+    let broke_off = match format {
+        ImportFormat::Ndjson => read_ndjson(&mut importer, input).await?,
+        ImportFormat::Csv => read_csv(&mut importer, input).await?,
+    };
+    importer.flush().await?;
+
+    // Batches read so far are committed, the report tells the client where
+    // to resume rather than being lost.
+    if let Some(error) = broke_off {
+        importer.report.errors.push(error);
+    }
+
+    Ok(importer.report)
+}
+
+/// Feeds the NDJSON lines of `input` to the importer. Returns where reading
+/// broke off when the input can't be read to its end.
+async fn read_ndjson<S, E>(
+    importer: &mut Importer,
+    mut input: S,
+) -> Result<Option<ImportLineError>, DatasetError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    // JSON strings can't hold a raw newline, every line is a record.
+    let mut buf = Vec::<u8>::new();
+    let mut line_no = 0;
+
+    while let Some(chunk) = input.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(err) => return Ok(Some(ImportLineError {
+                line: line_no + 1,
+                msg: format!("failed to read the input: {}", err),
+            })),
+        };
+        buf.extend_from_slice(&chunk);
+
+        let mut start = 0;
+        while let Some(pos) = buf[start..].iter().position(|b| *b == b'\n') {
+            line_no += 1;
+            importer.push_line(line_no, &buf[start..start + pos]).await?;
+            start += pos + 1;
+        }
+        buf.drain(..start);
+
+        if buf.len() > MAX_RECORD_LEN {
+            return Ok(Some(ImportLineError {
+                line: line_no + 1,
+                msg: format!("line is longer than {} bytes", MAX_RECORD_LEN),
+            }));
+        }
+    }
+
+    if !buf.is_empty() {
+        line_no += 1;
+        importer.push_line(line_no, &buf).await?;
+    }
+
+    Ok(None)
+}
+
+/// Feeds the CSV rows of `input` to the importer. Returns where reading
+/// broke off when the input can't be read to its end.
+async fn read_csv<S, E>(
+    importer: &mut Importer,
+    mut input: S,
+) -> Result<Option<ImportLineError>, DatasetError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    let mut rows = CsvRows::new();
+
+    while let Some(chunk) = input.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(err) => return Ok(Some(ImportLineError {
+                line: rows.line(),
+                msg: format!("failed to read the input: {}", err),
+            })),
+        };
+        let mut chunk = &chunk[..];
+        loop {
+            match rows.next_row(&mut chunk, false) {
+                Ok(Some((line_no, row))) => importer.push_csv_row(line_no, row).await?,
+                Ok(None) => break,
+                Err(error) => return Ok(Some(error)),
+            }
+        }
+    }
+
+    loop {
+        match rows.next_row(&mut &[][..], true) {
+            Ok(Some((line_no, row))) => importer.push_csv_row(line_no, row).await?,
+            Ok(None) => return Ok(None),
+            Err(error) => return Ok(Some(error)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rows read from `chunks` as they would arrive, with their line.
+    fn rows(chunks: &[&[u8]]) -> Result<Vec<(usize, Vec<String>)>, ImportLineError> {
+        let mut rows = CsvRows::new();
+        let mut read = Vec::new();
+        let mut push = |(line, row): (usize, csv::ByteRecord)| {
+            let fields = row.iter().map(|field| String::from_utf8_lossy(field).into_owned()).collect();
+            read.push((line, fields));
+        };
+
+        for chunk in chunks {
+            let mut chunk = *chunk;
+            while let Some(row) = rows.next_row(&mut chunk, false)? {
+                push(row);
+            }
+            assert!(chunk.is_empty());
+        }
+        while let Some(row) = rows.next_row(&mut &[][..], true)? {
+            push(row);
+        }
+
+        Ok(read)
+    }
+
+    fn row(line: usize, fields: &[&str]) -> (usize, Vec<String>) {
+        (line, fields.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn rows_span_chunk_boundaries() {
+        let csv = b"uri,typ\ns3://a,image\n\"s3://b\nc\",text\nlast,row";
+        let expected = vec![
+            row(1, &["uri", "typ"]),
+            row(2, &["s3://a", "image"]),
+            row(3, &["s3://b\nc", "text"]),
+            row(5, &["last", "row"]),
+        ];
+
+        assert_eq!(rows(&[csv]).unwrap(), expected);
+        for split in 1..csv.len() {
+            let (head, tail) = csv.split_at(split);
+            assert_eq!(rows(&[head, tail]).unwrap(), expected, "split at {}", split);
+        }
+        let bytes = csv.iter().map(std::slice::from_ref).collect::<Vec<_>>();
+        assert_eq!(rows(&bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn quoted_fields_hold_newlines_and_quotes() {
+        let csv = b"\"multi\nline\",\"say \"\"hi\"\"\",\"a,b\"\nnext,\"\",x\n";
+
+        assert_eq!(rows(&[csv]).unwrap(), vec![
+            row(1, &["multi\nline", "say \"hi\"", "a,b"]),
+            row(3, &["next", "", "x"]),
+        ]);
+    }
+
+    #[test]
+    fn crlf_line_endings_are_stripped() {
+        let csv = b"uri,typ\r\na,b\r\n\r\n\"c\r\nd\",e\r\n";
+        let expected = vec![
+            row(1, &["uri", "typ"]),
+            row(2, &["a", "b"]),
+            row(4, &["c\r\nd", "e"]),
+        ];
+
+        for split in 0..csv.len() {
+            let (head, tail) = csv.split_at(split);
+            assert_eq!(rows(&[head, tail]).unwrap(), expected, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn overlong_rows_are_refused_with_their_line() {
+        let mut csv = b"uri,typ\n".to_vec();
+        csv.extend(std::iter::repeat_n(b'a', MAX_RECORD_LEN + 1));
+
+        let error = rows(&[&csv]).unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+}
@@ -0,0 +1,144 @@
+use axum::{extract::State, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories,
+    middlewares::auth::Caller,
+    server::AppState,
+    utils::extractors::{json::JsonExtractor, path::PathExtractor},
+};
+use super::{access::{require_dataset_role, visible_to}, error::DatasetError};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DatasetItemsRelRequest {
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetItemsRelResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/datasets/{id}/items",
+    params(
+        ("id", Path, description = "Dataset id")
+    ),
+    request_body = DatasetItemsRelRequest,
+    responses(
+        (
+            status = 200,
+            description = "Dataset items attached successfully",
+            body = DatasetItemsRelResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset or dataset item not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn attach_dataset_items(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    JsonExtractor(DatasetItemsRelRequest { mut ids }): JsonExtractor<DatasetItemsRelRequest>,
+) -> Result<Json<DatasetItemsRelResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Editor).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    ids.sort_unstable();
+    ids.dedup();
+
+    let items = repositories::ds_item::get_by_ids(
+        &state.pg_pool, ids.clone()
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    if items.len() != ids.len() {
+        return Err(DatasetError::ItemNotFound);
+    }
+
+    // Linking hidden items would let the caller read them through this dataset.
+    if let Some(user_id) = visible_to(&state, &caller)
+        .await
+        .map_err(DatasetError::RepoError)?
+    {
+        let visible = repositories::ds_acl::count_visible_items(
+            &state.pg_pool, user_id, ids.clone()
+        )
+            .await
+            .map_err(DatasetError::RepoError)?;
+
+        if visible != ids.len() as i64 {
+            return Err(DatasetError::ItemNotFound);
+        }
+    }
+
+    repositories::dataset_item_rel::create_many(
+        &state.pg_pool, ds_id, ids
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    Ok(Json(DatasetItemsRelResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/datasets/{id}/items",
+    params(
+        ("id", Path, description = "Dataset id")
+    ),
+    request_body = DatasetItemsRelRequest,
+    responses(
+        (
+            status = 200,
+            description = "Dataset items detached successfully",
+            body = DatasetItemsRelResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn detach_dataset_items(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    JsonExtractor(DatasetItemsRelRequest { ids }): JsonExtractor<DatasetItemsRelRequest>,
+) -> Result<Json<DatasetItemsRelResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Editor).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    repositories::dataset_item_rel::delete_by_ids(
+        &state.pg_pool, ds_id, ids
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    Ok(Json(DatasetItemsRelResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
@@ -0,0 +1,93 @@
+use axum::{extract::State, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories::{self, ds_item_anno::NewDatasetItemAnnoDB},
+    middlewares::auth::Caller,
+    routes::datasets::access::require_item_role,
+    server::AppState,
+    utils::extractors::{
+        json::JsonExtractor,
+        path::PathExtractor,
+    },
+};
+use super::{error::DatasetItemAnnoError, schema::DatasetItemAnnoSchema};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DatasetItemAnnoCreationRequest {
+    pub name: String,
+    pub typ: String,
+    pub uri: Option<String>,
+    pub number: Option<f64>,
+    pub text: Option<String>,
+}
+
+impl DatasetItemAnnoCreationRequest {
+    fn into_new_anno(self, item_id: i32, user_id: i32) -> NewDatasetItemAnnoDB {
+        NewDatasetItemAnnoDB {
+            item_id,
+            name: self.name,
+            typ: self.typ,
+            uri: self.uri,
+            number: self.number,
+            text: self.text,
+            created_by: user_id,
+            updated_by: user_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetItemAnnoCreationResponse {
+    pub code: i32,
+    pub data: Option<DatasetItemAnnoSchema>,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/datasets/items/{id}/annos",
+    params(
+        ("id", Path, description = "Dataset item id")
+    ),
+    request_body = DatasetItemAnnoCreationRequest,
+    responses(
+        (
+            status = 200,
+            description = "Dataset item annotation created successfully",
+            body = DatasetItemAnnoCreationResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset item not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn create_dataset_item_anno(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(item_id): PathExtractor<i32>,
+    JsonExtractor(new_anno): JsonExtractor<DatasetItemAnnoCreationRequest>,
+) -> Result<Json<DatasetItemAnnoCreationResponse>, DatasetItemAnnoError> {
+    require_item_role(&state, &caller, item_id, DatasetRole::Editor).await?;
+
+    repositories::ds_item::try_get_by_id(
+        &state.pg_pool, item_id
+    )
+        .await
+        .map_err(DatasetItemAnnoError::RepoError)?
+        .ok_or(DatasetItemAnnoError::ItemNotFound)?;
+
+    let created_anno = repositories::ds_item_anno::create(
+        &state.pg_pool, new_anno.into_new_anno(item_id, caller.user.id)
+    )
+        .await
+        .map_err(DatasetItemAnnoError::RepoError)?;
+
+    Ok(Json(DatasetItemAnnoCreationResponse {
+        code: 0,
+        data: Some(DatasetItemAnnoSchema::from(created_anno)),
+        msg: None,
+    }))
+}
@@ -0,0 +1,64 @@
+use axum::{extract::State, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories,
+    middlewares::auth::Caller,
+    routes::datasets::access::require_item_role,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::error::DatasetItemAnnoError;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteDatasetItemAnnoResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/datasets/items/{id}/annos/{anno_id}",
+    params(
+        ("id", Path, description = "Dataset item id"),
+        ("anno_id", Path, description = "Dataset item annotation id"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Dataset item annotation deletion successfully",
+            body = DeleteDatasetItemAnnoResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset item annotation not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn delete_dataset_item_anno(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor((item_id, anno_id)): PathExtractor<(i32, i32)>,
+) -> Result<Json<DeleteDatasetItemAnnoResponse>, DatasetItemAnnoError> {
+    require_item_role(&state, &caller, item_id, DatasetRole::Editor).await?;
+
+    repositories::ds_item_anno::try_get_by_id(
+        &state.pg_pool, anno_id
+    )
+        .await
+        .map_err(DatasetItemAnnoError::RepoError)?
+        .filter(|anno| anno.item_id == item_id)
+        .ok_or(DatasetItemAnnoError::NotFound)?;
+
+    repositories::ds_item_anno::delete_by_id(&state.pg_pool, anno_id)
+        .await
+        .map_err(DatasetItemAnnoError::RepoError)?;
+
+    Ok(Json(DeleteDatasetItemAnnoResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
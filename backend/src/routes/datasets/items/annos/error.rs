@@ -0,0 +1,57 @@
+use axum::{response::IntoResponse, http::StatusCode, Json};
+use serde_json::json;
+
+use crate::{
+    infra::repositories::error::RepoError,
+    routes::datasets::access::AccessError,
+};
+
+#[derive(Debug)]
+pub enum DatasetItemAnnoError {
+    NotFound,
+    ItemNotFound,
+    AccessDenied,
+    RepoError(RepoError),
+}
+
+impl IntoResponse for DatasetItemAnnoError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, code, err_msg) = match self {
+            Self::NotFound => (
+                StatusCode::NOT_FOUND,
+                40001,
+                format!("Dataset item annotation not found."),
+            ),
+            Self::ItemNotFound => (
+                StatusCode::NOT_FOUND,
+                40004,
+                format!("Dataset item not found."),
+            ),
+            Self::AccessDenied => (
+                StatusCode::FORBIDDEN,
+                40005,
+                format!("Dataset role too weak for this operation."),
+            ),
+            Self::RepoError(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                40003,
+                format!("Dataset item annotation server error."),
+            ),
+        };
+        (
+            status,
+            Json(json!({"code": code, "msg": err_msg})),
+        )
+            .into_response()
+    }
+}
+
+impl From<AccessError> for DatasetItemAnnoError {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::Hidden => Self::ItemNotFound,
+            AccessError::Denied => Self::AccessDenied,
+            AccessError::RepoError(err) => Self::RepoError(err),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use axum::{extract::State, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories,
+    middlewares::auth::Caller,
+    routes::datasets::access::require_item_role,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::{error::DatasetItemAnnoError, schema::DatasetItemAnnoSchema};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetDatasetItemAnnoResponse {
+    pub code: i32,
+    pub data: Option<DatasetItemAnnoSchema>,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/datasets/items/{id}/annos/{anno_id}",
+    params(
+        ("id", Path, description = "Dataset item id"),
+        ("anno_id", Path, description = "Dataset item annotation id"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Dataset item annotation query successfully",
+            body = GetDatasetItemAnnoResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset item annotation not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_dataset_item_anno(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor((item_id, anno_id)): PathExtractor<(i32, i32)>,
+) -> Result<Json<GetDatasetItemAnnoResponse>, DatasetItemAnnoError> {
+    require_item_role(&state, &caller, item_id, DatasetRole::Viewer).await?;
+
+    let anno = repositories::ds_item_anno::try_get_by_id(
+        &state.pg_pool, anno_id
+    )
+        .await
+        .map_err(DatasetItemAnnoError::RepoError)?
+        .filter(|anno| anno.item_id == item_id)
+        .ok_or(DatasetItemAnnoError::NotFound)?;
+
+    Ok(Json(GetDatasetItemAnnoResponse {
+        code: 0,
+        data: Some(DatasetItemAnnoSchema::from(anno)),
+        msg: None,
+    }))
+}
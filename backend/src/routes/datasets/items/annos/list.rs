@@ -0,0 +1,86 @@
+use axum::{extract::{State, Query}, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories::{self, ds_item_anno::DatasetItemAnnosFilter},
+    middlewares::auth::Caller,
+    routes::datasets::access::require_item_role,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::{error::DatasetItemAnnoError, schema::DatasetItemAnnoSchema};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DatasetItemAnnoSearchQuery {
+    /// Annotation type
+    pub typ: Option<String>,
+    /// Annotation name
+    pub name: Option<String>,
+    /// Id of the user who created it
+    pub created_by: Option<i32>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
+    pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListDatasetItemAnnosResponse {
+    code: i32,
+    data: Option<Vec<DatasetItemAnnoSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
+    msg: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/datasets/items/{id}/annos",
+    params(
+        ("id", Path, description = "Dataset item id"),
+        DatasetItemAnnoSearchQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Dataset item annotation query successfully",
+            body = ListDatasetItemAnnosResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset item not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_dataset_item_annos(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(item_id): PathExtractor<i32>,
+    Query(mut params): Query<DatasetItemAnnosFilter>,
+) -> Result<Json<ListDatasetItemAnnosResponse>, DatasetItemAnnoError> {
+    require_item_role(&state, &caller, item_id, DatasetRole::Viewer).await?;
+
+    params.item_id = Some(item_id);
+
+    let annos = repositories::ds_item_anno::get_all(
+        &state.pg_pool, params
+    )
+        .await
+        .map_err(DatasetItemAnnoError::RepoError)?;
+
+    let annos = annos.map(DatasetItemAnnoSchema::from);
+
+    Ok(Json(ListDatasetItemAnnosResponse {
+        code: 0,
+        data: Some(annos.items),
+        next_cursor: annos.next_cursor,
+        total: annos.total,
+        msg: None,
+    }))
+}
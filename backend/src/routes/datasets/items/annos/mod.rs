@@ -0,0 +1,41 @@
+use axum::{routing::{get, post, put, delete}, Router};
+
+use crate::{middlewares::auth::AuthLayer, server::AppState};
+
+pub mod create;
+pub mod delete;
+pub mod error;
+pub mod get;
+pub mod list;
+pub mod schema;
+pub mod update;
+
+pub fn ds_item_annos_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            post(create::create_dataset_item_anno)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.items.annos.create".to_string()))),
+        )
+        .route(
+            "/",
+            get(list::list_dataset_item_annos)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.items.annos.read".to_string()))),
+        )
+        .route(
+            "/:anno_id",
+            get(get::get_dataset_item_anno)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.items.annos.read".to_string()))),
+        )
+        .route(
+            "/:anno_id",
+            put(update::update_dataset_item_anno)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.items.annos.update".to_string()))),
+        )
+        .route(
+            "/:anno_id",
+            delete(delete::delete_dataset_item_anno)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.items.annos.delete".to_string()))),
+        )
+        .with_state(state)
+}
@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::models::ds_item_anno::DatasetItemAnnoModel;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetItemAnnoSchema {
+    pub id: i32,
+    pub item_id: i32,
+    pub name: String,
+    pub typ: String,
+    pub uri: Option<String>,
+    pub number: Option<f64>,
+    pub text: Option<String>,
+    #[schema(value_type = String)]
+    created_at: NaiveDateTime,
+    #[schema(value_type = String)]
+    updated_at: NaiveDateTime,
+    /// Id of the user who created it, null when unknown
+    pub created_by: Option<i32>,
+    /// Id of the user who last changed it, null when unknown
+    pub updated_by: Option<i32>,
+}
+
+impl From<DatasetItemAnnoModel> for DatasetItemAnnoSchema {
+    fn from(anno: DatasetItemAnnoModel) -> Self {
+        Self {
+            id: anno.id,
+            item_id: anno.item_id,
+            name: anno.name,
+            typ: anno.typ,
+            uri: anno.uri,
+            number: anno.number,
+            text: anno.text,
+            created_at: anno.created_at,
+            updated_at: anno.updated_at,
+            created_by: anno.created_by,
+            updated_by: anno.updated_by,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use axum::{extract::State, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories::{self, ds_item_anno::UpdatedDatasetItemAnnoDB},
+    middlewares::auth::Caller,
+    routes::datasets::access::require_item_role,
+    server::AppState,
+    utils::{
+        extractors::{json::JsonExtractor, path::PathExtractor},
+        nullable,
+    },
+};
+use super::{error::DatasetItemAnnoError, schema::DatasetItemAnnoSchema};
+
+/// Partial update: fields left out are left alone, `uri`, `number` and
+/// `text` set to `null` are cleared.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DatasetItemAnnoUpdateRequest {
+    pub name: Option<String>,
+    pub typ: Option<String>,
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>, nullable)]
+    pub uri: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<f64>, nullable)]
+    pub number: Option<Option<f64>>,
+    #[serde(default, deserialize_with = "nullable::deserialize")]
+    #[schema(value_type = Option<String>, nullable)]
+    pub text: Option<Option<String>>,
+}
+
+impl DatasetItemAnnoUpdateRequest {
+    fn into_updated_anno(self, user_id: i32) -> UpdatedDatasetItemAnnoDB {
+        UpdatedDatasetItemAnnoDB {
+            name: self.name,
+            typ: self.typ,
+            uri: self.uri,
+            number: self.number,
+            text: self.text,
+            updated_by: user_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetItemAnnoUpdateResponse {
+    pub code: i32,
+    pub data: Option<DatasetItemAnnoSchema>,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/datasets/items/{id}/annos/{anno_id}",
+    params(
+        ("id", Path, description = "Dataset item id"),
+        ("anno_id", Path, description = "Dataset item annotation id"),
+    ),
+    request_body = DatasetItemAnnoUpdateRequest,
+    responses(
+        (
+            status = 200,
+            description = "Dataset item annotation update successfully",
+            body = DatasetItemAnnoUpdateResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset item annotation not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn update_dataset_item_anno(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor((item_id, anno_id)): PathExtractor<(i32, i32)>,
+    JsonExtractor(updated_anno): JsonExtractor<DatasetItemAnnoUpdateRequest>,
+) -> Result<Json<DatasetItemAnnoUpdateResponse>, DatasetItemAnnoError> {
+    require_item_role(&state, &caller, item_id, DatasetRole::Editor).await?;
+
+    repositories::ds_item_anno::try_get_by_id(
+        &state.pg_pool, anno_id
+    )
+        .await
+        .map_err(DatasetItemAnnoError::RepoError)?
+        .filter(|anno| anno.item_id == item_id)
+        .ok_or(DatasetItemAnnoError::NotFound)?;
+
+    let anno = repositories::ds_item_anno::update_by_id(
+        &state.pg_pool, anno_id, updated_anno.into_updated_anno(caller.user.id)
+    )
+        .await
+        .map_err(DatasetItemAnnoError::RepoError)?;
+
+    Ok(Json(DatasetItemAnnoUpdateResponse {
+        code: 0,
+        data: Some(DatasetItemAnnoSchema::from(anno)),
+        msg: None,
+    }))
+}
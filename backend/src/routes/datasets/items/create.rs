@@ -1,10 +1,13 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories::{self, ds_item::NewDatasetItemDB},
+    middlewares::auth::Caller,
+    routes::datasets::access::{AccessError, require_dataset_role},
     server::AppState,
     utils::extractors::json::JsonExtractor,
 };
@@ -14,13 +17,17 @@ use super::{error::DatasetItemError, schema::DatasetItemSchema};
 pub struct DatasetItemCreationRequest {
     pub typ: String,
     pub uri: String,
+    /// Dataset to link the new item to
+    pub ds_id: Option<i32>,
 }
 
-impl Into<NewDatasetItemDB> for DatasetItemCreationRequest {
-    fn into(self) -> NewDatasetItemDB {
+impl DatasetItemCreationRequest {
+    fn into_new_item(self, user_id: i32) -> NewDatasetItemDB {
         NewDatasetItemDB {
             typ: self.typ,
             uri: self.uri,
+            created_by: user_id,
+            updated_by: user_id,
         }
     }
 }
@@ -42,11 +49,13 @@ pub struct DatasetItemCreationResponse {
             description = "Dataset item created successfully",
             body = DatasetItemCreationResponse,
         ),
+        (status = NOT_FOUND, description = "Dataset not found"),
     )
 )]
 #[instrument(skip(state))]
 pub async fn create_dataset_item(
     State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
     JsonExtractor(new_item): JsonExtractor<DatasetItemCreationRequest>,
 ) -> Result<Json<DatasetItemCreationResponse>, DatasetItemError> {
     let item_in_db = repositories::ds_item::try_get_by_uri(
@@ -59,11 +68,34 @@ pub async fn create_dataset_item(
         return Err(DatasetItemError::Duplicate);
     }
 
-    let created_item = repositories::ds_item::create(
-        &state.pg_pool, new_item.into()
-    )
-        .await
-        .map_err(DatasetItemError::RepoError)?;
+    let created_item = match new_item.ds_id {
+        Some(ds_id) => {
+            require_dataset_role(&state, &caller, ds_id, DatasetRole::Editor)
+                .await
+                .map_err(|err| match err {
+                    AccessError::Hidden => DatasetItemError::DatasetNotFound,
+                    err => err.into(),
+                })?;
+
+            repositories::dataset::try_get_by_id(
+                &state.pg_pool, ds_id
+            )
+                .await
+                .map_err(DatasetItemError::RepoError)?
+                .ok_or(DatasetItemError::DatasetNotFound)?;
+
+            repositories::ds_item::create_in_dataset(
+                &state.pg_pool, new_item.into_new_item(caller.user.id), ds_id
+            )
+                .await
+                .map_err(DatasetItemError::RepoError)?
+        }
+        None => repositories::ds_item::create(
+            &state.pg_pool, new_item.into_new_item(caller.user.id)
+        )
+            .await
+            .map_err(DatasetItemError::RepoError)?,
+    };
 
     Ok(Json(DatasetItemCreationResponse {
         code: 0,
@@ -1,12 +1,19 @@
 use axum::{response::IntoResponse, http::StatusCode, Json};
 use serde_json::json;
 
-use crate::infra::repositories::error::RepoError;
+use crate::{
+    infra::repositories::error::RepoError,
+    routes::datasets::access::AccessError,
+};
 
 #[derive(Debug)]
 pub enum DatasetItemError {
     NotFound,
     Duplicate,
+    DatasetNotFound,
+    VersionNotFound,
+    AccessDenied,
+    InvalidCursor,
     RepoError(RepoError),
 }
 
@@ -23,6 +30,26 @@ impl IntoResponse for DatasetItemError {
                 40002,
                 format!("Dataset item already exists."),
             ),
+            Self::DatasetNotFound => (
+                StatusCode::NOT_FOUND,
+                40004,
+                format!("Dataset not found."),
+            ),
+            Self::VersionNotFound => (
+                StatusCode::NOT_FOUND,
+                40005,
+                format!("Dataset version not found."),
+            ),
+            Self::AccessDenied => (
+                StatusCode::FORBIDDEN,
+                40006,
+                format!("Dataset role too weak for this operation."),
+            ),
+            Self::InvalidCursor => (
+                StatusCode::BAD_REQUEST,
+                40007,
+                format!("Cursor does not match the requested order."),
+            ),
             Self::RepoError(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 40003,
@@ -36,3 +63,13 @@ impl IntoResponse for DatasetItemError {
             .into_response()
     }
 }
+
+impl From<AccessError> for DatasetItemError {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::Hidden => Self::NotFound,
+            AccessError::Denied => Self::AccessDenied,
+            AccessError::RepoError(err) => Self::RepoError(err),
+        }
+    }
+}
@@ -1,10 +1,13 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories,
+    middlewares::auth::Caller,
+    routes::datasets::access::require_item_role,
     server::AppState,
     utils::extractors::path::PathExtractor,
 };
@@ -35,8 +38,11 @@ pub struct GetDatasetItemResponse {
 #[instrument(skip(state))]
 pub async fn get_dataset_item(
     State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
     PathExtractor(item_id): PathExtractor<i32>,
 ) -> Result<Json<GetDatasetItemResponse>, DatasetItemError> {
+    require_item_role(&state, &caller, item_id, DatasetRole::Viewer).await?;
+
     let item = repositories::ds_item::get_by_id(
         &state.pg_pool, item_id
     )
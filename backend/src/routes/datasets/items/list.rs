@@ -1,10 +1,13 @@
-use axum::{extract::{State, Query}, Json};
+use axum::{extract::{State, Query}, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::{ToSchema, IntoParams};
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories::{self, ds_item::DatasetItemsFilter},
+    middlewares::auth::Caller,
+    routes::datasets::access::{AccessError, require_dataset_role, visible_to},
     server::AppState,
 };
 use super::{error::DatasetItemError, schema::DatasetItemSchema};
@@ -14,16 +17,51 @@ use super::{error::DatasetItemError, schema::DatasetItemSchema};
 pub struct DatasetItemSearchQuery {
     /// Dataset ID
     pub ds_id: Option<i32>,
-    /// Skip, default: 0
-    pub skip: Option<i64>,
-    /// Limit, default: 20
+    /// Dataset version id, lists its frozen members instead of the current ones
+    pub version: Option<i32>,
+    /// Item type
+    pub typ: Option<String>,
+    /// Id of the user who created it
+    pub created_by: Option<i32>,
+    /// Only items whose uri starts with this prefix
+    pub uri_prefix: Option<String>,
+    /// Created at or after, e.g. 2024-01-31T00:00:00
+    pub created_after: Option<String>,
+    /// Created strictly before
+    pub created_before: Option<String>,
+    /// Updated at or after
+    pub updated_after: Option<String>,
+    /// Updated strictly before
+    pub updated_before: Option<String>,
+    /// Only items having an annotation with this name. The other `anno_*`
+    /// predicates must hold for that same annotation.
+    pub anno_name: Option<String>,
+    /// Annotation number greater than or equal to
+    pub anno_number_min: Option<f64>,
+    /// Annotation number less than or equal to
+    pub anno_number_max: Option<f64>,
+    /// Annotation text equal to
+    pub anno_text: Option<String>,
+    /// One of id, typ, uri, created_at, updated_at, default: id
+    pub order_by: Option<String>,
+    /// asc or desc, default: asc
+    pub order: Option<String>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
     pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListDatasetItemsResponse {
     code: i32,
     data: Option<Vec<DatasetItemSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
     msg: Option<String>,
 }
 
@@ -37,28 +75,64 @@ pub struct ListDatasetItemsResponse {
             description = "Dataset item query successfully",
             body = ListDatasetItemsResponse,
         ),
+        (status = BAD_REQUEST, description = "Cursor issued for another order"),
         (status = NOT_FOUND, description = "Dataset item not found"),
     )
 )]
 #[instrument(skip(state))]
 pub async fn list_dataset_items(
     State(state): State<AppState>,
-    Query(params): Query<DatasetItemsFilter>,
+    Extension(caller): Extension<Caller>,
+    Query(mut params): Query<DatasetItemsFilter>,
 ) -> Result<Json<ListDatasetItemsResponse>, DatasetItemError> {
+    if !params.cursor_matches_order() {
+        return Err(DatasetItemError::InvalidCursor);
+    }
+
+    if let Some(version) = params.version {
+        let version = repositories::ds_version::try_get_by_id(
+            &state.pg_pool, version
+        )
+            .await
+            .map_err(DatasetItemError::RepoError)?
+            .ok_or(DatasetItemError::VersionNotFound)?;
+
+        if params.ds_id.is_some_and(|ds_id| ds_id != version.ds_id) {
+            return Err(DatasetItemError::VersionNotFound);
+        }
+
+        require_dataset_role(&state, &caller, version.ds_id, DatasetRole::Viewer)
+            .await
+            .map_err(|err| match err {
+                AccessError::Hidden => DatasetItemError::VersionNotFound,
+                err => err.into(),
+            })?;
+    } else if let Some(ds_id) = params.ds_id {
+        require_dataset_role(&state, &caller, ds_id, DatasetRole::Viewer)
+            .await
+            .map_err(|err| match err {
+                AccessError::Hidden => DatasetItemError::DatasetNotFound,
+                err => err.into(),
+            })?;
+    } else {
+        params.visible_to = visible_to(&state, &caller)
+            .await
+            .map_err(DatasetItemError::RepoError)?;
+    }
+
     let items = repositories::ds_item::get_all(
         &state.pg_pool, params
     )
         .await
         .map_err(DatasetItemError::RepoError)?;
 
-    let items = items
-        .into_iter()
-        .map(DatasetItemSchema::from)
-        .collect();
+    let items = items.map(DatasetItemSchema::from);
 
     Ok(Json(ListDatasetItemsResponse {
         code: 0,
-        data: Some(items),
+        data: Some(items.items),
+        next_cursor: items.next_cursor,
+        total: items.total,
         msg: None,
     }))
 }
@@ -2,6 +2,7 @@ use axum::{routing::{get, post, put, delete}, Router};
 
 use crate::{middlewares::auth::AuthLayer, server::AppState};
 
+pub mod annos;
 pub mod create;
 pub mod delete;
 pub mod error;
@@ -36,5 +37,6 @@ pub fn ds_items_routes(state: AppState) -> Router<AppState> {
             "/:id", delete(delete::delete_dataset_item)
                 .layer(AuthLayer::new(state.clone(), Some("datasets.items.delete".to_string()))),
         )
+        .nest("/:id/annos", annos::ds_item_annos_routes(state.clone()))
         .with_state(state)
 }
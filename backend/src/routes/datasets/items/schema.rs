@@ -13,6 +13,10 @@ pub struct DatasetItemSchema {
     created_at: NaiveDateTime,
     #[schema(value_type = String)]
     updated_at: NaiveDateTime,
+    /// Id of the user who created it, null when unknown
+    pub created_by: Option<i32>,
+    /// Id of the user who last changed it, null when unknown
+    pub updated_by: Option<i32>,
 }
 
 impl From<DatasetItemModel> for DatasetItemSchema {
@@ -23,6 +27,8 @@ impl From<DatasetItemModel> for DatasetItemSchema {
             uri: item.uri,
             created_at: item.created_at,
             updated_at: item.updated_at,
+            created_by: item.created_by,
+            updated_by: item.updated_by,
         }
     }
 }
@@ -1,10 +1,13 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories::{self, ds_item::UpdatedDatasetItemDB},
+    middlewares::auth::Caller,
+    routes::datasets::access::require_item_role,
     server::AppState,
     utils::extractors::{
         json::JsonExtractor,
@@ -19,11 +22,12 @@ pub struct DatasetItemUpdateRequest {
     pub uri: Option<String>,
 }
 
-impl Into<UpdatedDatasetItemDB> for DatasetItemUpdateRequest {
-    fn into(self) -> UpdatedDatasetItemDB {
+impl DatasetItemUpdateRequest {
+    fn into_updated_item(self, user_id: i32) -> UpdatedDatasetItemDB {
         UpdatedDatasetItemDB {
             typ: self.typ,
             uri: self.uri,
+            updated_by: user_id,
         }
     }
 }
@@ -50,9 +54,12 @@ pub struct DatasetItemUpdateResponse {
 #[instrument(skip(state))]
 pub async fn update_dataset_item(
     State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
     PathExtractor(item_id): PathExtractor<i32>,
     JsonExtractor(updated_item): JsonExtractor<DatasetItemUpdateRequest>,
 ) -> Result<Json<DatasetItemUpdateResponse>, DatasetItemError> {
+    require_item_role(&state, &caller, item_id, DatasetRole::Editor).await?;
+
     repositories::ds_item::try_get_by_id(
         &state.pg_pool, item_id
     )
@@ -61,7 +68,7 @@ pub async fn update_dataset_item(
         .ok_or(DatasetItemError::NotFound)?;
 
     let dataset = repositories::ds_item::update_by_id(
-        &state.pg_pool, item_id, updated_item.into()
+        &state.pg_pool, item_id, updated_item.into_updated_item(caller.user.id)
     )
         .await
         .map_err(DatasetItemError::RepoError)?;
@@ -1,27 +1,36 @@
-use axum::{extract::{State, Query}, Json};
+use axum::{extract::{State, Query}, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::{ToSchema, IntoParams};
 
 use crate::{
     infra::repositories::{self, dataset::DatasetsFilter},
+    middlewares::auth::Caller,
     server::AppState,
 };
-use super::{error::DatasetError, schema::DatasetSchema};
+use super::{access::visible_to, error::DatasetError, schema::DatasetSchema};
 
 #[derive(Deserialize, IntoParams)]
 #[into_params(parameter_in = Query)]
 pub struct DatasetSearchQuery {
-    /// Skip, default: 0
-    pub skip: Option<i64>,
-    /// Limit, default: 20
+    /// Id of the user who created it
+    pub created_by: Option<i32>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
     pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListDatasetsResponse {
     code: i32,
     data: Option<Vec<DatasetSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
     msg: Option<String>,
 }
 
@@ -37,22 +46,26 @@ pub struct ListDatasetsResponse {
 #[instrument(skip(state))]
 pub async fn list_datasets(
     State(state): State<AppState>,
-    Query(params): Query<DatasetsFilter>,
+    Extension(caller): Extension<Caller>,
+    Query(mut params): Query<DatasetsFilter>,
 ) -> Result<Json<ListDatasetsResponse>, DatasetError> {
+    params.visible_to = visible_to(&state, &caller)
+        .await
+        .map_err(DatasetError::RepoError)?;
+
     let datasets = repositories::dataset::get_all(
         &state.pg_pool, params
     )
         .await
         .map_err(DatasetError::RepoError)?;
 
-    let datasets = datasets
-        .into_iter()
-        .map(DatasetSchema::from)
-        .collect();
+    let datasets = datasets.map(DatasetSchema::from);
 
     Ok(Json(ListDatasetsResponse {
         code: 0,
-        data: Some(datasets),
+        data: Some(datasets.items),
+        next_cursor: datasets.next_cursor,
+        total: datasets.total,
         msg: None,
     }))
 }
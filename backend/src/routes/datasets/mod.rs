@@ -2,15 +2,23 @@ use axum::{routing::{get, post, put, delete}, Router};
 
 use crate::{middlewares::auth::AuthLayer, server::AppState};
 
+pub mod access;
+pub mod acl;
 pub mod create;
 pub mod delete;
+pub mod diff;
 pub mod error;
+pub mod export;
 pub mod get;
+pub mod import;
+pub mod item_rel;
 pub mod items;
 pub mod list;
 pub mod schema;
+pub mod shard_rel;
 pub mod shards;
 pub mod update;
+pub mod version;
 
 pub fn datasets_routes(state: AppState) -> Router<AppState> {
     Router::new()
@@ -41,5 +49,74 @@ pub fn datasets_routes(state: AppState) -> Router<AppState> {
             delete(delete::delete_dataset)
                 .layer(AuthLayer::new(state.clone(), Some("datasets.delete".to_string()))),
         )
+        .route(
+            "/:id/acls",
+            post(acl::grant_dataset_acl)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.update".to_string()))),
+        )
+        .route(
+            "/:id/acls",
+            get(acl::list_dataset_acls)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.read".to_string()))),
+        )
+        .route(
+            "/:id/acls/:acl_id",
+            delete(acl::revoke_dataset_acl)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.update".to_string()))),
+        )
+        .route(
+            "/:id/diff",
+            get(diff::diff_dataset)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.read".to_string()))),
+        )
+        .route(
+            "/:id/export",
+            get(export::export_dataset)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.read".to_string()))),
+        )
+        .route(
+            "/:id/items",
+            post(item_rel::attach_dataset_items)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.update".to_string()))),
+        )
+        .route(
+            "/:id/items",
+            delete(item_rel::detach_dataset_items)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.update".to_string()))),
+        )
+        // matchit can't express a literal `:` after a static prefix, so the
+        // `:import` verb is captured as a parameter and checked ahead of the
+        // permission.
+        .route(
+            "/:id/items:action",
+            post(import::import_dataset_items)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.items.import".to_string())))
+                .layer(axum::middleware::from_fn(import::require_import_action)),
+        )
+        .route(
+            "/:id/shards",
+            post(shard_rel::attach_dataset_shards)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.update".to_string()))),
+        )
+        .route(
+            "/:id/shards",
+            delete(shard_rel::detach_dataset_shards)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.update".to_string()))),
+        )
+        .route(
+            "/:id/versions",
+            post(version::create_dataset_version)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.update".to_string()))),
+        )
+        .route(
+            "/:id/versions",
+            get(version::list_dataset_versions)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.read".to_string()))),
+        )
+        .route(
+            "/:id/versions/:version_id",
+            get(version::get_dataset_version)
+                .layer(AuthLayer::new(state.clone(), Some("datasets.read".to_string()))),
+        )
         .with_state(state)
 }
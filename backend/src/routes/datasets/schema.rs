@@ -2,7 +2,18 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::domain::models::dataset::DatasetModel;
+use crate::domain::models::{
+    dataset::DatasetModel,
+    ds_acl::{DatasetAclModel, DatasetRole},
+    ds_item_anno::DatasetItemAnnoModel,
+    ds_version::{
+        DatasetDiffChange,
+        DatasetDiffKind,
+        DatasetDiffModel,
+        DatasetItemAnnoDiffModel,
+        DatasetVersionModel,
+    },
+};
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DatasetSchema {
@@ -13,6 +24,10 @@ pub struct DatasetSchema {
     created_at: NaiveDateTime,
     #[schema(value_type = String)]
     updated_at: NaiveDateTime,
+    /// Id of the user who created it, null when unknown
+    pub created_by: Option<i32>,
+    /// Id of the user who last changed it, null when unknown
+    pub updated_by: Option<i32>,
 }
 
 impl From<DatasetModel> for DatasetSchema {
@@ -23,6 +38,184 @@ impl From<DatasetModel> for DatasetSchema {
             description: dataset.description,
             created_at: dataset.created_at,
             updated_at: dataset.updated_at,
+            created_by: dataset.created_by,
+            updated_by: dataset.updated_by,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetVersionSchema {
+    pub id: i32,
+    pub ds_id: i32,
+    pub tag: String,
+    pub num_items: i32,
+    pub num_shards: i32,
+    pub num_annos: i32,
+    #[schema(value_type = String)]
+    created_at: NaiveDateTime,
+}
+
+impl From<DatasetVersionModel> for DatasetVersionSchema {
+    fn from(version: DatasetVersionModel) -> Self {
+        Self {
+            id: version.id,
+            ds_id: version.ds_id,
+            tag: version.tag,
+            num_items: version.num_items,
+            num_shards: version.num_shards,
+            num_annos: version.num_annos,
+            created_at: version.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DatasetDiffKindSchema {
+    Item,
+    Shard,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DatasetDiffChangeSchema {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetItemAnnoValueSchema {
+    pub name: String,
+    pub typ: String,
+    pub uri: Option<String>,
+    pub number: Option<f64>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetItemAnnoDiffSchema {
+    pub anno_id: i32,
+    pub change: DatasetDiffChangeSchema,
+    pub from: Option<DatasetItemAnnoValueSchema>,
+    pub to: Option<DatasetItemAnnoValueSchema>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetDiffSchema {
+    pub kind: DatasetDiffKindSchema,
+    pub id: i32,
+    pub change: DatasetDiffChangeSchema,
+    /// Annotation changes of a modified item
+    pub annotations: Vec<DatasetItemAnnoDiffSchema>,
+}
+
+impl From<DatasetDiffKind> for DatasetDiffKindSchema {
+    fn from(kind: DatasetDiffKind) -> Self {
+        match kind {
+            DatasetDiffKind::Item => Self::Item,
+            DatasetDiffKind::Shard => Self::Shard,
+        }
+    }
+}
+
+impl From<DatasetDiffChange> for DatasetDiffChangeSchema {
+    fn from(change: DatasetDiffChange) -> Self {
+        match change {
+            DatasetDiffChange::Added => Self::Added,
+            DatasetDiffChange::Removed => Self::Removed,
+            DatasetDiffChange::Modified => Self::Modified,
+        }
+    }
+}
+
+impl From<DatasetItemAnnoModel> for DatasetItemAnnoValueSchema {
+    fn from(anno: DatasetItemAnnoModel) -> Self {
+        Self {
+            name: anno.name,
+            typ: anno.typ,
+            uri: anno.uri,
+            number: anno.number,
+            text: anno.text,
+        }
+    }
+}
+
+impl From<DatasetItemAnnoDiffModel> for DatasetItemAnnoDiffSchema {
+    fn from(diff: DatasetItemAnnoDiffModel) -> Self {
+        Self {
+            anno_id: diff.anno_id,
+            change: diff.change.into(),
+            from: diff.from.map(Into::into),
+            to: diff.to.map(Into::into),
+        }
+    }
+}
+
+impl From<DatasetDiffModel> for DatasetDiffSchema {
+    fn from(diff: DatasetDiffModel) -> Self {
+        Self {
+            kind: diff.kind.into(),
+            id: diff.id,
+            change: diff.change.into(),
+            annotations: diff.annos.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DatasetRoleSchema {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+/// A role on the dataset held by exactly one of a user or a group
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetAclSchema {
+    pub id: i32,
+    pub ds_id: i32,
+    pub user_id: Option<i32>,
+    pub group_id: Option<i32>,
+    pub role: DatasetRoleSchema,
+    #[schema(value_type = String)]
+    created_at: NaiveDateTime,
+    #[schema(value_type = String)]
+    updated_at: NaiveDateTime,
+}
+
+impl From<DatasetRole> for DatasetRoleSchema {
+    fn from(role: DatasetRole) -> Self {
+        match role {
+            DatasetRole::Viewer => Self::Viewer,
+            DatasetRole::Editor => Self::Editor,
+            DatasetRole::Owner => Self::Owner,
+        }
+    }
+}
+
+impl From<DatasetRoleSchema> for DatasetRole {
+    fn from(role: DatasetRoleSchema) -> Self {
+        match role {
+            DatasetRoleSchema::Viewer => Self::Viewer,
+            DatasetRoleSchema::Editor => Self::Editor,
+            DatasetRoleSchema::Owner => Self::Owner,
+        }
+    }
+}
+
+impl From<DatasetAclModel> for DatasetAclSchema {
+    fn from(acl: DatasetAclModel) -> Self {
+        Self {
+            id: acl.id,
+            ds_id: acl.ds_id,
+            user_id: acl.user_id,
+            group_id: acl.group_id,
+            role: DatasetRoleSchema::from(acl.role),
+            created_at: acl.created_at,
+            updated_at: acl.updated_at,
         }
     }
 }
@@ -0,0 +1,144 @@
+use axum::{extract::State, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories,
+    middlewares::auth::Caller,
+    server::AppState,
+    utils::extractors::{json::JsonExtractor, path::PathExtractor},
+};
+use super::{access::{require_dataset_role, visible_to}, error::DatasetError};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DatasetShardsRelRequest {
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetShardsRelResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/datasets/{id}/shards",
+    params(
+        ("id", Path, description = "Dataset id")
+    ),
+    request_body = DatasetShardsRelRequest,
+    responses(
+        (
+            status = 200,
+            description = "Dataset shards attached successfully",
+            body = DatasetShardsRelResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset or dataset shard not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn attach_dataset_shards(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    JsonExtractor(DatasetShardsRelRequest { mut ids }): JsonExtractor<DatasetShardsRelRequest>,
+) -> Result<Json<DatasetShardsRelResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Editor).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    ids.sort_unstable();
+    ids.dedup();
+
+    let shards = repositories::ds_shard::get_by_ids(
+        &state.pg_pool, ids.clone()
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    if shards.len() != ids.len() {
+        return Err(DatasetError::ShardNotFound);
+    }
+
+    // Linking hidden shards would let the caller read them through this dataset.
+    if let Some(user_id) = visible_to(&state, &caller)
+        .await
+        .map_err(DatasetError::RepoError)?
+    {
+        let visible = repositories::ds_acl::count_visible_shards(
+            &state.pg_pool, user_id, ids.clone()
+        )
+            .await
+            .map_err(DatasetError::RepoError)?;
+
+        if visible != ids.len() as i64 {
+            return Err(DatasetError::ShardNotFound);
+        }
+    }
+
+    repositories::dataset_shard_rel::create_many(
+        &state.pg_pool, ds_id, ids
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    Ok(Json(DatasetShardsRelResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/datasets/{id}/shards",
+    params(
+        ("id", Path, description = "Dataset id")
+    ),
+    request_body = DatasetShardsRelRequest,
+    responses(
+        (
+            status = 200,
+            description = "Dataset shards detached successfully",
+            body = DatasetShardsRelResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn detach_dataset_shards(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    JsonExtractor(DatasetShardsRelRequest { ids }): JsonExtractor<DatasetShardsRelRequest>,
+) -> Result<Json<DatasetShardsRelResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Editor).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    repositories::dataset_shard_rel::delete_by_ids(
+        &state.pg_pool, ds_id, ids
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    Ok(Json(DatasetShardsRelResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
@@ -1,9 +1,10 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::user::UserModel,
     infra::repositories::{self, ds_shard::NewDatasetShardDB},
     server::AppState,
     utils::extractors::json::JsonExtractor,
@@ -15,10 +16,12 @@ pub struct DatasetShardCreationRequest {
     pub uri: String,
 }
 
-impl Into<NewDatasetShardDB> for DatasetShardCreationRequest {
-    fn into(self) -> NewDatasetShardDB {
+impl DatasetShardCreationRequest {
+    fn into_new_shard(self, user_id: i32) -> NewDatasetShardDB {
         NewDatasetShardDB {
             uri: self.uri,
+            created_by: user_id,
+            updated_by: user_id,
         }
     }
 }
@@ -45,6 +48,7 @@ pub struct DatasetShardCreationResponse {
 #[instrument(skip(state))]
 pub async fn create_dataset_shard(
     State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
     JsonExtractor(new_shard): JsonExtractor<DatasetShardCreationRequest>,
 ) -> Result<Json<DatasetShardCreationResponse>, DatasetShardError> {
     let shard_in_db = repositories::ds_shard::try_get_by_uri(
@@ -58,7 +62,7 @@ pub async fn create_dataset_shard(
     }
 
     let created_shard = repositories::ds_shard::create(
-        &state.pg_pool, new_shard.into()
+        &state.pg_pool, new_shard.into_new_shard(user.id)
     )
         .await
         .map_err(DatasetShardError::RepoError)?;
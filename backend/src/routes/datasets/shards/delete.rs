@@ -1,10 +1,13 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories,
+    middlewares::auth::Caller,
+    routes::datasets::access::require_shard_role,
     server::AppState,
     utils::extractors::path::PathExtractor,
 };
@@ -35,8 +38,11 @@ pub struct DeleteDatasetShardResponse {
 #[instrument(skip(state))]
 pub async fn delete_dataset_shard(
     State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
     PathExtractor(shard_id): PathExtractor<i32>,
 ) -> Result<Json<DeleteDatasetShardResponse>, DatasetShardError> {
+    require_shard_role(&state, &caller, shard_id, DatasetRole::Editor).await?;
+
     repositories::ds_shard::delete_by_id(&state.pg_pool, shard_id)
         .await
         .map_err(DatasetShardError::RepoError)?;
@@ -1,12 +1,18 @@
 use axum::{response::IntoResponse, http::StatusCode, Json};
 use serde_json::json;
 
-use crate::infra::repositories::error::RepoError;
+use crate::{
+    infra::repositories::error::RepoError,
+    routes::datasets::access::AccessError,
+};
 
 #[derive(Debug)]
 pub enum DatasetShardError {
     NotFound,
     Duplicate,
+    VersionNotFound,
+    AccessDenied,
+    DatasetNotFound,
     RepoError(RepoError),
 }
 
@@ -23,6 +29,21 @@ impl IntoResponse for DatasetShardError {
                 40002,
                 format!("Dataset shard already exists."),
             ),
+            Self::VersionNotFound => (
+                StatusCode::NOT_FOUND,
+                40004,
+                format!("Dataset version not found."),
+            ),
+            Self::AccessDenied => (
+                StatusCode::FORBIDDEN,
+                40005,
+                format!("Dataset role too weak for this operation."),
+            ),
+            Self::DatasetNotFound => (
+                StatusCode::NOT_FOUND,
+                40006,
+                format!("Dataset not found."),
+            ),
             Self::RepoError(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 40003,
@@ -36,3 +57,13 @@ impl IntoResponse for DatasetShardError {
             .into_response()
     }
 }
+
+impl From<AccessError> for DatasetShardError {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::Hidden => Self::NotFound,
+            AccessError::Denied => Self::AccessDenied,
+            AccessError::RepoError(err) => Self::RepoError(err),
+        }
+    }
+}
@@ -1,10 +1,13 @@
-use axum::{extract::{State, Query}, Json};
+use axum::{extract::{State, Query}, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::{ToSchema, IntoParams};
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories::{self, ds_shard::DatasetShardsFilter},
+    middlewares::auth::Caller,
+    routes::datasets::access::{AccessError, require_dataset_role, visible_to},
     server::AppState,
 };
 use super::{error::DatasetShardError, schema::DatasetShardSchema};
@@ -14,16 +17,26 @@ use super::{error::DatasetShardError, schema::DatasetShardSchema};
 pub struct DatasetShardSearchQuery {
     /// Dataset ID
     pub ds_id: Option<i32>,
-    /// Skip, default: 0
-    pub skip: Option<i64>,
-    /// Limit, default: 20
+    /// Dataset version id, lists its frozen members instead of the current ones
+    pub version: Option<i32>,
+    /// Id of the user who created it
+    pub created_by: Option<i32>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
     pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListDatasetShardsResponse {
     code: i32,
     data: Option<Vec<DatasetShardSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
     msg: Option<String>,
 }
 
@@ -43,22 +56,53 @@ pub struct ListDatasetShardsResponse {
 #[instrument(skip(state))]
 pub async fn list_dataset_shards(
     State(state): State<AppState>,
-    Query(params): Query<DatasetShardsFilter>,
+    Extension(caller): Extension<Caller>,
+    Query(mut params): Query<DatasetShardsFilter>,
 ) -> Result<Json<ListDatasetShardsResponse>, DatasetShardError> {
+    if let Some(version) = params.version {
+        let version = repositories::ds_version::try_get_by_id(
+            &state.pg_pool, version
+        )
+            .await
+            .map_err(DatasetShardError::RepoError)?
+            .ok_or(DatasetShardError::VersionNotFound)?;
+
+        if params.ds_id.is_some_and(|ds_id| ds_id != version.ds_id) {
+            return Err(DatasetShardError::VersionNotFound);
+        }
+
+        require_dataset_role(&state, &caller, version.ds_id, DatasetRole::Viewer)
+            .await
+            .map_err(|err| match err {
+                AccessError::Hidden => DatasetShardError::VersionNotFound,
+                err => err.into(),
+            })?;
+    } else if let Some(ds_id) = params.ds_id {
+        require_dataset_role(&state, &caller, ds_id, DatasetRole::Viewer)
+            .await
+            .map_err(|err| match err {
+                AccessError::Hidden => DatasetShardError::DatasetNotFound,
+                err => err.into(),
+            })?;
+    } else {
+        params.visible_to = visible_to(&state, &caller)
+            .await
+            .map_err(DatasetShardError::RepoError)?;
+    }
+
     let shards = repositories::ds_shard::get_all(
         &state.pg_pool, params
     )
         .await
         .map_err(DatasetShardError::RepoError)?;
 
-    let shards = shards
-        .into_iter()
-        .map(DatasetShardSchema::from)
-        .collect();
+    let shards = shards.map(DatasetShardSchema::from);
 
     Ok(Json(ListDatasetShardsResponse {
         code: 0,
-        data: Some(shards),
+        data: Some(shards.items),
+        next_cursor: shards.next_cursor,
+        total: shards.total,
         msg: None,
     }))
 }
@@ -12,6 +12,10 @@ pub struct DatasetShardSchema {
     created_at: NaiveDateTime,
     #[schema(value_type = String)]
     updated_at: NaiveDateTime,
+    /// Id of the user who created it, null when unknown
+    pub created_by: Option<i32>,
+    /// Id of the user who last changed it, null when unknown
+    pub updated_by: Option<i32>,
 }
 
 impl From<DatasetShardModel> for DatasetShardSchema {
@@ -21,6 +25,8 @@ impl From<DatasetShardModel> for DatasetShardSchema {
             uri: shard.uri,
             created_at: shard.created_at,
             updated_at: shard.updated_at,
+            created_by: shard.created_by,
+            updated_by: shard.updated_by,
         }
     }
 }
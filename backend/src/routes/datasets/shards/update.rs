@@ -1,10 +1,13 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories::{self, ds_shard::UpdatedDatasetShardDB},
+    middlewares::auth::Caller,
+    routes::datasets::access::require_shard_role,
     server::AppState,
     utils::extractors::{
         json::JsonExtractor,
@@ -18,10 +21,11 @@ pub struct DatasetShardUpdateRequest {
     pub uri: Option<String>,
 }
 
-impl Into<UpdatedDatasetShardDB> for DatasetShardUpdateRequest {
-    fn into(self) -> UpdatedDatasetShardDB {
+impl DatasetShardUpdateRequest {
+    fn into_updated_shard(self, user_id: i32) -> UpdatedDatasetShardDB {
         UpdatedDatasetShardDB {
             uri: self.uri,
+            updated_by: user_id,
         }
     }
 }
@@ -48,9 +52,12 @@ pub struct DatasetShardUpdateResponse {
 #[instrument(skip(state))]
 pub async fn update_dataset_shard(
     State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
     PathExtractor(shard_id): PathExtractor<i32>,
     JsonExtractor(updated_shard): JsonExtractor<DatasetShardUpdateRequest>,
 ) -> Result<Json<DatasetShardUpdateResponse>, DatasetShardError> {
+    require_shard_role(&state, &caller, shard_id, DatasetRole::Editor).await?;
+
     repositories::ds_shard::try_get_by_id(
         &state.pg_pool, shard_id
     )
@@ -59,7 +66,7 @@ pub async fn update_dataset_shard(
         .ok_or(DatasetShardError::NotFound)?;
 
     let dataset = repositories::ds_shard::update_by_id(
-        &state.pg_pool, shard_id, updated_shard.into()
+        &state.pg_pool, shard_id, updated_shard.into_updated_shard(caller.user.id)
     )
         .await
         .map_err(DatasetShardError::RepoError)?;
@@ -1,27 +1,30 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::models::ds_acl::DatasetRole,
     infra::repositories::{self, dataset::UpdatedDatasetDB},
+    middlewares::auth::Caller,
     server::AppState,
     utils::extractors::{
         json::JsonExtractor,
         path::PathExtractor,
     },
 };
-use super::{error::DatasetError, schema::DatasetSchema};
+use super::{access::require_dataset_role, error::DatasetError, schema::DatasetSchema};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct DatasetUpdateRequest {
     pub description: Option<String>,
 }
 
-impl Into<UpdatedDatasetDB> for DatasetUpdateRequest {
-    fn into(self) -> UpdatedDatasetDB {
+impl DatasetUpdateRequest {
+    fn into_updated_ds(self, user_id: i32) -> UpdatedDatasetDB {
         UpdatedDatasetDB {
             description: self.description,
+            updated_by: user_id,
         }
     }
 }
@@ -51,9 +54,12 @@ pub struct DatasetUpdateResponse {
 #[instrument(skip(state))]
 pub async fn update_dataset(
     State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
     PathExtractor(ds_id): PathExtractor<i32>,
     JsonExtractor(updated_ds): JsonExtractor<DatasetUpdateRequest>,
 ) -> Result<Json<DatasetUpdateResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Editor).await?;
+
     repositories::dataset::try_get_by_id(
         &state.pg_pool, ds_id
     )
@@ -62,7 +68,7 @@ pub async fn update_dataset(
         .ok_or(DatasetError::NotFound)?;
 
     let dataset = repositories::dataset::update_by_id(
-        &state.pg_pool, ds_id, updated_ds.into()
+        &state.pg_pool, ds_id, updated_ds.into_updated_ds(caller.user.id)
     )
         .await
         .map_err(DatasetError::RepoError)?;
@@ -0,0 +1,201 @@
+use axum::{extract::{State, Query}, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    domain::models::ds_acl::DatasetRole,
+    infra::repositories::{
+        self,
+        ds_version::{DatasetVersionsFilter, NewDatasetVersionDB},
+    },
+    middlewares::auth::Caller,
+    server::AppState,
+    utils::extractors::{json::JsonExtractor, path::PathExtractor},
+};
+use super::{access::require_dataset_role, error::DatasetError, schema::DatasetVersionSchema};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DatasetVersionCreationRequest {
+    pub tag: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DatasetVersionResponse {
+    pub code: i32,
+    pub data: Option<DatasetVersionSchema>,
+    pub msg: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DatasetVersionSearchQuery {
+    /// Version tag
+    pub tag: Option<String>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
+    pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListDatasetVersionsResponse {
+    code: i32,
+    data: Option<Vec<DatasetVersionSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
+    msg: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/datasets/{id}/versions",
+    params(
+        ("id", Path, description = "Dataset id")
+    ),
+    request_body = DatasetVersionCreationRequest,
+    responses(
+        (
+            status = 200,
+            description = "Dataset version created successfully",
+            body = DatasetVersionResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn create_dataset_version(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    JsonExtractor(new_version): JsonExtractor<DatasetVersionCreationRequest>,
+) -> Result<Json<DatasetVersionResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Editor).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    let version_in_db = repositories::ds_version::try_get_by_tag(
+        &state.pg_pool, ds_id, new_version.tag.clone()
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    if version_in_db.is_some() {
+        return Err(DatasetError::DuplicateVersion);
+    }
+
+    let created_version = repositories::ds_version::create(
+        &state.pg_pool,
+        NewDatasetVersionDB {
+            ds_id,
+            tag: new_version.tag,
+        },
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    Ok(Json(DatasetVersionResponse {
+        code: 0,
+        data: Some(DatasetVersionSchema::from(created_version)),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/datasets/{id}/versions",
+    params(
+        ("id", Path, description = "Dataset id"),
+        DatasetVersionSearchQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Dataset version query successfully",
+            body = ListDatasetVersionsResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_dataset_versions(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor(ds_id): PathExtractor<i32>,
+    Query(mut params): Query<DatasetVersionsFilter>,
+) -> Result<Json<ListDatasetVersionsResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Viewer).await?;
+
+    repositories::dataset::try_get_by_id(
+        &state.pg_pool, ds_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .ok_or(DatasetError::NotFound)?;
+
+    params.ds_id = Some(ds_id);
+
+    let versions = repositories::ds_version::get_all(
+        &state.pg_pool, params
+    )
+        .await
+        .map_err(DatasetError::RepoError)?;
+
+    let versions = versions.map(DatasetVersionSchema::from);
+
+    Ok(Json(ListDatasetVersionsResponse {
+        code: 0,
+        data: Some(versions.items),
+        next_cursor: versions.next_cursor,
+        total: versions.total,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/datasets/{id}/versions/{version_id}",
+    params(
+        ("id", Path, description = "Dataset id"),
+        ("version_id", Path, description = "Dataset version id"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Dataset version query successfully",
+            body = DatasetVersionResponse,
+        ),
+        (status = NOT_FOUND, description = "Dataset version not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_dataset_version(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    PathExtractor((ds_id, version_id)): PathExtractor<(i32, i32)>,
+) -> Result<Json<DatasetVersionResponse>, DatasetError> {
+    require_dataset_role(&state, &caller, ds_id, DatasetRole::Viewer).await?;
+
+    let version = repositories::ds_version::try_get_by_id(
+        &state.pg_pool, version_id
+    )
+        .await
+        .map_err(DatasetError::RepoError)?
+        .filter(|version| version.ds_id == ds_id)
+        .ok_or(DatasetError::VersionNotFound)?;
+
+    Ok(Json(DatasetVersionResponse {
+        code: 0,
+        data: Some(DatasetVersionSchema::from(version)),
+        msg: None,
+    }))
+}
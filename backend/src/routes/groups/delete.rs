@@ -42,6 +42,8 @@ pub async fn delete_group(
         .await
         .map_err(GroupError::RepoError)?;
 
+    state.auth_cache.invalidate_all();
+
     Ok(Json(DeleteGroupResponse {
         code: 0,
         data: true,
@@ -67,6 +69,8 @@ pub async fn delete_groups(
         .await
         .map_err(GroupError::RepoError)?;
 
+    state.auth_cache.invalidate_all();
+
     Ok(Json(DeleteGroupResponse {
         code: 0,
         data: true,
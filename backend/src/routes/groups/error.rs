@@ -7,6 +7,8 @@ use crate::infra::repositories::error::RepoError;
 pub enum GroupError {
     NotFound,
     Duplicate,
+    UserNotFound,
+    PermissionNotFound,
     RepoError(RepoError),
 }
 
@@ -16,13 +18,23 @@ impl IntoResponse for GroupError {
             Self::NotFound => (
                 StatusCode::NOT_FOUND,
                 30001,
-                format!("User not found."),
+                format!("Group not found."),
             ),
             Self::Duplicate => (
                 StatusCode::BAD_REQUEST,
                 30002,
                 format!("Group already exists."),
             ),
+            Self::UserNotFound => (
+                StatusCode::NOT_FOUND,
+                30004,
+                format!("User not found."),
+            ),
+            Self::PermissionNotFound => (
+                StatusCode::NOT_FOUND,
+                30005,
+                format!("Permission not found."),
+            ),
             Self::RepoError(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 30003,
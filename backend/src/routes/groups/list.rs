@@ -12,16 +12,22 @@ use super::{error::GroupError, schema::GroupSchema};
 #[derive(Deserialize, IntoParams)]
 #[into_params(parameter_in = Query)]
 pub struct GroupSearchQuery {
-    /// Skip, default: 0
-    pub skip: Option<i64>,
-    /// Limit, default: 20
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
     pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListGroupsResponse {
     code: i32,
     data: Option<Vec<GroupSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
     msg: Option<String>,
 }
 
@@ -45,14 +51,13 @@ pub async fn list_groups(
         .await
         .map_err(GroupError::RepoError)?;
 
-    let groups = groups
-        .into_iter()
-        .map(GroupSchema::from)
-        .collect();
+    let groups = groups.map(GroupSchema::from);
 
     Ok(Json(ListGroupsResponse {
         code: 0,
-        data: Some(groups),
+        data: Some(groups.items),
+        next_cursor: groups.next_cursor,
+        total: groups.total,
         msg: None,
     }))
 }
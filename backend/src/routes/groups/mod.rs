@@ -1,4 +1,4 @@
-use axum::{routing::{get, post, delete}, Router};
+use axum::{routing::{get, post, put, delete}, Router};
 
 use crate::{middlewares::auth::AuthLayer, server::AppState};
 
@@ -7,7 +7,9 @@ pub mod delete;
 pub mod error;
 pub mod get;
 pub mod list;
+pub mod permission;
 pub mod schema;
+pub mod user;
 
 pub fn groups_routes(state: AppState) -> Router<AppState> {
     Router::new()
@@ -36,5 +38,35 @@ pub fn groups_routes(state: AppState) -> Router<AppState> {
             delete(delete::delete_group)
                 .layer(AuthLayer::new(state.clone(), Some("groups.delete".to_string()))),
         )
+        .route(
+            "/:id/users",
+            get(user::get_group_users)
+                .layer(AuthLayer::new(state.clone(), Some("groups.read".to_string()))),
+        )
+        .route(
+            "/:id/users/:user_id",
+            put(user::add_group_user)
+                .layer(AuthLayer::new(state.clone(), Some("groups.update".to_string()))),
+        )
+        .route(
+            "/:id/users/:user_id",
+            delete(user::remove_group_user)
+                .layer(AuthLayer::new(state.clone(), Some("groups.update".to_string()))),
+        )
+        .route(
+            "/:id/permissions",
+            get(permission::get_group_permissions)
+                .layer(AuthLayer::new(state.clone(), Some("groups.read".to_string()))),
+        )
+        .route(
+            "/:id/permissions/:perm_id",
+            put(permission::add_group_permission)
+                .layer(AuthLayer::new(state.clone(), Some("groups.update".to_string()))),
+        )
+        .route(
+            "/:id/permissions/:perm_id",
+            delete(permission::remove_group_permission)
+                .layer(AuthLayer::new(state.clone(), Some("groups.update".to_string()))),
+        )
         .with_state(state)
 }
@@ -0,0 +1,155 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    infra::repositories::{self, group_permission_rel::NewGroupPermDB},
+    routes::permissions::schema::PermissionSchema,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::error::GroupError;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetGroupPermissionsResponse {
+    pub code: i32,
+    pub data: Option<Vec<PermissionSchema>>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GroupPermissionResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/groups/{id}/permissions",
+    params(
+        ("id", Path, description = "Group id")
+    ),
+    responses(
+        (status = 200, description = "Group query successfully", body = GetGroupPermissionsResponse),
+        (status = NOT_FOUND, description = "Group not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_group_permissions(
+    State(state): State<AppState>,
+    PathExtractor(group_id): PathExtractor<i32>,
+) -> Result<Json<GetGroupPermissionsResponse>, GroupError> {
+    repositories::group::try_get_by_id(
+        &state.pg_pool, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?
+        .ok_or(GroupError::NotFound)?;
+
+    let perms = repositories::group::get_permissions(
+        &state.pg_pool, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?;
+
+    Ok(Json(GetGroupPermissionsResponse {
+        code: 0,
+        data: Some(perms.into_iter().map(Into::into).collect()),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/groups/{id}/permissions/{perm_id}",
+    params(
+        ("id", Path, description = "Group id"),
+        ("perm_id", Path, description = "Permission id"),
+    ),
+    responses(
+        (status = 200, description = "Permission granted to group successfully", body = GroupPermissionResponse),
+        (status = NOT_FOUND, description = "Group or permission not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn add_group_permission(
+    State(state): State<AppState>,
+    PathExtractor((group_id, perm_id)): PathExtractor<(i32, i32)>,
+) -> Result<Json<GroupPermissionResponse>, GroupError> {
+    repositories::group::try_get_by_id(
+        &state.pg_pool, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?
+        .ok_or(GroupError::NotFound)?;
+
+    repositories::permission::try_get_by_id(
+        &state.pg_pool, perm_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?
+        .ok_or(GroupError::PermissionNotFound)?;
+
+    let rel_in_db = repositories::group_permission_rel::try_get_by_id(
+        &state.pg_pool, group_id, perm_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?;
+
+    if rel_in_db.is_none() {
+        repositories::group_permission_rel::create(
+            &state.pg_pool, NewGroupPermDB { group_id, permission_id: perm_id }
+        )
+            .await
+            .map_err(GroupError::RepoError)?;
+
+        state.auth_cache.invalidate_all();
+    }
+
+    Ok(Json(GroupPermissionResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/groups/{id}/permissions/{perm_id}",
+    params(
+        ("id", Path, description = "Group id"),
+        ("perm_id", Path, description = "Permission id"),
+    ),
+    responses(
+        (status = 200, description = "Permission revoked from group successfully", body = GroupPermissionResponse),
+        (status = NOT_FOUND, description = "Group not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn remove_group_permission(
+    State(state): State<AppState>,
+    PathExtractor((group_id, perm_id)): PathExtractor<(i32, i32)>,
+) -> Result<Json<GroupPermissionResponse>, GroupError> {
+    repositories::group::try_get_by_id(
+        &state.pg_pool, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?
+        .ok_or(GroupError::NotFound)?;
+
+    repositories::group_permission_rel::delete_by_id(
+        &state.pg_pool, group_id, perm_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?;
+
+    state.auth_cache.invalidate_all();
+
+    Ok(Json(GroupPermissionResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
@@ -0,0 +1,155 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    infra::repositories::{self, user_group_rel::NewUserGroupDB},
+    routes::users::schema::UserSchema,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::error::GroupError;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetGroupUsersResponse {
+    pub code: i32,
+    pub data: Option<Vec<UserSchema>>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GroupUserResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/groups/{id}/users",
+    params(
+        ("id", Path, description = "Group id")
+    ),
+    responses(
+        (status = 200, description = "Group query successfully", body = GetGroupUsersResponse),
+        (status = NOT_FOUND, description = "Group not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_group_users(
+    State(state): State<AppState>,
+    PathExtractor(group_id): PathExtractor<i32>,
+) -> Result<Json<GetGroupUsersResponse>, GroupError> {
+    repositories::group::try_get_by_id(
+        &state.pg_pool, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?
+        .ok_or(GroupError::NotFound)?;
+
+    let users = repositories::group::get_users(
+        &state.pg_pool, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?;
+
+    Ok(Json(GetGroupUsersResponse {
+        code: 0,
+        data: Some(users.into_iter().map(Into::into).collect()),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/groups/{id}/users/{user_id}",
+    params(
+        ("id", Path, description = "Group id"),
+        ("user_id", Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "User added to group successfully", body = GroupUserResponse),
+        (status = NOT_FOUND, description = "Group or user not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn add_group_user(
+    State(state): State<AppState>,
+    PathExtractor((group_id, user_id)): PathExtractor<(i32, i32)>,
+) -> Result<Json<GroupUserResponse>, GroupError> {
+    repositories::group::try_get_by_id(
+        &state.pg_pool, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?
+        .ok_or(GroupError::NotFound)?;
+
+    repositories::user::try_get_by_id(
+        &state.pg_pool, user_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?
+        .ok_or(GroupError::UserNotFound)?;
+
+    let rel_in_db = repositories::user_group_rel::try_get_by_id(
+        &state.pg_pool, user_id, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?;
+
+    if rel_in_db.is_none() {
+        repositories::user_group_rel::create(
+            &state.pg_pool, NewUserGroupDB { user_id, group_id }
+        )
+            .await
+            .map_err(GroupError::RepoError)?;
+
+        state.auth_cache.invalidate_user(user_id);
+    }
+
+    Ok(Json(GroupUserResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/groups/{id}/users/{user_id}",
+    params(
+        ("id", Path, description = "Group id"),
+        ("user_id", Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "User removed from group successfully", body = GroupUserResponse),
+        (status = NOT_FOUND, description = "Group not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn remove_group_user(
+    State(state): State<AppState>,
+    PathExtractor((group_id, user_id)): PathExtractor<(i32, i32)>,
+) -> Result<Json<GroupUserResponse>, GroupError> {
+    repositories::group::try_get_by_id(
+        &state.pg_pool, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?
+        .ok_or(GroupError::NotFound)?;
+
+    repositories::user_group_rel::delete_by_id(
+        &state.pg_pool, user_id, group_id
+    )
+        .await
+        .map_err(GroupError::RepoError)?;
+
+    state.auth_cache.invalidate_user(user_id);
+
+    Ok(Json(GroupUserResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
@@ -0,0 +1,54 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    infra::repositories,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::error::LockoutError;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteLockoutResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/lockouts/{id}",
+    params(
+        ("id", Path, description = "Lockout id")
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Failed logins forgotten, lifting the lockout",
+            body = DeleteLockoutResponse,
+        ),
+        (status = NOT_FOUND, description = "Lockout not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn delete_lockout(
+    State(state): State<AppState>,
+    PathExtractor(lockout_id): PathExtractor<i32>,
+) -> Result<Json<DeleteLockoutResponse>, LockoutError> {
+    repositories::lockout::try_get_by_id(&state.pg_pool, lockout_id)
+        .await
+        .map_err(LockoutError::RepoError)?
+        .ok_or(LockoutError::NotFound)?;
+
+    repositories::lockout::delete_by_id(&state.pg_pool, lockout_id)
+        .await
+        .map_err(LockoutError::RepoError)?;
+
+    Ok(Json(DeleteLockoutResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
@@ -0,0 +1,32 @@
+use axum::{response::IntoResponse, http::StatusCode, Json};
+use serde_json::json;
+
+use crate::infra::repositories::error::RepoError;
+
+#[derive(Debug)]
+pub enum LockoutError {
+    NotFound,
+    RepoError(RepoError),
+}
+
+impl IntoResponse for LockoutError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, code, err_msg) = match self {
+            Self::NotFound => (
+                StatusCode::NOT_FOUND,
+                60001,
+                format!("Lockout not found."),
+            ),
+            Self::RepoError(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                60002,
+                format!("Internal server error."),
+            ),
+        };
+        (
+            status,
+            Json(json!({"code": code, "msg": err_msg})),
+        )
+            .into_response()
+    }
+}
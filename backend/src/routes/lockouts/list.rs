@@ -0,0 +1,73 @@
+use axum::{extract::{State, Query}, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    infra::repositories::{self, lockout::LockoutsFilter},
+    server::AppState,
+};
+use super::{error::LockoutError, schema::LockoutSchema};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LockoutSearchQuery {
+    /// `username` or `ip`
+    pub kind: Option<String>,
+    /// Username or IP address
+    pub key: Option<String>,
+    /// Only the entries refusing logins right now when true, only the
+    /// others when false
+    pub locked: Option<bool>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
+    pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListLockoutsResponse {
+    code: i32,
+    data: Option<Vec<LockoutSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
+    msg: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/lockouts",
+    params(LockoutSearchQuery),
+    responses(
+        (
+            status = 200,
+            description = "Usernames and addresses with recent failed logins, newest first",
+            body = ListLockoutsResponse,
+        ),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_lockouts(
+    State(state): State<AppState>,
+    Query(params): Query<LockoutsFilter>,
+) -> Result<Json<ListLockoutsResponse>, LockoutError> {
+    let lockouts = repositories::lockout::get_all(
+        &state.pg_pool, params
+    )
+        .await
+        .map_err(LockoutError::RepoError)?;
+
+    let lockouts = lockouts.map(LockoutSchema::from);
+
+    Ok(Json(ListLockoutsResponse {
+        code: 0,
+        data: Some(lockouts.items),
+        next_cursor: lockouts.next_cursor,
+        total: lockouts.total,
+        msg: None,
+    }))
+}
@@ -0,0 +1,23 @@
+use axum::{routing::{get, delete}, Router};
+
+use crate::{middlewares::auth::AuthLayer, server::AppState};
+
+pub mod delete;
+pub mod error;
+pub mod list;
+pub mod schema;
+
+pub fn lockouts_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list::list_lockouts)
+                .layer(AuthLayer::new(state.clone(), Some("lockouts.read".to_string()))),
+        )
+        .route(
+            "/:id",
+            delete(delete::delete_lockout)
+                .layer(AuthLayer::new(state.clone(), Some("lockouts.delete".to_string()))),
+        )
+        .with_state(state)
+}
@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::models::lockout::LockoutModel;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LockoutSchema {
+    pub id: i32,
+    /// `username` or `ip`
+    pub kind: String,
+    /// Username or IP address
+    pub key: String,
+    /// Failed logins in a row
+    pub failures: i32,
+    #[schema(value_type = String)]
+    pub last_failure_at: NaiveDateTime,
+    /// Logins are refused until then, they aren't when null or past
+    #[schema(value_type = Option<String>)]
+    pub locked_until: Option<NaiveDateTime>,
+    #[schema(value_type = String)]
+    pub created_at: NaiveDateTime,
+    #[schema(value_type = String)]
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<LockoutModel> for LockoutSchema {
+    fn from(lockout: LockoutModel) -> Self {
+        Self {
+            id: lockout.id,
+            kind: lockout.kind.as_str().to_string(),
+            key: lockout.key,
+            failures: lockout.failures,
+            last_failure_at: lockout.last_failure_at,
+            locked_until: lockout.locked_until,
+            created_at: lockout.created_at,
+            updated_at: lockout.updated_at,
+        }
+    }
+}
@@ -1,5 +1,7 @@
+pub mod audit;
 pub mod auth;
 pub mod datasets;
 pub mod groups;
+pub mod lockouts;
 pub mod permissions;
 pub mod users;
@@ -4,6 +4,7 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    domain::policy,
     infra::repositories::{self, permission::NewPermissionDB},
     server::AppState,
     utils::extractors::json::JsonExtractor,
@@ -12,6 +13,9 @@ use super::{error::PermissionError, schema::PermissionSchema};
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct PermissionCreationRequest {
+    /// Dot separated name such as `datasets.read`, a pattern such as
+    /// `datasets.*` or `*` granting everything below it, or either of them
+    /// prefixed with `!` to deny it
     pub name: String,
 }
 
@@ -40,6 +44,7 @@ pub struct PermissionCreationResponse {
             description = "Permission created successfully",
             body = PermissionCreationResponse,
         ),
+        (status = BAD_REQUEST, description = "The name is invalid or already taken"),
     )
 )]
 #[instrument(skip(state))]
@@ -47,6 +52,10 @@ pub async fn create_permission(
     State(state): State<AppState>,
     JsonExtractor(new_perm): JsonExtractor<PermissionCreationRequest>,
 ) -> Result<Json<PermissionCreationResponse>, PermissionError> {
+    if !policy::is_valid_name(&new_perm.name) {
+        return Err(PermissionError::InvalidName);
+    }
+
     let perm_in_db = repositories::permission::try_get_by_name(
         &state.pg_pool, new_perm.name.clone()
     )
@@ -63,6 +72,8 @@ pub async fn create_permission(
         .await
         .map_err(PermissionError::RepoError)?;
 
+    state.auth_cache.invalidate_all();
+
     Ok(Json(PermissionCreationResponse {
         code: 0,
         data: Some(PermissionSchema::from(created_perm)),
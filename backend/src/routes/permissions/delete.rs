@@ -42,6 +42,8 @@ pub async fn delete_permission(
         .await
         .map_err(PermissionError::RepoError)?;
 
+    state.auth_cache.invalidate_all();
+
     Ok(Json(DeletePermissionResponse {
         code: 0,
         data: true,
@@ -67,6 +69,8 @@ pub async fn delete_permissions(
         .await
         .map_err(PermissionError::RepoError)?;
 
+    state.auth_cache.invalidate_all();
+
     Ok(Json(DeletePermissionResponse {
         code: 0,
         data: true,
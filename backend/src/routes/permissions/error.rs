@@ -7,6 +7,7 @@ use crate::infra::repositories::error::RepoError;
 pub enum PermissionError {
     NotFound,
     Duplicate,
+    InvalidName,
     RepoError(RepoError),
 }
 
@@ -23,6 +24,11 @@ impl IntoResponse for PermissionError {
                 40002,
                 format!("Permission already exists."),
             ),
+            Self::InvalidName => (
+                StatusCode::BAD_REQUEST,
+                40004,
+                format!("Invalid permission name."),
+            ),
             Self::RepoError(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 40003,
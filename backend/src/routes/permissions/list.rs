@@ -12,16 +12,22 @@ use super::{error::PermissionError, schema::PermissionSchema};
 #[derive(Deserialize, IntoParams)]
 #[into_params(parameter_in = Query)]
 pub struct PermissionSearchQuery {
-    /// Skip, default: 0
-    pub skip: Option<i64>,
-    /// Limit, default: 20
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
     pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListPermissionsResponse {
     code: i32,
     data: Option<Vec<PermissionSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
     msg: Option<String>,
 }
 
@@ -45,14 +51,13 @@ pub async fn list_permissions(
         .await
         .map_err(PermissionError::RepoError)?;
 
-    let perms = perms
-        .into_iter()
-        .map(PermissionSchema::from)
-        .collect();
+    let perms = perms.map(PermissionSchema::from);
 
     Ok(Json(ListPermissionsResponse {
         code: 0,
-        data: Some(perms),
+        data: Some(perms.items),
+        next_cursor: perms.next_cursor,
+        total: perms.total,
         msg: None,
     }))
 }
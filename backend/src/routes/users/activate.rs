@@ -50,6 +50,8 @@ pub async fn activate_user(
         .await
         .map_err(UserError::RepoError)?;
 
+    state.auth_cache.invalidate_user(user_id);
+
     Ok(Json(ActivateUserResponse {
         code: 0,
         data: Some(UserSchema::from(user)),
@@ -89,6 +91,8 @@ pub async fn deactivate_user(
         .await
         .map_err(UserError::RepoError)?;
 
+    state.auth_cache.invalidate_user(user_id);
+
     Ok(Json(ActivateUserResponse {
         code: 0,
         data: Some(UserSchema::from(user)),
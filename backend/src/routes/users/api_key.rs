@@ -0,0 +1,196 @@
+use axum::{extract::State, Extension, Json};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::{
+        models::{api_key::ApiKeyModel, user::UserModel},
+        policy::Policy,
+    },
+    infra::repositories::{self, api_key::NewApiKeyDB},
+    routes::auth::api_key,
+    server::AppState,
+    utils::extractors::{json::JsonExtractor, path::PathExtractor},
+};
+use super::{error::UserError, schema::ApiKeySchema};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ApiKeyCreationRequest {
+    pub name: String,
+    /// Names of the permissions the key may use, each held by the caller.
+    /// Patterns such as `datasets.*` are held when all they reach is.
+    pub scopes: Vec<String>,
+    /// Never expires when omitted
+    #[schema(value_type = Option<String>)]
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyCreationResponse {
+    pub code: i32,
+    pub data: Option<ApiKeySchema>,
+    /// The `drk_` key to send as `Authorization: Bearer`, only ever returned
+    /// here
+    pub key: Option<String>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListApiKeysResponse {
+    pub code: i32,
+    pub data: Option<Vec<ApiKeySchema>>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteApiKeyResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/me/api-keys",
+    request_body = ApiKeyCreationRequest,
+    responses(
+        (
+            status = 200,
+            description = "API key created successfully",
+            body = ApiKeyCreationResponse,
+        ),
+        (status = BAD_REQUEST, description = "A scope is not held by the caller, or the expiry is past"),
+    )
+)]
+#[instrument(skip(state, new_key), fields(name = %new_key.name))]
+pub async fn create_me_api_key(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
+    caller_key: Option<Extension<ApiKeyModel>>,
+    JsonExtractor(new_key): JsonExtractor<ApiKeyCreationRequest>,
+) -> Result<Json<ApiKeyCreationResponse>, UserError> {
+    if new_key
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= chrono::Utc::now().naive_utc())
+    {
+        return Err(UserError::InvalidApiKey("expires_at is in the past".to_string()));
+    }
+
+    let perms = repositories::user::get_permissions(
+        &state.pg_pool, user.id
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+    let policy = Policy::new(perms.iter().map(|perm| &perm.name));
+    let caller_scopes = caller_key
+        .as_ref()
+        .map(|Extension(key)| Policy::new(&key.scopes));
+
+    // A key can't mint a key reaching further than itself.
+    let mut permission_ids = Vec::with_capacity(new_key.scopes.len());
+    for scope in new_key.scopes.iter() {
+        let is_held = policy.covers(scope)
+            && caller_scopes.as_ref().is_none_or(|scopes| scopes.covers(scope));
+        if !is_held {
+            return Err(UserError::InvalidApiKey(format!("scope {} is not held", scope)));
+        }
+
+        let perm = repositories::permission::try_get_by_name(
+            &state.pg_pool, scope.clone()
+        )
+            .await
+            .map_err(UserError::RepoError)?
+            .ok_or_else(|| UserError::InvalidApiKey(format!("scope {} is no permission", scope)))?;
+        permission_ids.push(perm.id);
+    }
+
+    let new_api_key = api_key::generate()
+        .map_err(|_| UserError::InternalServerError("failed to generate the api key".to_owned()))?;
+
+    let created_key = repositories::api_key::create(
+        &state.pg_pool,
+        NewApiKeyDB {
+            user_id: user.id,
+            name: new_key.name,
+            prefix: new_api_key.prefix,
+            hashed_secret: new_api_key.hashed_secret,
+            expires_at: new_key.expires_at,
+        },
+        permission_ids,
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    Ok(Json(ApiKeyCreationResponse {
+        code: 0,
+        data: Some(ApiKeySchema::from(created_key)),
+        key: Some(new_api_key.key),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/me/api-keys",
+    responses(
+        (status = 200, description = "API keys query successfully", body = ListApiKeysResponse),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_me_api_keys(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
+) -> Result<Json<ListApiKeysResponse>, UserError> {
+    let keys = repositories::api_key::get_all_by_user_id(
+        &state.pg_pool, user.id
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    Ok(Json(ListApiKeysResponse {
+        code: 0,
+        data: Some(keys.into_iter().map(ApiKeySchema::from).collect()),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/me/api-keys/{id}",
+    params(
+        ("id", Path, description = "API key id"),
+    ),
+    responses(
+        (status = 200, description = "API key revoked successfully", body = DeleteApiKeyResponse),
+        (status = NOT_FOUND, description = "API key not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn delete_me_api_key(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
+    PathExtractor(key_id): PathExtractor<i32>,
+) -> Result<Json<DeleteApiKeyResponse>, UserError> {
+    repositories::api_key::try_get_by_id(
+        &state.pg_pool, key_id
+    )
+        .await
+        .map_err(UserError::RepoError)?
+        .filter(|key| key.user_id == user.id)
+        .ok_or(UserError::ApiKeyNotFound)?;
+
+    repositories::api_key::delete_by_id(
+        &state.pg_pool, key_id
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+    state.auth_cache.invalidate_api_key(key_id);
+
+    Ok(Json(DeleteApiKeyResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
@@ -6,18 +6,33 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
-    infra::repositories::{self, user::NewUserDB},
+    infra::{mailer, repositories::{self, user::NewUserDB}},
     server::AppState,
     utils::extractors::json::JsonExtractor,
 };
 use super::{error::UserError, schema::UserSchema};
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Deserialize, ToSchema)]
 pub struct UserCreationRequest {
     pub username: String,
     pub password: String,
     pub nickname: String,
     pub avatar_uri: String,
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+// Keeps the password out of the logs of `create_user`.
+impl std::fmt::Debug for UserCreationRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserCreationRequest")
+            .field("username", &self.username)
+            .field("password", &"[redacted]")
+            .field("nickname", &self.nickname)
+            .field("avatar_uri", &self.avatar_uri)
+            .field("email", &self.email)
+            .finish()
+    }
 }
 
 impl TryInto<NewUserDB> for UserCreationRequest {
@@ -35,6 +50,7 @@ impl TryInto<NewUserDB> for UserCreationRequest {
             hashed_password,
             nickname: self.nickname,
             avatar_uri: self.avatar_uri,
+            email: self.email,
         })
     }
 }
@@ -73,6 +89,12 @@ pub async fn create_user(
         return Err(UserError::DuplicateUsername);
     }
 
+    if let Some(email) = &new_user.email {
+        check_email(&state, email).await?;
+    }
+
+    check_password(&state, &new_user.password)?;
+
     let created_user = repositories::user::create(
         &state.pg_pool, new_user.try_into()?
     )
@@ -85,3 +107,29 @@ pub async fn create_user(
         msg: None,
     }))
 }
+
+/// Fails unless `email` is a valid address no other user has.
+pub async fn check_email(state: &AppState, email: &str) -> Result<(), UserError> {
+    if !mailer::is_valid_address(email) {
+        return Err(UserError::InvalidEmail);
+    }
+
+    let user_in_db = repositories::user::try_get_by_email(
+        &state.pg_pool, email.to_string()
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    if user_in_db.is_some() {
+        return Err(UserError::DuplicateEmail);
+    }
+
+    Ok(())
+}
+
+/// Fails unless `password` meets the password policy.
+pub fn check_password(state: &AppState, password: &str) -> Result<(), UserError> {
+    state.password_policy
+        .check(password)
+        .map_err(UserError::WeakPassword)
+}
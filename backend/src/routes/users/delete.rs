@@ -42,6 +42,8 @@ pub async fn delete_user(
         .await
         .map_err(UserError::RepoError)?;
 
+    state.auth_cache.invalidate_user(user_id);
+
     Ok(Json(DeleteUserResponse {
         code: 0,
         data: true,
@@ -67,6 +69,8 @@ pub async fn delete_users(
         .await
         .map_err(UserError::RepoError)?;
 
+    state.auth_cache.invalidate_all();
+
     Ok(Json(DeleteUserResponse {
         code: 0,
         data: true,
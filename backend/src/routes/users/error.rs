@@ -1,12 +1,26 @@
 use axum::{response::IntoResponse, http::StatusCode, Json};
 use serde_json::json;
 
-use crate::infra::repositories::error::RepoError;
+use crate::{domain::password::PasswordViolation, infra::repositories::error::RepoError};
 
 #[derive(Debug)]
 pub enum UserError {
     NotFound,
     DuplicateUsername,
+    InvalidApiKey(String),
+    ApiKeyNotFound,
+    DuplicateEmail,
+    InvalidEmail,
+    RegistrationDisabled,
+    InvalidVerificationToken,
+    RegistrationNotFound,
+    RegistrationNotReviewable,
+    WeakPassword(PasswordViolation),
+    PasswordResetDisabled,
+    InvalidResetToken,
+    TwoFactorAlreadyEnabled,
+    TwoFactorNotEnrolled,
+    InvalidTwoFactorCode,
     InternalServerError(String),
     RepoError(RepoError),
 }
@@ -24,6 +38,76 @@ impl IntoResponse for UserError {
                 20002,
                 format!("Username already exists."),
             ),
+            Self::InvalidApiKey(msg) => (
+                StatusCode::BAD_REQUEST,
+                20005,
+                format!("Invalid API key: {}.", msg),
+            ),
+            Self::ApiKeyNotFound => (
+                StatusCode::NOT_FOUND,
+                20006,
+                format!("API key not found."),
+            ),
+            Self::DuplicateEmail => (
+                StatusCode::BAD_REQUEST,
+                20007,
+                format!("Email already exists."),
+            ),
+            Self::InvalidEmail => (
+                StatusCode::BAD_REQUEST,
+                20008,
+                format!("Invalid email address."),
+            ),
+            Self::RegistrationDisabled => (
+                StatusCode::NOT_FOUND,
+                20009,
+                format!("Registration is not enabled."),
+            ),
+            Self::InvalidVerificationToken => (
+                StatusCode::BAD_REQUEST,
+                20010,
+                format!("Invalid or expired verification token."),
+            ),
+            Self::RegistrationNotFound => (
+                StatusCode::NOT_FOUND,
+                20011,
+                format!("Registration not found."),
+            ),
+            Self::RegistrationNotReviewable => (
+                StatusCode::BAD_REQUEST,
+                20012,
+                format!("Registration can't be reviewed in its current status."),
+            ),
+            Self::WeakPassword(violation) => (
+                StatusCode::BAD_REQUEST,
+                20013,
+                format!("Password {}.", violation),
+            ),
+            Self::PasswordResetDisabled => (
+                StatusCode::NOT_FOUND,
+                20014,
+                format!("Password reset is not enabled."),
+            ),
+            Self::InvalidResetToken => (
+                StatusCode::BAD_REQUEST,
+                20015,
+                format!("Invalid or expired password reset token."),
+            ),
+            Self::TwoFactorAlreadyEnabled => (
+                StatusCode::BAD_REQUEST,
+                20016,
+                format!("Two-factor authentication is already enabled."),
+            ),
+            Self::TwoFactorNotEnrolled => (
+                StatusCode::BAD_REQUEST,
+                20017,
+                format!("Two-factor authentication is not enrolled."),
+            ),
+            Self::InvalidTwoFactorCode => (
+                StatusCode::BAD_REQUEST,
+                20018,
+                format!("Invalid two-factor authentication code."),
+            ),
             Self::InternalServerError(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 20003,
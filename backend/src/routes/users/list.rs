@@ -22,16 +22,22 @@ pub struct UserSearchQuery {
     pub is_active: Option<bool>,
     pub with_groups: Option<bool>,
     pub with_permissions: Option<bool>,
-    /// Skip, default: 0
-    pub skip: Option<i64>,
-    /// Limit, default: 20
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
     pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ListUsersResponse {
     code: i32,
     data: Option<Vec<UserSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
     msg: Option<String>,
 }
 
@@ -55,14 +61,13 @@ pub async fn list_users(
         .await
         .map_err(UserError::RepoError)?;
 
-    let users = users
-        .into_iter()
-        .map(UserSchema::from)
-        .collect();
+    let users = users.map(UserSchema::from);
 
     Ok(Json(ListUsersResponse {
         code: 0,
-        data: Some(users),
+        data: Some(users.items),
+        next_cursor: users.next_cursor,
+        total: users.total,
         msg: None,
     }))
 }
@@ -3,19 +3,48 @@ use axum::{routing::{get, post, put, delete}, Router};
 use crate::{middlewares::auth::AuthLayer, server::AppState};
 
 pub mod activate;
+pub mod api_key;
 pub mod create;
 pub mod delete;
 pub mod error;
 pub mod get;
 pub mod group;
 pub mod list;
+pub mod password;
 pub mod permission;
+pub mod registration;
 pub mod schema;
+pub mod session;
+pub mod two_factor;
 pub mod update;
 
 pub fn users_routes(state: AppState) -> Router<AppState> {
     Router::new()
-        .route("/", post(create::create_user))
+        .route(
+            "/",
+            post(create::create_user)
+                .layer(AuthLayer::new(state.clone(), Some("users.create".to_string()))),
+        )
+        .route("/register", post(registration::register))
+        .route("/register/verify", get(registration::verify_registration))
+        .route("/register/resend", post(registration::resend_verification))
+        .route("/password/forgot", post(password::forgot_password))
+        .route("/password/reset", post(password::reset_password))
+        .route(
+            "/registrations",
+            get(registration::list_registrations)
+                .layer(AuthLayer::new(state.clone(), Some("users.activate".to_string()))),
+        )
+        .route(
+            "/registrations/:id/approve",
+            post(registration::approve_registration)
+                .layer(AuthLayer::new(state.clone(), Some("users.activate".to_string()))),
+        )
+        .route(
+            "/registrations/:id/reject",
+            post(registration::reject_registration)
+                .layer(AuthLayer::new(state.clone(), Some("users.activate".to_string()))),
+        )
         .route(
             "/me",
             get(get::get_me)
@@ -26,6 +55,38 @@ pub fn users_routes(state: AppState) -> Router<AppState> {
             put(update::update_me)
                 .layer(AuthLayer::new(state.clone(), None)),
         )
+        .route(
+            "/me/api-keys",
+            post(api_key::create_me_api_key)
+                .layer(AuthLayer::new(state.clone(), None)),
+        )
+        .route(
+            "/me/api-keys",
+            get(api_key::list_me_api_keys)
+                .layer(AuthLayer::new(state.clone(), None)),
+        )
+        .route(
+            "/me/api-keys/:id",
+            delete(api_key::delete_me_api_key)
+                .layer(AuthLayer::new(state.clone(), None)),
+        )
+        .route(
+            "/me/2fa",
+            get(two_factor::get_me_two_factor)
+                .post(two_factor::enroll_me_two_factor)
+                .delete(two_factor::disable_me_two_factor)
+                .layer(AuthLayer::new(state.clone(), None)),
+        )
+        .route(
+            "/me/2fa/confirm",
+            post(two_factor::confirm_me_two_factor)
+                .layer(AuthLayer::new(state.clone(), None)),
+        )
+        .route(
+            "/me/2fa/recovery-codes",
+            post(two_factor::regenerate_me_recovery_codes)
+                .layer(AuthLayer::new(state.clone(), None)),
+        )
         .route(
             "/me/groups",
             get(group::get_me_groups)
@@ -71,6 +132,16 @@ pub fn users_routes(state: AppState) -> Router<AppState> {
             get(activate::deactivate_user)
                 .layer(AuthLayer::new(state.clone(), Some("users.activate".to_string()))),
         )
+        .route(
+            "/:id/sessions",
+            delete(session::revoke_user_sessions)
+                .layer(AuthLayer::new(state.clone(), Some("users.revoke_sessions".to_string()))),
+        )
+        .route(
+            "/:id/2fa",
+            delete(two_factor::reset_user_two_factor)
+                .layer(AuthLayer::new(state.clone(), Some("users.update_all".to_string()))),
+        )
         .route(
             "/:id/groups",
             get(group::get_user_groups)
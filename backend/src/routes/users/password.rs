@@ -0,0 +1,154 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    bootstrap,
+    infra::{
+        mailer::Email,
+        repositories::{self, password_reset::NewPasswordResetDB},
+    },
+    routes::auth::token::{hash_opaque_token, new_opaque_token},
+    server::AppState,
+    utils::extractors::json::JsonExtractor,
+};
+use super::{create::check_password, error::UserError};
+
+/// Lifetime of the token sent to reset a forgotten password.
+pub const RESET_TOKEN_MINUTES: i64 = 60;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ForgotPasswordResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ResetPasswordRequest {
+    /// Token sent by email
+    pub token: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResetPasswordResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/password/forgot",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (
+            status = 200,
+            description = "Reset token sent if the email belongs to an active user",
+            body = ForgotPasswordResponse,
+        ),
+        (status = NOT_FOUND, description = "Password reset is not enabled"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    JsonExtractor(request): JsonExtractor<ForgotPasswordRequest>,
+) -> Result<Json<ForgotPasswordResponse>, UserError> {
+    let mailer = state.mailer.as_ref().ok_or(UserError::PasswordResetDisabled)?;
+
+    let user = repositories::user::try_get_by_email(
+        &state.pg_pool, request.email.clone()
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    // Answers the same whether or not the email is known, so that it can't
+    // be used to find out who has an account.
+    if let Some(user) = user.filter(|user| user.is_active) {
+        let token = new_opaque_token();
+        repositories::password_reset::create(
+            &state.pg_pool,
+            NewPasswordResetDB {
+                user_id: user.id,
+                token_hash: hash_opaque_token(&token),
+                expires_at: (chrono::Utc::now() + chrono::Duration::minutes(RESET_TOKEN_MINUTES)).naive_utc(),
+            },
+        )
+            .await
+            .map_err(UserError::RepoError)?;
+
+        let email = Email {
+            to: request.email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hello {},\n\n\
+                Someone asked to reset your password. Send the token below \
+                along with your new password to {}/v1/users/password/reset \
+                within {} minutes. Ignore this email if it wasn't you.\n\n{}\n",
+                user.nickname,
+                state.public_url.trim_end_matches('/'),
+                RESET_TOKEN_MINUTES,
+                token
+            ),
+        };
+
+        if let Err(err) = mailer.send(email).await {
+            tracing::error!("Failed to send the password reset email of user {}: {}", user.id, err);
+        }
+    }
+
+    Ok(Json(ForgotPasswordResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/password/reset",
+    request_body = ResetPasswordRequest,
+    responses(
+        (
+            status = 200,
+            description = "Password set, sessions of the user revoked and API keys deleted",
+            body = ResetPasswordResponse,
+        ),
+        (status = BAD_REQUEST, description = "Invalid or expired token, or weak password"),
+    )
+)]
+#[instrument(skip(state, request))]
+pub async fn reset_password(
+    State(state): State<AppState>,
+    JsonExtractor(request): JsonExtractor<ResetPasswordRequest>,
+) -> Result<Json<ResetPasswordResponse>, UserError> {
+    check_password(&state, &request.password)?;
+
+    let hashed_password = bootstrap::hash_password(&request.password)
+        .map_err(|_| UserError::InternalServerError("failed to hash the password".to_owned()))?;
+
+    let reset = repositories::password_reset::try_consume(
+        &state.pg_pool, hash_opaque_token(&request.token), hashed_password
+    )
+        .await
+        .map_err(UserError::RepoError)?
+        .ok_or(UserError::InvalidResetToken)?;
+
+    // Whoever knew the old password is logged out, which the cache would
+    // otherwise let them stay for its TTL.
+    state.auth_cache.invalidate_user(reset.user_id);
+
+    Ok(Json(ResetPasswordResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
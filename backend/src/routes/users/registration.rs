@@ -0,0 +1,509 @@
+use axum::{extract::{State, Query}, Json, Extension};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::{ToSchema, IntoParams};
+
+use crate::{
+    domain::models::{
+        registration::{RegistrationModel, RegistrationStatus},
+        user::UserModel,
+    },
+    infra::{
+        mailer::{self, Email},
+        repositories::{
+            self,
+            registration::{NewRegistrationDB, RegistrationsFilter},
+            user::NewUserDB,
+        },
+    },
+    routes::auth::token::{hash_opaque_token, new_opaque_token},
+    server::AppState,
+    utils::extractors::{json::JsonExtractor, path::PathExtractor},
+};
+use super::{
+    create::{check_password, UserCreationRequest},
+    error::UserError,
+    schema::RegistrationSchema,
+};
+
+/// Lifetime of the link sent to verify the email of a registration.
+pub const VERIFICATION_TOKEN_HOURS: i64 = 24;
+
+#[derive(Deserialize, ToSchema)]
+pub struct RegistrationRequest {
+    pub username: String,
+    pub password: String,
+    pub nickname: String,
+    /// Where the verification link is sent
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RegistrationResponse {
+    pub code: i32,
+    pub data: Option<RegistrationSchema>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RegisterResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VerifyRegistrationQuery {
+    /// Token of the link sent by email
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ResendVerificationRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResendVerificationResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RegistrationSearchQuery {
+    /// `unverified`, `pending`, `approved` or `rejected`
+    pub status: Option<String>,
+    /// Opaque cursor returned as `next_cursor` by the previous page
+    pub cursor: Option<String>,
+    /// Limit, default: 20, max: 100
+    pub limit: Option<i64>,
+    /// Whether to count all matching rows, default: false
+    pub with_total: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListRegistrationsResponse {
+    code: i32,
+    data: Option<Vec<RegistrationSchema>>,
+    /// Pass as `cursor` to fetch the next page, null on the last page
+    next_cursor: Option<String>,
+    /// Number of matching rows, only set when `with_total` is true
+    total: Option<i64>,
+    msg: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewRegistrationRequest {
+    /// Recorded along with the decision and sent to the user
+    pub reason: Option<String>,
+}
+
+fn token_expires_at() -> chrono::NaiveDateTime {
+    (chrono::Utc::now() + chrono::Duration::hours(VERIFICATION_TOKEN_HOURS)).naive_utc()
+}
+
+async fn send_verification(
+    state: &AppState,
+    user: &UserModel,
+    email: String,
+    token: &str,
+) -> Result<(), UserError> {
+    let mailer = state.mailer.as_ref().ok_or(UserError::RegistrationDisabled)?;
+
+    let link = format!(
+        "{}/v1/users/register/verify?token={}",
+        state.public_url.trim_end_matches('/'), token
+    );
+    let email = Email {
+        to: email,
+        subject: "Verify your email".to_string(),
+        body: format!(
+            "Hello {},\n\n\
+            Open the link below within {} hours to verify your email. An admin \
+            will then review your registration.\n\n{}\n",
+            user.nickname, VERIFICATION_TOKEN_HOURS, link
+        ),
+    };
+
+    mailer.send(email).await.map_err(|err| {
+        tracing::error!("Failed to send the verification email of user {}: {}", user.id, err);
+        UserError::InternalServerError("failed to send the verification email".to_string())
+    })
+}
+
+/// Tells the owner of an address someone tried to register with again, so
+/// that the registration can answer the same as for a new address. Failing
+/// to do so is only logged for the same reason.
+async fn send_already_registered(state: &AppState, user: &UserModel, email: String) {
+    let Some(mailer) = state.mailer.as_ref() else {
+        return;
+    };
+
+    let email = Email {
+        to: email,
+        subject: "Your email was used to register".to_string(),
+        body: format!(
+            "Hello {},\n\n\
+            Someone tried to register with this email, which already belongs to \
+            your account {}. If it was you, log in or reset your password. \
+            Otherwise you can ignore this email.\n",
+            user.nickname, user.username
+        ),
+    };
+
+    if let Err(err) = mailer.send(email).await {
+        tracing::error!("Failed to notify user {} of a registration with their email: {}", user.id, err);
+    }
+}
+
+/// Tells the user about the decision on their registration. Failing to do
+/// so doesn't undo the decision.
+async fn send_decision(state: &AppState, registration: &RegistrationModel) {
+    let (Some(mailer), Ok(Some(user))) = (
+        state.mailer.as_ref(),
+        repositories::user::try_get_by_id(&state.pg_pool, registration.user_id).await,
+    ) else {
+        return;
+    };
+    let Some(address) = user.email else {
+        return;
+    };
+
+    let decision = match registration.status {
+        RegistrationStatus::Approved => "approved, you can now log in",
+        _ => "rejected",
+    };
+    let reason = registration.reason
+        .as_ref()
+        .map(|reason| format!("\n\nReason: {}", reason))
+        .unwrap_or_default();
+    let email = Email {
+        to: address,
+        subject: "Your registration was reviewed".to_string(),
+        body: format!("Hello {},\n\nYour registration was {}.{}\n", user.nickname, decision, reason),
+    };
+
+    if let Err(err) = mailer.send(email).await {
+        tracing::error!("Failed to notify user {} of their registration: {}", user.id, err);
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/register",
+    request_body = RegistrationRequest,
+    responses(
+        (
+            status = 200,
+            description = "Verification link sent, or a notice when the email is already registered",
+            body = RegisterResponse,
+        ),
+        (status = NOT_FOUND, description = "Registration is not enabled"),
+    )
+)]
+#[instrument(skip(state, request), fields(username = %request.username))]
+pub async fn register(
+    State(state): State<AppState>,
+    JsonExtractor(request): JsonExtractor<RegistrationRequest>,
+) -> Result<Json<RegisterResponse>, UserError> {
+    if !state.allow_registration || state.mailer.is_none() {
+        return Err(UserError::RegistrationDisabled);
+    }
+
+    if !mailer::is_valid_address(&request.email) {
+        return Err(UserError::InvalidEmail);
+    }
+    check_password(&state, &request.password)?;
+
+    // Registrations left unverified past their link free their username and
+    // email for whoever registers them next.
+    let stale = repositories::registration::delete_stale(
+        &state.pg_pool, request.username.clone(), request.email.clone()
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    for user_id in stale {
+        state.auth_cache.invalidate_user(user_id);
+    }
+
+    let user_in_db = repositories::user::try_get_by_username(
+        &state.pg_pool, request.username.clone()
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    if user_in_db.is_some() {
+        return Err(UserError::DuplicateUsername);
+    }
+
+    let email = request.email.clone();
+    // Hashed before looking the email up, so that both answers take as long.
+    let new_user: NewUserDB = UserCreationRequest {
+        username: request.username,
+        password: request.password,
+        nickname: request.nickname,
+        avatar_uri: String::new(),
+        email: Some(request.email),
+    }
+        .try_into()?;
+
+    let owner = repositories::user::try_get_by_email(
+        &state.pg_pool, email.clone()
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    // Answers the same whether or not the email is registered, so that it
+    // can't be used to find out who registered. Its owner is told instead.
+    match owner {
+        Some(owner) => send_already_registered(&state, &owner, email).await,
+        None => {
+            let token = new_opaque_token();
+            let (user, _) = repositories::registration::register(
+                &state.pg_pool,
+                new_user,
+                NewRegistrationDB {
+                    token_hash: hash_opaque_token(&token),
+                    token_expires_at: token_expires_at(),
+                },
+            )
+                .await
+                .map_err(UserError::RepoError)?;
+
+            send_verification(&state, &user, email, &token).await?;
+        }
+    }
+
+    Ok(Json(RegisterResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/register/verify",
+    params(VerifyRegistrationQuery),
+    responses(
+        (
+            status = 200,
+            description = "Email verified, the registration awaits an admin",
+            body = RegistrationResponse,
+        ),
+        (status = BAD_REQUEST, description = "Invalid or expired token"),
+    )
+)]
+#[instrument(skip(state, params))]
+pub async fn verify_registration(
+    State(state): State<AppState>,
+    Query(params): Query<VerifyRegistrationQuery>,
+) -> Result<Json<RegistrationResponse>, UserError> {
+    let registration = repositories::registration::try_verify(
+        &state.pg_pool, hash_opaque_token(&params.token)
+    )
+        .await
+        .map_err(UserError::RepoError)?
+        .ok_or(UserError::InvalidVerificationToken)?;
+
+    Ok(Json(RegistrationResponse {
+        code: 0,
+        data: Some(RegistrationSchema::from(registration)),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/register/resend",
+    request_body = ResendVerificationRequest,
+    responses(
+        (
+            status = 200,
+            description = "Verification link sent again if the email belongs to an unverified registration",
+            body = ResendVerificationResponse,
+        ),
+        (status = NOT_FOUND, description = "Registration is not enabled"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn resend_verification(
+    State(state): State<AppState>,
+    JsonExtractor(request): JsonExtractor<ResendVerificationRequest>,
+) -> Result<Json<ResendVerificationResponse>, UserError> {
+    if !state.allow_registration || state.mailer.is_none() {
+        return Err(UserError::RegistrationDisabled);
+    }
+
+    let user = repositories::user::try_get_by_email(
+        &state.pg_pool, request.email.clone()
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    // Answers the same whether or not the email is known, so that it can't
+    // be used to find out who registered.
+    if let Some(user) = user {
+        let token = new_opaque_token();
+        let renewed = repositories::registration::try_renew_token(
+            &state.pg_pool, user.id, hash_opaque_token(&token), token_expires_at()
+        )
+            .await
+            .map_err(UserError::RepoError)?;
+
+        if renewed.is_some() {
+            send_verification(&state, &user, request.email, &token).await?;
+        }
+    }
+
+    Ok(Json(ResendVerificationResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/registrations",
+    params(RegistrationSearchQuery),
+    responses(
+        (status = 200, description = "Registrations listed, oldest first", body = ListRegistrationsResponse),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn list_registrations(
+    State(state): State<AppState>,
+    Query(params): Query<RegistrationsFilter>,
+) -> Result<Json<ListRegistrationsResponse>, UserError> {
+    let registrations = repositories::registration::get_all(
+        &state.pg_pool, params
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+
+    let registrations = registrations.map(RegistrationSchema::from);
+
+    Ok(Json(ListRegistrationsResponse {
+        code: 0,
+        data: Some(registrations.items),
+        next_cursor: registrations.next_cursor,
+        total: registrations.total,
+        msg: None,
+    }))
+}
+
+async fn review(
+    state: &AppState,
+    reviewer: &UserModel,
+    registration_id: i32,
+    reviewable: &[RegistrationStatus],
+    decision: RegistrationStatus,
+    reason: Option<String>,
+) -> Result<RegistrationModel, UserError> {
+    repositories::registration::try_get_by_id(
+        &state.pg_pool, registration_id
+    )
+        .await
+        .map_err(UserError::RepoError)?
+        .ok_or(UserError::RegistrationNotFound)?;
+
+    let registration = repositories::registration::try_review(
+        &state.pg_pool, registration_id, reviewable, decision, reviewer.id, reason
+    )
+        .await
+        .map_err(UserError::RepoError)?
+        .ok_or(UserError::RegistrationNotReviewable)?;
+
+    state.auth_cache.invalidate_user(registration.user_id);
+    send_decision(state, &registration).await;
+
+    Ok(registration)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/registrations/{id}/approve",
+    params(
+        ("id", Path, description = "Registration id"),
+    ),
+    request_body = ReviewRegistrationRequest,
+    responses(
+        (
+            status = 200,
+            description = "Registration approved and user activated",
+            body = RegistrationResponse,
+        ),
+        (status = NOT_FOUND, description = "Registration not found"),
+        (status = BAD_REQUEST, description = "Registration is not pending"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn approve_registration(
+    State(state): State<AppState>,
+    Extension(reviewer): Extension<UserModel>,
+    PathExtractor(registration_id): PathExtractor<i32>,
+    JsonExtractor(request): JsonExtractor<ReviewRegistrationRequest>,
+) -> Result<Json<RegistrationResponse>, UserError> {
+    // Only verified registrations can be approved.
+    let registration = review(
+        &state,
+        &reviewer,
+        registration_id,
+        &[RegistrationStatus::Pending],
+        RegistrationStatus::Approved,
+        request.reason,
+    )
+        .await?;
+
+    Ok(Json(RegistrationResponse {
+        code: 0,
+        data: Some(RegistrationSchema::from(registration)),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/registrations/{id}/reject",
+    params(
+        ("id", Path, description = "Registration id"),
+    ),
+    request_body = ReviewRegistrationRequest,
+    responses(
+        (
+            status = 200,
+            description = "Registration rejected, the user stays inactive",
+            body = RegistrationResponse,
+        ),
+        (status = NOT_FOUND, description = "Registration not found"),
+        (status = BAD_REQUEST, description = "Registration was already reviewed"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn reject_registration(
+    State(state): State<AppState>,
+    Extension(reviewer): Extension<UserModel>,
+    PathExtractor(registration_id): PathExtractor<i32>,
+    JsonExtractor(request): JsonExtractor<ReviewRegistrationRequest>,
+) -> Result<Json<RegistrationResponse>, UserError> {
+    let registration = review(
+        &state,
+        &reviewer,
+        registration_id,
+        &[RegistrationStatus::Unverified, RegistrationStatus::Pending],
+        RegistrationStatus::Rejected,
+        request.reason,
+    )
+        .await?;
+
+    Ok(Json(RegistrationResponse {
+        code: 0,
+        data: Some(RegistrationSchema::from(registration)),
+        msg: None,
+    }))
+}
@@ -2,7 +2,11 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::domain::models::user::UserModel;
+use crate::domain::models::{
+    api_key::ApiKeyModel,
+    registration::RegistrationModel,
+    user::UserModel,
+};
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserSchema {
@@ -11,6 +15,7 @@ pub struct UserSchema {
     pub nickname: String,
     pub avatar_uri: String,
     pub is_active: bool,
+    pub email: Option<String>,
     #[schema(value_type = String)]
     created_at: NaiveDateTime,
     #[schema(value_type = String)]
@@ -25,8 +30,89 @@ impl From<UserModel> for UserSchema {
             nickname: user.nickname,
             avatar_uri: user.avatar_uri,
             is_active: user.is_active,
+            email: user.email,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeySchema {
+    pub id: i32,
+    pub name: String,
+    /// Public part of the key, the `drk_` key starts with it
+    pub prefix: String,
+    /// Permissions the key may use, as long as its user holds them
+    pub scopes: Vec<String>,
+    #[schema(value_type = Option<String>)]
+    pub expires_at: Option<NaiveDateTime>,
+    #[schema(value_type = Option<String>)]
+    pub last_used_at: Option<NaiveDateTime>,
+    #[schema(value_type = String)]
+    created_at: NaiveDateTime,
+    #[schema(value_type = String)]
+    updated_at: NaiveDateTime,
+}
+
+impl From<ApiKeyModel> for ApiKeySchema {
+    fn from(key: ApiKeyModel) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            prefix: key.prefix,
+            scopes: key.scopes,
+            expires_at: key.expires_at,
+            last_used_at: key.last_used_at,
+            created_at: key.created_at,
+            updated_at: key.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RegistrationSchema {
+    pub id: i32,
+    pub user_id: i32,
+    /// The registered user, set in listings and right after registering
+    pub user: Option<UserSchema>,
+    /// `unverified`, `pending`, `approved` or `rejected`
+    pub status: String,
+    #[schema(value_type = Option<String>)]
+    pub verified_at: Option<NaiveDateTime>,
+    /// Admin who approved or rejected the registration
+    pub reviewed_by: Option<i32>,
+    #[schema(value_type = Option<String>)]
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub reason: Option<String>,
+    #[schema(value_type = String)]
+    created_at: NaiveDateTime,
+    #[schema(value_type = String)]
+    updated_at: NaiveDateTime,
+}
+
+impl From<RegistrationModel> for RegistrationSchema {
+    fn from(registration: RegistrationModel) -> Self {
+        Self {
+            id: registration.id,
+            user_id: registration.user_id,
+            user: None,
+            status: registration.status.as_str().to_string(),
+            verified_at: registration.verified_at,
+            reviewed_by: registration.reviewed_by,
+            reviewed_at: registration.reviewed_at,
+            reason: registration.reason,
+            created_at: registration.created_at,
+            updated_at: registration.updated_at,
+        }
+    }
+}
+
+impl From<(RegistrationModel, UserModel)> for RegistrationSchema {
+    fn from((registration, user): (RegistrationModel, UserModel)) -> Self {
+        Self {
+            user: Some(UserSchema::from(user)),
+            ..Self::from(registration)
+        }
+    }
+}
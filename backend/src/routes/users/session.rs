@@ -0,0 +1,60 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    infra::repositories,
+    server::AppState,
+    utils::extractors::path::PathExtractor,
+};
+use super::error::UserError;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RevokeUserSessionsResponse {
+    pub code: i32,
+    /// Number of live sessions revoked
+    pub data: i64,
+    pub msg: Option<String>,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/{id}/sessions",
+    params(
+        ("id", Path, description = "User id"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Every session of the user revoked, their tokens are no longer accepted",
+            body = RevokeUserSessionsResponse,
+        ),
+        (status = NOT_FOUND, description = "User not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn revoke_user_sessions(
+    State(state): State<AppState>,
+    PathExtractor(user_id): PathExtractor<i32>,
+) -> Result<Json<RevokeUserSessionsResponse>, UserError> {
+    repositories::user::try_get_by_id(
+        &state.pg_pool, user_id
+    )
+        .await
+        .map_err(UserError::RepoError)?
+        .ok_or(UserError::NotFound)?;
+
+    let revoked = repositories::session::revoke_all_by_user_id(
+        &state.pg_pool, user_id
+    )
+        .await
+        .map_err(UserError::RepoError)?;
+    state.auth_cache.invalidate_user(user_id);
+
+    Ok(Json(RevokeUserSessionsResponse {
+        code: 0,
+        data: revoked as i64,
+        msg: None,
+    }))
+}
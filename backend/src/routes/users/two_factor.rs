@@ -0,0 +1,329 @@
+use axum::{extract::State, Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    domain::{
+        models::{totp_secret::TotpSecretModel, user::UserModel},
+        totp,
+    },
+    infra::repositories,
+    routes::auth::{token::hash_opaque_token, two_factor::verify_code},
+    server::AppState,
+    utils::extractors::{json::JsonExtractor, path::PathExtractor},
+};
+use super::error::UserError;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorStatusSchema {
+    /// Whether logins take a code on top of the password
+    pub enabled: bool,
+    /// Whether an enrollment awaits its first code
+    pub pending: bool,
+    /// Recovery codes not used yet
+    pub recovery_codes_left: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetTwoFactorResponse {
+    pub code: i32,
+    pub data: Option<TwoFactorStatusSchema>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorEnrollmentSchema {
+    /// Base32 encoded secret, for apps the URL can't be scanned into
+    pub secret: String,
+    /// `otpauth://` URL to show as a QR code
+    pub otpauth_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EnrollTwoFactorResponse {
+    pub code: i32,
+    pub data: Option<TwoFactorEnrollmentSchema>,
+    pub msg: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TwoFactorCodeRequest {
+    /// Code of the authenticator app, or a recovery code where accepted
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    pub code: i32,
+    /// Recovery codes, each accepted once instead of a code of the app.
+    /// They are only ever returned here.
+    pub data: Vec<String>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DisableTwoFactorResponse {
+    pub code: i32,
+    pub data: bool,
+    pub msg: Option<String>,
+}
+
+/// New recovery codes, along with the digests stored in their stead.
+fn new_recovery_codes() -> (Vec<String>, Vec<String>) {
+    let codes = totp::new_recovery_codes();
+    let code_hashes = codes
+        .iter()
+        .map(|code| hash_opaque_token(&totp::normalize_recovery_code(code)))
+        .collect();
+
+    (codes, code_hashes)
+}
+
+/// Enabled secret of the user, whose code is asked for before 2FA is
+/// changed so that a stolen session can't turn it off.
+async fn get_enabled_secret(state: &AppState, user_id: i32) -> Result<TotpSecretModel, UserError> {
+    repositories::totp_secret::try_get_by_user_id(&state.pg_pool, user_id)
+        .await
+        .map_err(UserError::RepoError)?
+        .filter(|secret| secret.is_enabled())
+        .ok_or(UserError::TwoFactorNotEnrolled)
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/me/2fa",
+    responses(
+        (status = 200, description = "2FA status query successfully", body = GetTwoFactorResponse),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn get_me_two_factor(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
+) -> Result<Json<GetTwoFactorResponse>, UserError> {
+    let secret = repositories::totp_secret::try_get_by_user_id(&state.pg_pool, user.id)
+        .await
+        .map_err(UserError::RepoError)?;
+
+    let enabled = secret.as_ref().is_some_and(|secret| secret.is_enabled());
+    let recovery_codes_left = match enabled {
+        true => repositories::recovery_code::count_unused_by_user_id(&state.pg_pool, user.id)
+            .await
+            .map_err(UserError::RepoError)?,
+        false => 0,
+    };
+
+    Ok(Json(GetTwoFactorResponse {
+        code: 0,
+        data: Some(TwoFactorStatusSchema {
+            enabled,
+            pending: secret.is_some() && !enabled,
+            recovery_codes_left,
+        }),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/me/2fa",
+    responses(
+        (
+            status = 200,
+            description = "Enrollment started, to be confirmed with a first code",
+            body = EnrollTwoFactorResponse,
+        ),
+        (status = BAD_REQUEST, description = "2FA is already enabled"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn enroll_me_two_factor(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
+) -> Result<Json<EnrollTwoFactorResponse>, UserError> {
+    let secret = repositories::totp_secret::try_get_by_user_id(&state.pg_pool, user.id)
+        .await
+        .map_err(UserError::RepoError)?;
+    if secret.is_some_and(|secret| secret.is_enabled()) {
+        return Err(UserError::TwoFactorAlreadyEnabled);
+    }
+
+    // Enrolling again replaces the secret of an unconfirmed enrollment.
+    let secret = repositories::totp_secret::enroll(&state.pg_pool, user.id, totp::new_secret())
+        .await
+        .map_err(UserError::RepoError)?;
+
+    let otpauth_url = totp::otpauth_url(&secret.secret, &state.totp_issuer, &user.username)
+        .ok_or_else(|| UserError::InternalServerError("failed to build the otpauth URL".to_owned()))?;
+
+    Ok(Json(EnrollTwoFactorResponse {
+        code: 0,
+        data: Some(TwoFactorEnrollmentSchema {
+            secret: secret.secret,
+            otpauth_url,
+        }),
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/me/2fa/confirm",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (
+            status = 200,
+            description = "2FA enabled, with the recovery codes",
+            body = RecoveryCodesResponse,
+        ),
+        (status = BAD_REQUEST, description = "No pending enrollment, or invalid code"),
+    )
+)]
+#[instrument(skip(state, request))]
+pub async fn confirm_me_two_factor(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
+    JsonExtractor(request): JsonExtractor<TwoFactorCodeRequest>,
+) -> Result<Json<RecoveryCodesResponse>, UserError> {
+    let secret = repositories::totp_secret::try_get_by_user_id(&state.pg_pool, user.id)
+        .await
+        .map_err(UserError::RepoError)?
+        .ok_or(UserError::TwoFactorNotEnrolled)?;
+    if secret.is_enabled() {
+        return Err(UserError::TwoFactorAlreadyEnabled);
+    }
+
+    // Only a code of the app proves it was set up, there are no recovery
+    // codes yet anyway.
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let step = totp::verify(&secret.secret, &request.code, now, secret.last_used_step)
+        .ok_or(UserError::InvalidTwoFactorCode)?;
+
+    let (codes, code_hashes) = new_recovery_codes();
+    repositories::totp_secret::try_enable(&state.pg_pool, user.id, step, code_hashes)
+        .await
+        .map_err(UserError::RepoError)?
+        .ok_or(UserError::InvalidTwoFactorCode)?;
+
+    tracing::info!("User {} enabled 2FA", user.id);
+
+    Ok(Json(RecoveryCodesResponse {
+        code: 0,
+        data: codes,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/users/me/2fa/recovery-codes",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (
+            status = 200,
+            description = "Recovery codes replaced, the former ones no longer work",
+            body = RecoveryCodesResponse,
+        ),
+        (status = BAD_REQUEST, description = "2FA is not enabled, or invalid code"),
+    )
+)]
+#[instrument(skip(state, request))]
+pub async fn regenerate_me_recovery_codes(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
+    JsonExtractor(request): JsonExtractor<TwoFactorCodeRequest>,
+) -> Result<Json<RecoveryCodesResponse>, UserError> {
+    let secret = get_enabled_secret(&state, user.id).await?;
+    let verified = verify_code(&state, &secret, &request.code)
+        .await
+        .map_err(UserError::RepoError)?;
+    if !verified {
+        return Err(UserError::InvalidTwoFactorCode);
+    }
+
+    let (codes, code_hashes) = new_recovery_codes();
+    repositories::recovery_code::replace_by_user_id(&state.pg_pool, user.id, code_hashes)
+        .await
+        .map_err(UserError::RepoError)?;
+
+    Ok(Json(RecoveryCodesResponse {
+        code: 0,
+        data: codes,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/me/2fa",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "2FA disabled", body = DisableTwoFactorResponse),
+        (status = BAD_REQUEST, description = "2FA is not enabled, or invalid code"),
+    )
+)]
+#[instrument(skip(state, request))]
+pub async fn disable_me_two_factor(
+    State(state): State<AppState>,
+    Extension(user): Extension<UserModel>,
+    JsonExtractor(request): JsonExtractor<TwoFactorCodeRequest>,
+) -> Result<Json<DisableTwoFactorResponse>, UserError> {
+    let secret = get_enabled_secret(&state, user.id).await?;
+    let verified = verify_code(&state, &secret, &request.code)
+        .await
+        .map_err(UserError::RepoError)?;
+    if !verified {
+        return Err(UserError::InvalidTwoFactorCode);
+    }
+
+    repositories::totp_secret::delete_by_user_id(&state.pg_pool, user.id)
+        .await
+        .map_err(UserError::RepoError)?;
+
+    tracing::info!("User {} disabled 2FA", user.id);
+
+    Ok(Json(DisableTwoFactorResponse {
+        code: 0,
+        data: true,
+        msg: None,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/users/{id}/2fa",
+    params(
+        ("id", Path, description = "User id"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "2FA of the user reset, e.g. after they lost their device and recovery codes",
+            body = DisableTwoFactorResponse,
+        ),
+        (status = NOT_FOUND, description = "User not found"),
+    )
+)]
+#[instrument(skip(state))]
+pub async fn reset_user_two_factor(
+    State(state): State<AppState>,
+    PathExtractor(user_id): PathExtractor<i32>,
+) -> Result<Json<DisableTwoFactorResponse>, UserError> {
+    repositories::user::try_get_by_id(
+        &state.pg_pool, user_id
+    )
+        .await
+        .map_err(UserError::RepoError)?
+        .ok_or(UserError::NotFound)?;
+
+    let deleted = repositories::totp_secret::delete_by_user_id(&state.pg_pool, user_id)
+        .await
+        .map_err(UserError::RepoError)?;
+
+    Ok(Json(DisableTwoFactorResponse {
+        code: 0,
+        data: deleted > 0,
+        msg: None,
+    }))
+}
@@ -14,15 +14,26 @@ use crate::{
         path::PathExtractor,
     },
 };
-use super::{error::UserError, schema::UserSchema};
+use super::{create::check_password, error::UserError, schema::UserSchema};
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Deserialize, ToSchema)]
 pub struct UserUpdateRequest {
     pub password: Option<String>,
     pub nickname: Option<String>,
     pub avatar_uri: Option<String>,
 }
 
+// Keeps the password out of the logs of `update_user` and `update_me`.
+impl std::fmt::Debug for UserUpdateRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserUpdateRequest")
+            .field("password", &self.password.as_ref().map(|_| "[redacted]"))
+            .field("nickname", &self.nickname)
+            .field("avatar_uri", &self.avatar_uri)
+            .finish()
+    }
+}
+
 impl TryInto<UpdatedUserDB> for UserUpdateRequest {
     type Error = UserError;
 
@@ -81,12 +92,18 @@ pub async fn update_user(
         .map_err(UserError::RepoError)?
         .ok_or(UserError::NotFound)?;
 
+    if let Some(password) = &updated_user.password {
+        check_password(&state, password)?;
+    }
+
     let user = repositories::user::update_by_id(
         &state.pg_pool, user_id, updated_user.try_into()?
     )
         .await
         .map_err(UserError::RepoError)?;
 
+    state.auth_cache.invalidate_user(user_id);
+
     Ok(Json(UserUpdateResponse {
         code: 0,
         data: Some(UserSchema::from(user)),
@@ -119,12 +136,18 @@ pub async fn update_me(
         .map_err(UserError::RepoError)?
         .ok_or(UserError::NotFound)?;
 
+    if let Some(password) = &updated_user.password {
+        check_password(&state, password)?;
+    }
+
     let user = repositories::user::update_by_id(
         &state.pg_pool, user.id, updated_user.try_into()?
     )
         .await
         .map_err(UserError::RepoError)?;
 
+    state.auth_cache.invalidate_user(user.id);
+
     Ok(Json(UserUpdateResponse {
         code: 0,
         data: Some(UserSchema::from(user)),
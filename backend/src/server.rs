@@ -1,6 +1,6 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
-use axum::{Router, http, routing::{post, get}, response::IntoResponse};
+use axum::{Router, http::{self, HeaderName}, routing::{post, get}, response::IntoResponse};
 use axum_tracing_opentelemetry::middleware::OtelAxumLayer;
 use deadpool_diesel::postgres::{Manager, Pool};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
@@ -9,10 +9,30 @@ use tracing::instrument;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+use crate::bootstrap::{self, PermissionRegistry};
+use crate::domain::{lockout::LockoutPolicy, password::PasswordPolicy};
+use crate::infra::{
+    auth_cache::AuthCache,
+    jwt_keys::JwtKeys,
+    ldap::LdapConfig,
+    mailer::{self, Mailer, MailerConfig},
+    oidc::{OidcClient, OidcConfig},
+};
+use crate::middlewares::audit::{self, AuditLayer};
 use crate::routes::{
-    auth::{login::login, logout::logout},
+    audit::audit_routes,
+    auth::{
+        authenticator::{authenticators, AuthBackend, Authenticator},
+        jwks::jwks,
+        login::login,
+        logout::logout,
+        oidc::{oidc_callback, oidc_login},
+        refresh::refresh,
+        two_factor::login_two_factor,
+    },
     datasets::datasets_routes,
     groups::groups_routes,
+    lockouts::lockouts_routes,
     permissions::permissions_routes,
     users::users_routes,
 };
@@ -22,7 +42,39 @@ pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/");
 #[derive(Clone)]
 pub struct AppState {
     pub pg_pool: Pool,
+    /// Keys what only this server reads, such as 2FA challenges
     pub jwt_secret: String,
+    /// Keys the body digests of the audit log, derived from `jwt_secret`
+    pub audit_key: Arc<[u8]>,
+    /// Keys of the access tokens, which other services may verify
+    pub jwt_keys: Arc<JwtKeys>,
+    /// Users and permissions read by `AuthLayer`, to be invalidated by
+    /// handlers changing them
+    pub auth_cache: Arc<AuthCache>,
+    /// Check permissions against the claims of access tokens instead of the
+    /// database
+    pub trust_token_claims: bool,
+    /// Checks the credentials given to `/login`, in order
+    pub authenticators: Arc<[Box<dyn Authenticator>]>,
+    /// Set when users may log in through an OpenID Connect provider
+    pub oidc: Option<OidcClient>,
+    /// Permissions required by the routes, reconciled with the database
+    /// once the router is built
+    pub permission_registry: Arc<PermissionRegistry>,
+    /// Set when the server can send emails
+    pub mailer: Option<Arc<dyn Mailer>>,
+    /// Whether users may register themselves, which takes a mailer
+    pub allow_registration: bool,
+    /// URL the API is reached at, for links sent by email
+    pub public_url: String,
+    /// Requirements of the passwords users set
+    pub password_policy: Arc<PasswordPolicy>,
+    /// Throttling of failed logins
+    pub lockout_policy: LockoutPolicy,
+    /// Header the reverse proxy sets to the address of the client
+    pub client_ip_header: Option<HeaderName>,
+    /// Issuer authenticator apps list the accounts enrolled in 2FA under
+    pub totp_issuer: String,
 }
 
 #[instrument]
@@ -47,11 +99,39 @@ async fn ping() -> &'static str {
     "OK"
 }
 
+/// How users log in and how their requests are authorized.
+pub struct AuthConfig {
+    pub jwt_secret: String,
+    /// Keys of the access tokens, the secret unless keys were loaded
+    pub jwt_keys: JwtKeys,
+    /// Authenticators checking the credentials given to `/login`, in order
+    pub backends: Vec<AuthBackend>,
+    pub ldap: Option<LdapConfig>,
+    pub oidc: Option<OidcConfig>,
+    /// How long sessions, API keys, users and their permissions are cached,
+    /// zero disables the cache
+    pub cache_ttl: Duration,
+    pub trust_token_claims: bool,
+    /// Let users register themselves, pending email verification and the
+    /// approval of an admin
+    pub allow_registration: bool,
+    /// URL the API is reached at, for links sent by email
+    pub public_url: String,
+    pub password_policy: PasswordPolicy,
+    pub lockout_policy: LockoutPolicy,
+    /// Header the reverse proxy sets to the address of the client, the peer
+    /// of the connection is used when unset
+    pub client_ip_header: Option<HeaderName>,
+    /// Issuer authenticator apps list the accounts enrolled in 2FA under
+    pub totp_issuer: String,
+}
+
 pub async fn run(
     addr: SocketAddr,
     allow_origin: Option<AllowOrigin>,
     database_url: String,
-    jwt_secret: String
+    auth: AuthConfig,
+    mailer: Option<MailerConfig>,
 ) -> Result<(), axum::BoxError> {
     #[derive(OpenApi)]
     #[openapi(
@@ -59,20 +139,47 @@ pub async fn run(
             ping,
             // login
             crate::routes::auth::login::login,
+            crate::routes::auth::two_factor::login_two_factor,
             // logout
             crate::routes::auth::logout::logout,
+            // jwks
+            crate::routes::auth::jwks::jwks,
+            // refresh
+            crate::routes::auth::refresh::refresh,
+            // auth/oidc
+            crate::routes::auth::oidc::oidc_login,
+            crate::routes::auth::oidc::oidc_callback,
             // datasets
             crate::routes::datasets::create::create_dataset,
             crate::routes::datasets::get::get_dataset,
             crate::routes::datasets::list::list_datasets,
             crate::routes::datasets::update::update_dataset,
             crate::routes::datasets::delete::delete_dataset,
+            crate::routes::datasets::item_rel::attach_dataset_items,
+            crate::routes::datasets::item_rel::detach_dataset_items,
+            crate::routes::datasets::import::import_dataset_items,
+            crate::routes::datasets::export::export_dataset,
+            crate::routes::datasets::version::create_dataset_version,
+            crate::routes::datasets::version::list_dataset_versions,
+            crate::routes::datasets::version::get_dataset_version,
+            crate::routes::datasets::diff::diff_dataset,
+            crate::routes::datasets::acl::grant_dataset_acl,
+            crate::routes::datasets::acl::list_dataset_acls,
+            crate::routes::datasets::acl::revoke_dataset_acl,
+            crate::routes::datasets::shard_rel::attach_dataset_shards,
+            crate::routes::datasets::shard_rel::detach_dataset_shards,
             // datasets/items
             crate::routes::datasets::items::create::create_dataset_item,
             crate::routes::datasets::items::get::get_dataset_item,
             crate::routes::datasets::items::list::list_dataset_items,
             crate::routes::datasets::items::update::update_dataset_item,
             crate::routes::datasets::items::delete::delete_dataset_item,
+            // datasets/items/annos
+            crate::routes::datasets::items::annos::create::create_dataset_item_anno,
+            crate::routes::datasets::items::annos::get::get_dataset_item_anno,
+            crate::routes::datasets::items::annos::list::list_dataset_item_annos,
+            crate::routes::datasets::items::annos::update::update_dataset_item_anno,
+            crate::routes::datasets::items::annos::delete::delete_dataset_item_anno,
             // datasets/shards
             crate::routes::datasets::shards::create::create_dataset_shard,
             crate::routes::datasets::shards::get::get_dataset_shard,
@@ -84,6 +191,19 @@ pub async fn run(
             crate::routes::groups::get::get_group,
             crate::routes::groups::list::list_groups,
             crate::routes::groups::delete::delete_group,
+            // groups/users
+            crate::routes::groups::user::get_group_users,
+            crate::routes::groups::user::add_group_user,
+            crate::routes::groups::user::remove_group_user,
+            // groups/permissions
+            crate::routes::groups::permission::get_group_permissions,
+            crate::routes::groups::permission::add_group_permission,
+            crate::routes::groups::permission::remove_group_permission,
+            // audit
+            crate::routes::audit::list::list_audit_logs,
+            // lockouts
+            crate::routes::lockouts::list::list_lockouts,
+            crate::routes::lockouts::delete::delete_lockout,
             // permissions
             crate::routes::permissions::create::create_permission,
             crate::routes::permissions::get::get_permission,
@@ -98,20 +218,46 @@ pub async fn run(
             crate::routes::users::update::update_me,
             crate::routes::users::delete::delete_user,
             crate::routes::users::activate::activate_user,
+            // users/registrations
+            crate::routes::users::registration::register,
+            crate::routes::users::registration::verify_registration,
+            crate::routes::users::registration::resend_verification,
+            crate::routes::users::registration::list_registrations,
+            crate::routes::users::registration::approve_registration,
+            crate::routes::users::registration::reject_registration,
+            // users/password
+            crate::routes::users::password::forgot_password,
+            crate::routes::users::password::reset_password,
             // users/groups
             crate::routes::users::group::get_user_groups,
             crate::routes::users::group::get_me_groups,
             // users/permissions
             crate::routes::users::permission::get_user_permissions,
             crate::routes::users::permission::get_me_permissions,
+            // users/sessions
+            crate::routes::users::session::revoke_user_sessions,
+            // users/2fa
+            crate::routes::users::two_factor::get_me_two_factor,
+            crate::routes::users::two_factor::enroll_me_two_factor,
+            crate::routes::users::two_factor::confirm_me_two_factor,
+            crate::routes::users::two_factor::regenerate_me_recovery_codes,
+            crate::routes::users::two_factor::disable_me_two_factor,
+            crate::routes::users::two_factor::reset_user_two_factor,
+            // users/api-keys
+            crate::routes::users::api_key::create_me_api_key,
+            crate::routes::users::api_key::list_me_api_keys,
+            crate::routes::users::api_key::delete_me_api_key,
         ),
         components(
             schemas(
                 // login
                 crate::routes::auth::login::LoginRequest,
                 crate::routes::auth::login::LoginResponse,
+                crate::routes::auth::two_factor::LoginTwoFactorRequest,
                 // logout
                 crate::routes::auth::logout::LogoutResponse,
+                // refresh
+                crate::routes::auth::refresh::RefreshRequest,
                 // datasets
                 crate::routes::datasets::schema::DatasetSchema,
                 crate::routes::datasets::create::DatasetCreationRequest,
@@ -121,6 +267,36 @@ pub async fn run(
                 crate::routes::datasets::update::DatasetUpdateRequest,
                 crate::routes::datasets::update::DatasetUpdateResponse,
                 crate::routes::datasets::delete::DeleteDatasetResponse,
+                crate::routes::datasets::item_rel::DatasetItemsRelRequest,
+                crate::routes::datasets::item_rel::DatasetItemsRelResponse,
+                crate::routes::datasets::import::ImportFormat,
+                crate::routes::datasets::import::ImportItemRecord,
+                crate::routes::datasets::import::ImportItemAnnoRecord,
+                crate::routes::datasets::import::ImportLineError,
+                crate::routes::datasets::import::ImportReport,
+                crate::routes::datasets::import::ImportDatasetItemsResponse,
+                crate::routes::datasets::export::ExportFormat,
+                crate::routes::datasets::export::ExportItemRecord,
+                crate::routes::datasets::export::ExportItemAnnoRecord,
+                crate::routes::datasets::schema::DatasetVersionSchema,
+                crate::routes::datasets::version::DatasetVersionCreationRequest,
+                crate::routes::datasets::version::DatasetVersionResponse,
+                crate::routes::datasets::version::ListDatasetVersionsResponse,
+                crate::routes::datasets::schema::DatasetDiffKindSchema,
+                crate::routes::datasets::schema::DatasetDiffChangeSchema,
+                crate::routes::datasets::schema::DatasetItemAnnoValueSchema,
+                crate::routes::datasets::schema::DatasetItemAnnoDiffSchema,
+                crate::routes::datasets::schema::DatasetDiffSchema,
+                crate::routes::datasets::diff::DatasetDiffFormat,
+                crate::routes::datasets::diff::DatasetDiffResponse,
+                crate::routes::datasets::schema::DatasetRoleSchema,
+                crate::routes::datasets::schema::DatasetAclSchema,
+                crate::routes::datasets::acl::DatasetAclGrantRequest,
+                crate::routes::datasets::acl::DatasetAclResponse,
+                crate::routes::datasets::acl::ListDatasetAclsResponse,
+                crate::routes::datasets::acl::DeleteDatasetAclResponse,
+                crate::routes::datasets::shard_rel::DatasetShardsRelRequest,
+                crate::routes::datasets::shard_rel::DatasetShardsRelResponse,
                 // datasets/items
                 crate::routes::datasets::items::schema::DatasetItemSchema,
                 crate::routes::datasets::items::create::DatasetItemCreationRequest,
@@ -130,6 +306,15 @@ pub async fn run(
                 crate::routes::datasets::items::update::DatasetItemUpdateRequest,
                 crate::routes::datasets::items::update::DatasetItemUpdateResponse,
                 crate::routes::datasets::items::delete::DeleteDatasetItemResponse,
+                // datasets/items/annos
+                crate::routes::datasets::items::annos::schema::DatasetItemAnnoSchema,
+                crate::routes::datasets::items::annos::create::DatasetItemAnnoCreationRequest,
+                crate::routes::datasets::items::annos::create::DatasetItemAnnoCreationResponse,
+                crate::routes::datasets::items::annos::get::GetDatasetItemAnnoResponse,
+                crate::routes::datasets::items::annos::list::ListDatasetItemAnnosResponse,
+                crate::routes::datasets::items::annos::update::DatasetItemAnnoUpdateRequest,
+                crate::routes::datasets::items::annos::update::DatasetItemAnnoUpdateResponse,
+                crate::routes::datasets::items::annos::delete::DeleteDatasetItemAnnoResponse,
                 // datasets/shards
                 crate::routes::datasets::shards::schema::DatasetShardSchema,
                 crate::routes::datasets::shards::create::DatasetShardCreationRequest,
@@ -146,6 +331,19 @@ pub async fn run(
                 crate::routes::groups::get::GetGroupResponse,
                 crate::routes::groups::list::ListGroupsResponse,
                 crate::routes::groups::delete::DeleteGroupResponse,
+                // groups/users
+                crate::routes::groups::user::GetGroupUsersResponse,
+                crate::routes::groups::user::GroupUserResponse,
+                // groups/permissions
+                crate::routes::groups::permission::GetGroupPermissionsResponse,
+                crate::routes::groups::permission::GroupPermissionResponse,
+                // audit
+                crate::routes::audit::schema::AuditLogSchema,
+                crate::routes::audit::list::ListAuditLogsResponse,
+                // lockouts
+                crate::routes::lockouts::schema::LockoutSchema,
+                crate::routes::lockouts::list::ListLockoutsResponse,
+                crate::routes::lockouts::delete::DeleteLockoutResponse,
                 // permissions
                 crate::routes::permissions::schema::PermissionSchema,
                 crate::routes::permissions::create::PermissionCreationRequest,
@@ -163,10 +361,40 @@ pub async fn run(
                 crate::routes::users::update::UserUpdateResponse,
                 crate::routes::users::delete::DeleteUserResponse,
                 crate::routes::users::activate::ActivateUserResponse,
+                // users/registrations
+                crate::routes::users::schema::RegistrationSchema,
+                crate::routes::users::registration::RegistrationRequest,
+                crate::routes::users::registration::RegisterResponse,
+                crate::routes::users::registration::RegistrationResponse,
+                crate::routes::users::registration::ResendVerificationRequest,
+                crate::routes::users::registration::ResendVerificationResponse,
+                crate::routes::users::registration::ListRegistrationsResponse,
+                crate::routes::users::registration::ReviewRegistrationRequest,
+                // users/password
+                crate::routes::users::password::ForgotPasswordRequest,
+                crate::routes::users::password::ForgotPasswordResponse,
+                crate::routes::users::password::ResetPasswordRequest,
+                crate::routes::users::password::ResetPasswordResponse,
                 // users/groups
                 crate::routes::users::group::GetUserGroupsResponse,
                 // users/permissions
                 crate::routes::users::permission::GetUserPermissionsResponse,
+                // users/sessions
+                crate::routes::users::session::RevokeUserSessionsResponse,
+                // users/2fa
+                crate::routes::users::two_factor::TwoFactorStatusSchema,
+                crate::routes::users::two_factor::GetTwoFactorResponse,
+                crate::routes::users::two_factor::TwoFactorEnrollmentSchema,
+                crate::routes::users::two_factor::EnrollTwoFactorResponse,
+                crate::routes::users::two_factor::TwoFactorCodeRequest,
+                crate::routes::users::two_factor::RecoveryCodesResponse,
+                crate::routes::users::two_factor::DisableTwoFactorResponse,
+                // users/api-keys
+                crate::routes::users::schema::ApiKeySchema,
+                crate::routes::users::api_key::ApiKeyCreationRequest,
+                crate::routes::users::api_key::ApiKeyCreationResponse,
+                crate::routes::users::api_key::ListApiKeysResponse,
+                crate::routes::users::api_key::DeleteApiKeyResponse,
             ),
         ),
         tags(
@@ -191,6 +419,12 @@ pub async fn run(
         .allow_credentials(true)
         .allow_origin(allow_origin);
 
+    let authenticators = authenticators(&auth.backends, auth.ldap)?;
+    let mailer = mailer.map(mailer::mailer).transpose()?;
+    if auth.allow_registration && mailer.is_none() {
+        return Err("registration needs a mailer to send verification links".into());
+    }
+
     let manager = Manager::new(
         database_url, deadpool_diesel::Runtime::Tokio1
     );
@@ -198,35 +432,68 @@ pub async fn run(
     run_migrations(&pg_pool).await;
 
     let state = AppState {
-        pg_pool, jwt_secret
+        pg_pool,
+        audit_key: audit::digest_key(&auth.jwt_secret).into(),
+        jwt_secret: auth.jwt_secret,
+        jwt_keys: Arc::new(auth.jwt_keys),
+        auth_cache: Arc::new(AuthCache::new(auth.cache_ttl)),
+        trust_token_claims: auth.trust_token_claims,
+        authenticators: authenticators.into(),
+        oidc: auth.oidc.map(OidcClient::new),
+        permission_registry: Arc::new(PermissionRegistry::default()),
+        mailer,
+        allow_registration: auth.allow_registration,
+        public_url: auth.public_url,
+        password_policy: Arc::new(auth.password_policy),
+        lockout_policy: auth.lockout_policy,
+        client_ip_header: auth.client_ip_header,
+        totp_issuer: auth.totp_issuer,
     };
 
     let router = Router::new()
+        .nest("/v1/audit", audit_routes(state.clone()))
         .nest("/v1/datasets", datasets_routes(state.clone()))
         .nest("/v1/groups", groups_routes(state.clone()))
+        .nest("/v1/lockouts", lockouts_routes(state.clone()))
         .nest("/v1/permissions", permissions_routes(state.clone()))
         .nest("/v1/users", users_routes(state.clone()))
         .route("/login", post(login))
+        .route("/login/2fa", post(login_two_factor))
         .route("/logout", get(logout))
+        .route("/refresh", post(refresh))
+        .route("/auth/oidc/login", get(oidc_login))
+        .route("/auth/oidc/callback", get(oidc_callback))
+        .route("/.well-known/jwks.json", get(jwks))
+        .route("/ping", get(ping))
         .merge(
             SwaggerUi::new("/docs")
                 .url("/api-doc/openapi.json", ApiDoc::openapi()),
         )
         .fallback(not_found)
+        .layer(AuditLayer::new(state.clone()))
         .layer(cors_layer)
         .layer(OtelAxumLayer::default())
-        .with_state(state);
+        .with_state(state.clone());
+
+    let added = bootstrap::reconcile_permissions(
+        &state.pg_pool, &state.permission_registry
+    )
+        .await?;
+    tracing::info!("Added {} missing permissions", added);
+
+    #[cfg(unix)]
+    tokio::spawn(reload_jwt_keys_on_hangup(state.jwt_keys.clone()));
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
     tracing::info!("Listening on {}", addr);
-    axum::serve(listener, router)
+    axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(shutdown_signal())
         .await?;
 
     Ok(())
 }
 
-async fn run_migrations(pool: &Pool) {
+pub async fn run_migrations(pool: &Pool) {
     let conn = pool.get().await.unwrap();
     conn.interact(|conn| conn.run_pending_migrations(MIGRATIONS).map(|_| ()))
         .await
@@ -234,6 +501,22 @@ async fn run_migrations(pool: &Pool) {
         .unwrap();
 }
 
+/// Reloads the keys of the access tokens whenever the server gets `SIGHUP`,
+/// which rotates them without a restart.
+#[cfg(unix)]
+async fn reload_jwt_keys_on_hangup(jwt_keys: Arc<JwtKeys>) {
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .expect("failed to install SIGHUP handler");
+
+    while hangup.recv().await.is_some() {
+        match jwt_keys.reload() {
+            Ok(Some(kid)) => tracing::info!("Reloaded JWT keys, signing with {}", kid),
+            Ok(None) => tracing::info!("No JWT key directory to reload"),
+            Err(err) => tracing::error!("Failed to reload JWT keys, keeping the current ones: {}", err),
+        }
+    }
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
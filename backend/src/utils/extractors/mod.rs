@@ -1,2 +1,3 @@
+pub mod client_ip;
 pub mod json;
 pub mod path;
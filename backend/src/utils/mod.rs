@@ -1 +1,2 @@
 pub mod extractors;
+pub mod nullable;
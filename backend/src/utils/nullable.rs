@@ -0,0 +1,16 @@
+//! Nullable fields of update requests, which tell a value left alone from a
+//! value cleared.
+
+use serde::{Deserialize, Deserializer};
+
+/// Deserializes a nullable field as `None` when it is absent, which leaves
+/// the value alone, `Some(None)` when it is `null`, which clears it, and
+/// `Some(Some(_))` when it is set. Takes `#[serde(default)]` for the field
+/// to be optional.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
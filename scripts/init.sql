@@ -38,32 +38,24 @@ INSERT INTO permissions (name) VALUES ('permissions.create');
 INSERT INTO permissions (name) VALUES ('permissions.read');
 INSERT INTO permissions (name) VALUES ('permissions.delete');
 
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 17);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 18);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 19);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 20);
-
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 21);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 22);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 23);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 24);
-
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 25);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 26);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 27);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 28);
-
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 29);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 30);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 31);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 32);
-
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 33);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 34);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 35);
-
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 36);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 37);
-INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 38);
+INSERT INTO permissions (name) VALUES ('datasets.items.annos.create');
+INSERT INTO permissions (name) VALUES ('datasets.items.annos.read');
+INSERT INTO permissions (name) VALUES ('datasets.items.annos.update');
+INSERT INTO permissions (name) VALUES ('datasets.items.annos.delete');
+
+INSERT INTO permissions (name) VALUES ('groups.update');
+
+INSERT INTO permissions (name) VALUES ('datasets.items.import');
+
+INSERT INTO permissions (name) VALUES ('datasets.admin');
+
+INSERT INTO permissions (name) VALUES ('audit.read');
+
+INSERT INTO permissions (name) VALUES ('users.revoke_sessions');
+
+-- Holds every permission, present and future
+INSERT INTO permissions (name) VALUES ('*');
+
+INSERT INTO groups_permissions_rel (group_id, permission_id) VALUES (5, 48);
 
 INSERT INTO users_groups_rel (user_id, group_id) VALUES (1, 5);